@@ -320,6 +320,34 @@ impl<T: Clone + Default + Debug> ComputationGraph<T> {
         self
     }
 
+    /// Create node relationship with an input value and sample weights for the previous node.
+    /// Inputs of the operation are the previous node, the value and the weights (in that order).
+    pub fn weighted(
+        &mut self,
+        rhs: T,
+        weights: T,
+        op: Box<dyn Operation<T>>) -> &mut ComputationGraph<T> {
+
+        let lhs_idx = self.curr_node_idx as usize;
+
+        self.add_node(Node::val(rhs));
+        self.variables.push(self.curr_node_idx as usize);
+
+        self.add_node(Node::val(weights));
+        self.variables.push(self.curr_node_idx as usize);
+
+        let mut node = Node::binary(lhs_idx, lhs_idx + 1, op);
+        node.add_input(lhs_idx + 2);
+        self.add_node(node);
+        self.operations.push(self.curr_node_idx as usize);
+
+        for idx in lhs_idx..lhs_idx + 3 {
+            self.add_upstream_node(idx, vec![self.curr_node_idx as usize]);
+        }
+
+        self
+    }
+
     /// Create node that applies functions to previous node
     pub fn function(
         &mut self, 
@@ -815,7 +843,13 @@ mod graph_test {
             "Mul", "Sub", "Add", "DefaultValue", 
             "Tanh", "BinaryCrossEntropy", "CategoricalCrossEntropy", 
             "DefaultLossFunction",
-            "MSE", "Sigmoid"
+            "MSE", "Sigmoid",
+            "WeightedMSE", "WeightedBinaryCrossEntropy",
+            "ClassWeightedBinaryCrossEntropy", "BinaryFocalLoss",
+            "Huber", "Quantile", "LogCosh",
+            "Hinge", "SquaredHinge",
+            "Exp", "Identity", "Reciprocal",
+            "PoissonDeviance", "GammaDeviance", "TweedieDeviance"
         ];
 
         keys_vec.sort(); 
@@ -36,7 +36,9 @@ pub struct NodeSerialize<T> {
     pub inputs: Vec<usize>,
     pub upstream: Vec<usize>,
     pub value: Tensor<T>,
-    pub operation: String
+    pub operation: String,
+    #[serde(default)]
+    pub parameters: Vec<f64>
 }
 
 /// Trait for serializing and deserializing node structure
@@ -166,13 +168,23 @@ impl<T: Clone + Default> Node<T> {
             inputs: self.inputs.clone(),
             upstream: self.upstream.clone(),
             value: self.value.clone(),
-            operation: format!("{:?}", self.operation)
+            operation: operation_name(self.operation.as_ref()),
+            parameters: self.operation.parameters()
         }
 
     }
 
 }
 
+/// Registry key of an operation, the type name without any parameters from its debug output
+fn operation_name<T>(op: &dyn Operation<T>) -> String {
+    let name = format!("{:?}", op);
+    match name.find([' ', '(', '{']) {
+        Some(idx) => name[..idx].to_string(),
+        None => name
+    }
+}
+
 macro_rules! node_serialize {
 
     ($t:ty) => {
@@ -186,7 +198,8 @@ macro_rules! node_serialize {
                     inputs: self.inputs.clone(),
                     upstream: self.upstream.clone(),
                     value: self.value.clone(),
-                    operation: format!("{:?}", self.operation)
+                    operation: operation_name(self.operation.as_ref()),
+                    parameters: self.operation.parameters()
                 };
 
                 Ok(serde_json::to_string_pretty(&obj).unwrap())
@@ -208,7 +221,7 @@ macro_rules! node_serialize {
                             inputs: node.inputs,
                             upstream: node.upstream,
                             value: node.value,
-                            operation: op.clone(),
+                            operation: op.with_parameters(&node.parameters),
                         })
                     },
                     _ => panic!("Couldn't find matching behavior trait for {key}")
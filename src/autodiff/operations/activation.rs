@@ -19,6 +19,15 @@ pub trait ActivationFunction<T> {
     /// Tanh activation function 
     fn tanh(&mut self) -> &mut ComputationGraph<T>;
 
+    /// Exponential activation function (inverse of log link)
+    fn exp(&mut self) -> &mut ComputationGraph<T>;
+
+    /// Identity activation function (inverse of identity link)
+    fn identity(&mut self) -> &mut ComputationGraph<T>;
+
+    /// Reciprocal activation function (inverse of inverse link)
+    fn reciprocal(&mut self) -> &mut ComputationGraph<T>;
+
 }
 
 macro_rules! activation_funcs {
@@ -35,6 +44,18 @@ macro_rules! activation_funcs {
                 self.function(Box::new(Tanh))
             }
 
+            fn exp(&mut self) -> &mut ComputationGraph<$t> {
+                self.function(Box::new(Exp))
+            }
+
+            fn identity(&mut self) -> &mut ComputationGraph<$t> {
+                self.function(Box::new(Identity))
+            }
+
+            fn reciprocal(&mut self) -> &mut ComputationGraph<$t> {
+                self.function(Box::new(Reciprocal))
+            }
+
         }
     }
 
@@ -229,6 +250,200 @@ impl Operation<f64> for Tanh {
 }
 
 
+/// Multiply gradient of the single upstream node by the local derivative 
+/// of an element wise activation and pass it to the input
+fn chain_upstream(
+    nodes: &mut [Node<Array2<f64>>], 
+    curr_idx: usize,
+    name: &str,
+    derivative: Array2<f64>) {
+
+    let inputs = nodes[curr_idx].inputs();
+    let upstream = nodes[curr_idx].upstream();
+    if inputs.len() != 1 || upstream.len() != 1 {
+        panic!("{name} node must have 1 input and 1 upstream value"); 
+    }
+
+    let grad = nodes[upstream[0]].grad() * derivative;
+    nodes[curr_idx].set_grad_output(grad.clone());
+    nodes[inputs[0]].set_grad_output(grad);
+}
+
+
+/// Exponential activation, inverse of the log link function
+#[derive(Clone, Debug)]
+pub struct Exp;
+
+impl Operation<Array2<f64>> for Exp {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Exp activation on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        nodes[inputs[0]].output().mapv(f64::exp)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward exp on node index: {:?}",
+            curr_idx
+        );
+
+        let derivative = nodes[curr_idx].output();
+        chain_upstream(nodes, curr_idx, "Exp", derivative);
+    }
+}
+
+
+impl Operation<f64> for Exp {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        nodes[inputs[0]].output().exp()
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let upstream = nodes[curr_idx].upstream();
+        let grad = nodes[upstream[0]].grad() * nodes[curr_idx].output();
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+    }
+}
+
+
+/// Identity activation, inverse of the identity link function
+#[derive(Clone, Debug)]
+pub struct Identity;
+
+impl Operation<Array2<f64>> for Identity {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        let inputs = nodes[curr_idx].inputs();
+        nodes[inputs[0]].output()
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        let derivative = Array2::ones(nodes[curr_idx].output().raw_dim());
+        chain_upstream(nodes, curr_idx, "Identity", derivative);
+    }
+}
+
+
+impl Operation<f64> for Identity {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        nodes[inputs[0]].output()
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let upstream = nodes[curr_idx].upstream();
+        let grad = nodes[upstream[0]].grad();
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+    }
+}
+
+
+/// Reciprocal activation `1 / x`, inverse of the inverse link function
+#[derive(Clone, Debug)]
+pub struct Reciprocal;
+
+impl Operation<Array2<f64>> for Reciprocal {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Reciprocal activation on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        nodes[inputs[0]].output().mapv(|v| 1.0 / v)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward reciprocal on node index: {:?}",
+            curr_idx
+        );
+
+        let derivative = nodes[curr_idx].output().mapv(|r| -r * r);
+        chain_upstream(nodes, curr_idx, "Reciprocal", derivative);
+    }
+}
+
+
+impl Operation<f64> for Reciprocal {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        1.0 / nodes[inputs[0]].output()
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let upstream = nodes[curr_idx].upstream();
+        let r = nodes[curr_idx].output();
+        let grad = nodes[upstream[0]].grad() * -r * r;
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+    }
+}
+
+
 #[cfg(test)]
 mod activation_ops_test {
 
@@ -338,6 +553,46 @@ mod activation_ops_test {
 
     }
 
+    #[test]
+    fn test_link_activations() {
+
+        let eta = arr2(&[[0.0], [1.0], [2.0]]);
+        let b = arr2(&[[0.0]]);
+        let y = arr2(&[[1.0], [1.0], [1.0]]);
+
+        let mut graph = ComputationGraph::new();
+        graph.add(vec![eta.clone(), b.clone()]);
+        graph.exp();
+        graph.mse(y.clone());
+        graph.forward();
+        graph.backward();
+
+        let mu = eta.mapv(f64::exp);
+        assert_eq!(graph.node(3).output(), mu);
+        assert_eq!(graph.node(2).grad(), (&mu - &y) * &mu);
+
+        let mut graph = ComputationGraph::new();
+        graph.add(vec![eta.clone() + 1.0, b.clone()]);
+        graph.reciprocal();
+        graph.mse(y.clone());
+        graph.forward();
+        graph.backward();
+
+        let mu = eta.mapv(|v| 1.0 / (v + 1.0));
+        assert_eq!(graph.node(3).output(), mu);
+        assert_eq!(graph.node(2).grad(), (&mu - &y) * mu.mapv(|m| -m * m));
+
+        let mut graph = ComputationGraph::new();
+        graph.add(vec![eta.clone(), b]);
+        graph.identity();
+        graph.mse(y.clone());
+        graph.forward();
+        graph.backward();
+
+        assert_eq!(graph.node(3).output(), eta);
+        assert_eq!(graph.node(2).grad(), &eta - &y);
+    }
+
 }
 
 
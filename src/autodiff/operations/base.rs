@@ -15,6 +15,22 @@ pub trait Operation<T>: OperationClone<T> + Debug {
  
     fn backward(&self, nodes: &mut Vec<Node<T>>, curr_idx: usize);
 
+    /// Parameters of the operation that are serialized with the node
+    fn parameters(&self) -> Vec<f64> {
+        vec![]
+    }
+
+    /// Create operation of the same type from serialized parameters
+    fn with_parameters(&self, _parameters: &[f64]) -> Box<dyn Operation<T>> {
+        self.clone_box()
+    }
+
+    /// Whether the operation is a (weighted) squared error loss,
+    /// models with this loss can be fit with direct least squares solvers
+    fn is_squared_error(&self) -> bool {
+        false
+    }
+
 }
 
 pub trait OperationClone<T> {
@@ -17,17 +17,56 @@ pub trait LossFunction<T> {
     /// Mean absolute error
     fn mae(&mut self, val: T) -> &mut ComputationGraph<T>;
 
+    /// Huber loss with threshold delta between squared & absolute error
+    fn huber(&mut self, val: T, delta: f64) -> &mut ComputationGraph<T>;
+
+    /// Quantile (pinball) loss for quantile tau
+    fn quantile(&mut self, val: T, tau: f64) -> &mut ComputationGraph<T>;
+
+    /// Log-cosh loss
+    fn log_cosh(&mut self, val: T) -> &mut ComputationGraph<T>;
+
+    /// Hinge loss for labels of 0 or 1
+    fn hinge(&mut self, val: T) -> &mut ComputationGraph<T>;
+
+    /// Squared hinge loss for labels of 0 or 1
+    fn squared_hinge(&mut self, val: T) -> &mut ComputationGraph<T>;
+
+    /// Poisson deviance of predicted means
+    fn poisson_deviance(&mut self, val: T) -> &mut ComputationGraph<T>;
+
+    /// Gamma deviance of predicted means
+    fn gamma_deviance(&mut self, val: T) -> &mut ComputationGraph<T>;
+
+    /// Tweedie deviance of predicted means with variance power
+    fn tweedie_deviance(&mut self, val: T, power: f64) -> &mut ComputationGraph<T>;
+
     /// Binary cross entropy
     fn bce(&mut self, val: T) -> &mut ComputationGraph<T>;
 
     /// Categorical cross entropy
     fn cce(&mut self, val: T) -> &mut ComputationGraph<T>;
 
+    /// Mean squared error with sample weights
+    fn weighted_mse(&mut self, val: T, weights: T) -> &mut ComputationGraph<T>;
+
+    /// Binary cross entropy with sample weights
+    fn weighted_bce(&mut self, val: T, weights: T) -> &mut ComputationGraph<T>;
+
     /// Default function for no loss function provided
     fn default(&mut self) -> &mut ComputationGraph<T>;
 
 }
 
+
+/// Losses over rows of class scores (softmax across columns), only defined for matrices
+pub trait CategoricalLossFunction {
+
+    /// Categorical cross entropy with sample weights
+    fn weighted_cce(&mut self, val: Array2<f64>, weights: Array2<f64>) -> &mut ComputationGraph<Array2<f64>>;
+
+}
+
 macro_rules! loss_funcs {
 
     ($t:ty) => {
@@ -42,6 +81,38 @@ macro_rules! loss_funcs {
                 self.unary(val, Box::new(MAE))
             }
 
+            fn huber(&mut self, val: $t, delta: f64) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(Huber::new(delta)))
+            }
+
+            fn quantile(&mut self, val: $t, tau: f64) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(Quantile::new(tau)))
+            }
+
+            fn log_cosh(&mut self, val: $t) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(LogCosh))
+            }
+
+            fn hinge(&mut self, val: $t) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(Hinge))
+            }
+
+            fn squared_hinge(&mut self, val: $t) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(SquaredHinge))
+            }
+
+            fn poisson_deviance(&mut self, val: $t) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(PoissonDeviance))
+            }
+
+            fn gamma_deviance(&mut self, val: $t) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(GammaDeviance))
+            }
+
+            fn tweedie_deviance(&mut self, val: $t, power: f64) -> &mut ComputationGraph<$t> {
+                self.unary(val, Box::new(TweedieDeviance::new(power)))
+            }
+
             fn bce(&mut self, val: $t) -> &mut ComputationGraph<$t> {
                 self.unary(val, Box::new(BinaryCrossEntropy))
             }
@@ -50,6 +121,14 @@ macro_rules! loss_funcs {
                 self.unary(val, Box::new(CategoricalCrossEntropy))
             }
 
+            fn weighted_mse(&mut self, val: $t, weights: $t) -> &mut ComputationGraph<$t> {
+                self.weighted(val, weights, Box::new(WeightedMSE))
+            }
+
+            fn weighted_bce(&mut self, val: $t, weights: $t) -> &mut ComputationGraph<$t> {
+                self.weighted(val, weights, Box::new(WeightedBinaryCrossEntropy))
+            }
+
             fn default(&mut self) -> &mut ComputationGraph<$t> {
                 self.function(Box::new(DefaultLossFunction))
             }
@@ -63,6 +142,14 @@ loss_funcs!(f64);
 loss_funcs!(Array2<f64>); 
 
 
+impl CategoricalLossFunction for ComputationGraph<Array2<f64>> {
+
+    fn weighted_cce(&mut self, val: Array2<f64>, weights: Array2<f64>) -> &mut ComputationGraph<Array2<f64>> {
+        self.weighted(val, weights, Box::new(WeightedCategoricalCrossEntropy))
+    }
+}
+
+
 #[derive(Clone, Debug)]
 pub struct DefaultLossFunction;
 
@@ -212,6 +299,10 @@ impl Operation<Array2<f64>> for MSE {
         ); 
 
     }
+
+    fn is_squared_error(&self) -> bool {
+        true
+    }
 }
 
 
@@ -578,74 +669,1928 @@ impl Operation<f64> for CategoricalCrossEntropy {
 }
 
 
-#[cfg(test)]
-mod loss_ops_test {
+/// Sample weights of a weighted loss function as a column rescaled to a mean of 1,
+/// so uniform weights give the same loss & gradients as the unweighted operation
+fn normalized_weights(weights: &Array2<f64>, rows: usize) -> Array2<f64> {
 
-    use crate::autodiff::graph::*;
-    use crate::autodiff::operations::arithmetic::*; 
-    use crate::autodiff::operations::loss::*; 
-    use ndarray::{arr2};
+    if weights.len() != rows {
+        panic!(
+            "Sample weights of length {:?} do not match number of rows {:?}",
+            weights.len(), rows
+        );
+    }
 
+    let total = weights.sum();
+    if total <= 0.0 {
+        panic!("Sample weights must have a positive sum");
+    }
 
-    #[test]
-    fn test_mse() {
+    let column = weights.to_shape((rows, 1)).unwrap().to_owned();
+    column * (rows as f64 / total)
+}
 
-        let a = arr2(&[[1.0], [2.0], [3.0]]); 
-        let b = arr2(&[[1.0], [2.0], [3.0]]); 
-        let c = arr2(&[[1.0], [1.0], [1.0]]); 
 
-        let mut graph = ComputationGraph::new();
-        graph.add(vec![a, b]);
-        graph.mse(c);
+/// Row wise softmax of logits
+fn softmax(logits: &Array2<f64>) -> Array2<f64> {
 
-        assert_eq!(graph.nodes().len(), 5);
+    let samples: Vec<_> = logits
+        .axis_iter(Axis(0))
+        .map(|row| {
+            let max = row.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+            let exp = row.mapv(|x| (x - max).exp());
+            let sum = exp.sum();
+            exp.mapv(|x| x / sum)
+        })
+        .collect();
 
-        assert_eq!(graph.node(0).inputs().len(), 0); 
-        assert_eq!(graph.node(0).output().shape(), vec![3, 1]); 
+    let views: Vec<_> = samples.iter().map(|r| r.view()).collect();
+    stack(Axis(0), &views).unwrap()
+}
 
-        assert_eq!(graph.node(1).inputs().len(), 0); 
-        assert_eq!(graph.node(1).output().shape(), vec![3, 1]); 
 
-        assert_eq!(graph.node(2).inputs().len(), 2); 
-        assert_eq!(graph.node(2).output().shape(), vec![0, 0]); 
+/// Mean squared error where each row (sample) is scaled by a weight
+#[derive(Clone, Debug)]
+pub struct WeightedMSE;
 
-        graph.forward();
+impl Operation<Array2<f64>> for WeightedMSE {
 
-        assert_eq!(graph.node(2).output().shape(), vec![3, 1]); 
-        assert_eq!(
-            graph.node(2).output(), 
-            arr2(&[[2.0],[4.0],[6.0]])
-        );
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
 
-        graph.backward();
+        debug!(
+            "Performing forward weighted MSE on node index: {:?}",
+            curr_idx
+        ); 
 
-        assert_eq!(
-            graph.node(2).grad(), 
-            arr2(&[[1.0],[3.0],[5.0]])
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        let weights = normalized_weights(&nodes[inputs[2]].output(), y_true.nrows());
+
+        let squared = (y_true.clone() - y_pred).mapv(|x| x * x);
+        let val = (squared * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), val) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward weighted MSE on node index: {:?}",
+            curr_idx
         );
 
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = normalized_weights(&nodes[inputs[2]].output(), y_true.nrows());
+
+        let grad = (y_pred - y_true) * weights;
+        nodes[curr_idx].set_grad_output(grad.clone());
+        nodes[inputs[0]].set_grad_output(grad.clone());
+        nodes[inputs[1]].set_grad_output(grad);
+
+        debug!(
+            "Updated gradients for node input indexes: {:?}",
+            inputs
+        ); 
+
     }
 
-    #[test]
-    fn test_mae() {
+    fn is_squared_error(&self) -> bool {
+        true
+    }
+}
 
-        let a = arr2(&[[5.0], [10.0], [6.0],[9.0],[10.0]]); 
-        let b = arr2(&[[5.0], [5.0], [6.0],[9.0],[10.0]]); 
-        let c = arr2(&[[12.0], [15.0], [10.0], [20.0], [18.0]]); 
 
-        let mut graph = ComputationGraph::new();
-        graph.add(vec![a, b]);
-        graph.mae(c);
+impl Operation<f64> for WeightedMSE {
 
-        graph.forward();
-        graph.backward();
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
 
-        assert_eq!(graph.curr_node().output().as_slice().unwrap()[0], 1.6);
-        assert_eq!(
-            graph.curr_node().grad(), 
-            arr2(&[[-0.2],[0.2],[0.2],[-0.2],[0.2]])
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        let weight = nodes[inputs[2]].output();
+        weight * (y_true - y_pred).powf(2.0) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weight = nodes[inputs[2]].output();
+        let grad = weight * (y_pred - y_true);
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+}
+
+
+/// Binary cross entropy where each row (sample) is scaled by a weight
+#[derive(Clone, Debug)]
+pub struct WeightedBinaryCrossEntropy;
+
+impl Operation<Array2<f64>> for WeightedBinaryCrossEntropy {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward weighted BCE on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        if y_pred.shape() != y_true.shape() {
+            panic!(
+                "Value shapes for weighted binary cross entropy not equal {:?} != {:?}",
+                y_pred.shape(), y_true.shape()
+            );
+        }
+
+        let weights = normalized_weights(&nodes[inputs[2]].output(), y_true.nrows());
+        let losses = ndarray::Zip::from(&y_true)
+            .and(&y_pred)
+            .map_collect(|&y, &p| binary_cross_entropy(y, p));
+
+        let result = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), result) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward weighted BCE on node index: {:?}",
+            curr_idx
         );
 
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = normalized_weights(&nodes[inputs[2]].output(), y_true.nrows());
+
+        let grad = ndarray::Zip::from(&y_true)
+            .and(&y_pred)
+            .map_collect(|&y, &p| binary_cross_entropy_grad(y, p));
+
+        nodes[curr_idx].set_grad_output(grad * weights); 
+
+        debug!(
+            "Updated gradients for node input indexes: {:?}",
+            inputs
+        ); 
+
+    }
+}
+
+
+impl Operation<f64> for WeightedBinaryCrossEntropy {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        let weight = nodes[inputs[2]].output();
+        weight * binary_cross_entropy(y_true, y_pred)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weight = nodes[inputs[2]].output();
+        let grad = weight * binary_cross_entropy_grad(y_true, y_pred);
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+}
+
+
+/// Binary cross entropy of a single label & predicted probability
+fn binary_cross_entropy(y: f64, p: f64) -> f64 {
+    -(y * p.ln() + (1.0 - y) * (1.0 - p).ln())
+}
+
+
+/// Derivative of binary cross entropy with respect to the (clamped) predicted probability
+fn binary_cross_entropy_grad(y: f64, p: f64) -> f64 {
+    let epsilon = 1e-7;
+    let p = p.clamp(epsilon, 1.0 - epsilon);
+    -(y / p) + (1.0 - y) / (1.0 - p)
+}
+
+
+/// Categorical cross entropy (with softmax of logits) where each row (sample) is scaled by a weight
+#[derive(Clone, Debug)]
+pub struct WeightedCategoricalCrossEntropy;
+
+impl Operation<Array2<f64>> for WeightedCategoricalCrossEntropy {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!("[Weighted CCE]: Performing forward on node: {:?}", curr_idx);
+
+        let inputs = nodes[curr_idx].inputs();
+        let logits = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = normalized_weights(&nodes[inputs[2]].output(), y_true.nrows());
+
+        let losses = -(y_true.clone() * softmax(&logits).mapv(f64::ln));
+        let total_loss = (losses * weights).sum() / y_true.nrows() as f64;
+        Array2::from_elem((1, 1), total_loss)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!("[Weighted CCE]: Backward pass on node {:?}", curr_idx); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let logits = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+
+        if logits.shape() != y_true.shape() {
+            panic!("Value shapes for weighted categorical cross entropy not equal");
+        } 
+
+        let weights = normalized_weights(&nodes[inputs[2]].output(), y_true.nrows());
+        let softmax = softmax(&logits);
+        let grad = (softmax.clone() - y_true) * weights;
+
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(softmax);
+    }
+}
+
+
+/// Weights of the rows of a loss node, normalized sample weights when the node
+/// has a weights input otherwise uniform weights
+fn row_weights(nodes: &[Node<Array2<f64>>], inputs: &[usize], rows: usize) -> Array2<f64> {
+    match inputs.get(2) {
+        Some(&idx) => normalized_weights(&nodes[idx].output(), rows),
+        None => Array2::ones((rows, 1))
+    }
+}
+
+
+/// Weight of a scalar loss node, the sample weight input when the node has one otherwise 1
+fn scalar_weight(nodes: &[Node<f64>], inputs: &[usize]) -> f64 {
+    inputs.get(2).map_or(1.0, |&idx| nodes[idx].output())
+}
+
+
+/// Class weights that balance the frequency of each class, `n / (classes * count)`.
+/// A single column of binary labels is treated as the classes 0 and 1.
+fn balanced_weights(y: &Array2<f64>) -> Vec<f64> {
+
+    let counts: Vec<f64> = if y.ncols() == 1 {
+        let positive = y.sum();
+        vec![y.nrows() as f64 - positive, positive]
+    } else {
+        y.sum_axis(Axis(0)).to_vec()
+    };
+
+    let classes = counts.len() as f64;
+    counts
+        .iter()
+        .map(|count| {
+            if *count > 0.0 { y.nrows() as f64 / (classes * count) } else { 0.0 }
+        })
+        .collect()
+}
+
+
+/// Binary cross entropy where each class is scaled by a weight, 
+/// `class_weights` holds the weights of the negative & positive class
+#[derive(Clone, Debug)]
+pub struct ClassWeightedBinaryCrossEntropy {
+    pub class_weights: Vec<f64>
+}
+
+impl ClassWeightedBinaryCrossEntropy {
+
+    /// Create loss with weights of the negative & positive class
+    pub fn new(negative: f64, positive: f64) -> Self {
+        ClassWeightedBinaryCrossEntropy {
+            class_weights: vec![negative, positive]
+        }
+    }
+
+    /// Create loss with weights inversely proportional to class frequencies in `y`
+    pub fn balanced(y: &Array2<f64>) -> Self {
+        ClassWeightedBinaryCrossEntropy {
+            class_weights: balanced_weights(y)
+        }
+    }
+
+    /// Weight of each row from its label
+    fn label_weights(&self, y_true: &Array2<f64>) -> Array2<f64> {
+        y_true.mapv(|y| self.label_weight(y))
+    }
+
+    /// Weight of a single label
+    fn label_weight(&self, y: f64) -> f64 {
+
+        if self.class_weights.len() != 2 {
+            panic!(
+                "Class weighted binary cross entropy expects 2 class weights, got {:?}",
+                self.class_weights.len()
+            );
+        }
+
+        let (negative, positive) = (self.class_weights[0], self.class_weights[1]);
+        y * positive + (1.0 - y) * negative
+    }
+}
+
+impl Operation<Array2<f64>> for ClassWeightedBinaryCrossEntropy {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward class weighted BCE on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        if y_pred.shape() != y_true.shape() {
+            panic!(
+                "Value shapes for class weighted binary cross entropy not equal {:?} != {:?}",
+                y_pred.shape(), y_true.shape()
+            );
+        }
+
+        let weights = self.label_weights(&y_true) * row_weights(nodes, &inputs, y_true.nrows());
+        let losses = ndarray::Zip::from(&y_true)
+            .and(&y_pred)
+            .map_collect(|&y, &p| binary_cross_entropy(y, p));
+
+        let result = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), result) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward class weighted BCE on node index: {:?}",
+            curr_idx
+        );
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = self.label_weights(&y_true) * row_weights(nodes, &inputs, y_true.nrows());
+        let grad = ndarray::Zip::from(&y_true)
+            .and(&y_pred)
+            .map_collect(|&y, &p| binary_cross_entropy_grad(y, p));
+
+        nodes[curr_idx].set_grad_output(grad * weights); 
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        self.class_weights.clone()
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<Array2<f64>>> {
+        Box::new(ClassWeightedBinaryCrossEntropy { class_weights: parameters.to_vec() })
+    }
+}
+
+
+impl Operation<f64> for ClassWeightedBinaryCrossEntropy {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        let weight = self.label_weight(y_true) * scalar_weight(nodes, &inputs);
+        weight * binary_cross_entropy(y_true, y_pred)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weight = self.label_weight(y_true) * scalar_weight(nodes, &inputs);
+        let grad = weight * binary_cross_entropy_grad(y_true, y_pred);
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        self.class_weights.clone()
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<f64>> {
+        Box::new(ClassWeightedBinaryCrossEntropy { class_weights: parameters.to_vec() })
+    }
+}
+
+
+/// Categorical cross entropy (with softmax of logits) where each class is scaled by a weight
+#[derive(Clone, Debug)]
+pub struct ClassWeightedCategoricalCrossEntropy {
+    pub class_weights: Vec<f64>
+}
+
+impl ClassWeightedCategoricalCrossEntropy {
+
+    /// Create loss with a weight for each class (column of one hot outputs)
+    pub fn new(class_weights: Vec<f64>) -> Self {
+        ClassWeightedCategoricalCrossEntropy { class_weights }
+    }
+
+    /// Create loss with weights inversely proportional to class frequencies in `y`
+    pub fn balanced(y: &Array2<f64>) -> Self {
+        ClassWeightedCategoricalCrossEntropy {
+            class_weights: balanced_weights(y)
+        }
+    }
+
+    /// Weight of each row from its one hot label
+    fn label_weights(&self, y_true: &Array2<f64>) -> Array2<f64> {
+
+        if self.class_weights.len() != y_true.ncols() {
+            panic!(
+                "Class weights of length {:?} do not match number of classes {:?}",
+                self.class_weights.len(), y_true.ncols()
+            );
+        }
+
+        let class_weights = Array2::from_shape_vec(
+            (self.class_weights.len(), 1),
+            self.class_weights.clone()
+        ).unwrap();
+        y_true.dot(&class_weights)
+    }
+}
+
+impl Operation<Array2<f64>> for ClassWeightedCategoricalCrossEntropy {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!("[Class Weighted CCE]: Performing forward on node: {:?}", curr_idx);
+
+        let inputs = nodes[curr_idx].inputs();
+        let logits = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = self.label_weights(&y_true) * row_weights(nodes, &inputs, y_true.nrows());
+
+        let losses = -(y_true.clone() * softmax(&logits).mapv(f64::ln));
+        let total_loss = (losses * weights).sum() / y_true.nrows() as f64;
+        Array2::from_elem((1, 1), total_loss)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!("[Class Weighted CCE]: Backward pass on node {:?}", curr_idx); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let logits = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+
+        if logits.shape() != y_true.shape() {
+            panic!("Value shapes for class weighted categorical cross entropy not equal");
+        } 
+
+        let weights = self.label_weights(&y_true) * row_weights(nodes, &inputs, y_true.nrows());
+        let softmax = softmax(&logits);
+        let grad = (softmax.clone() - y_true) * weights;
+
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(softmax);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        self.class_weights.clone()
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<Array2<f64>>> {
+        Box::new(ClassWeightedCategoricalCrossEntropy::new(parameters.to_vec()))
+    }
+}
+
+
+/// Focal loss for binary labels, `-alpha_t * (1 - p_t)^gamma * ln(p_t)`.
+/// Down weights well classified samples by `gamma`, `alpha` weights the positive class
+/// & `1 - alpha` the negative class. With `gamma` of 0 & `alpha` of 0.5 it is half of binary cross entropy.
+#[derive(Clone, Debug)]
+pub struct BinaryFocalLoss {
+    pub gamma: f64,
+    pub alpha: f64
+}
+
+impl BinaryFocalLoss {
+
+    /// Create focal loss with focusing parameter `gamma` & positive class weight `alpha`
+    pub fn new(gamma: f64, alpha: f64) -> Self {
+        BinaryFocalLoss { gamma, alpha }
+    }
+
+    /// Focal loss of a single label & predicted probability
+    fn loss(&self, y: f64, p_raw: f64) -> f64 {
+        let p = p_raw.clamp(1e-7, 1.0 - 1e-7);
+        let p_t = y * p + (1.0 - y) * (1.0 - p);
+        let alpha_t = y * self.alpha + (1.0 - y) * (1.0 - self.alpha);
+        -alpha_t * (1.0 - p_t).powf(self.gamma) * p_t.ln()
+    }
+
+    /// Derivative of the focal loss with respect to the predicted probability
+    fn slope(&self, y: f64, p_raw: f64) -> f64 {
+        let gamma = self.gamma;
+        let p = p_raw.clamp(1e-7, 1.0 - 1e-7);
+        let p_t = y * p + (1.0 - y) * (1.0 - p);
+        let alpha_t = y * self.alpha + (1.0 - y) * (1.0 - self.alpha);
+
+        // derivative with respect to p_t, chained with dp_t/dp = 2y - 1
+        let focus = if gamma == 0.0 { 0.0 } else {
+            gamma * (1.0 - p_t).powf(gamma - 1.0) * p_t.ln()
+        };
+        let d_p_t = alpha_t * (focus - (1.0 - p_t).powf(gamma) / p_t);
+        d_p_t * (2.0 * y - 1.0)
+    }
+}
+
+impl Operation<Array2<f64>> for BinaryFocalLoss {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward binary focal loss on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        if y_pred.shape() != y_true.shape() {
+            panic!(
+                "Value shapes for binary focal loss not equal {:?} != {:?}",
+                y_pred.shape(), y_true.shape()
+            );
+        }
+
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+        let losses = ndarray::Zip::from(&y_true)
+            .and(&y_pred)
+            .map_collect(|&y, &p| self.loss(y, p));
+
+        let result = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), result) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward binary focal loss on node index: {:?}",
+            curr_idx
+        );
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+        let grad = ndarray::Zip::from(&y_true)
+            .and(&y_pred)
+            .map_collect(|&y, &p| self.slope(y, p));
+
+        nodes[curr_idx].set_grad_output(grad * weights); 
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.gamma, self.alpha]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<Array2<f64>>> {
+        Box::new(BinaryFocalLoss::new(parameters[0], parameters[1]))
+    }
+}
+
+
+impl Operation<f64> for BinaryFocalLoss {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        scalar_weight(nodes, &inputs) * self.loss(y_true, y_pred)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let grad = scalar_weight(nodes, &inputs) * self.slope(y_true, y_pred);
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.gamma, self.alpha]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<f64>> {
+        Box::new(BinaryFocalLoss::new(parameters[0], parameters[1]))
+    }
+}
+
+
+/// Focal loss (with softmax of logits) for one hot labels, `-sum(y * (1 - p)^gamma * ln(p))`.
+/// With `gamma` of 0 it is the same as categorical cross entropy.
+#[derive(Clone, Debug)]
+pub struct CategoricalFocalLoss {
+    pub gamma: f64
+}
+
+impl CategoricalFocalLoss {
+
+    /// Create focal loss with focusing parameter `gamma`
+    pub fn new(gamma: f64) -> Self {
+        CategoricalFocalLoss { gamma }
+    }
+}
+
+impl Operation<Array2<f64>> for CategoricalFocalLoss {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!("[Categorical Focal]: Performing forward on node: {:?}", curr_idx);
+
+        let inputs = nodes[curr_idx].inputs();
+        let logits = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let losses = softmax(&logits).mapv(|p| -(1.0 - p).powf(self.gamma) * p.ln()) * y_true.clone();
+        let total_loss = (losses * weights).sum() / y_true.nrows() as f64;
+        Array2::from_elem((1, 1), total_loss)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!("[Categorical Focal]: Backward pass on node {:?}", curr_idx); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let logits = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+
+        if logits.shape() != y_true.shape() {
+            panic!("Value shapes for categorical focal loss not equal");
+        } 
+
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+        let softmax = softmax(&logits);
+        let gamma = self.gamma;
+
+        // a_k = y_k * p_k * dL/dp_k, chained through softmax as a_j - p_j * sum(a)
+        let scaled = ndarray::Zip::from(&y_true)
+            .and(&softmax)
+            .map_collect(|&y, &p_raw| {
+                let p = p_raw.max(1e-12);
+                let focus = if gamma == 0.0 { 0.0 } else {
+                    gamma * (1.0 - p).powf(gamma - 1.0) * p.ln()
+                };
+                y * p * (focus - (1.0 - p).powf(gamma) / p)
+            });
+
+        let totals = scaled.sum_axis(Axis(1)).insert_axis(Axis(1));
+        let grad = (scaled - softmax.clone() * totals) * weights;
+
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(softmax);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.gamma]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<Array2<f64>>> {
+        Box::new(CategoricalFocalLoss::new(parameters[0]))
+    }
+}
+
+
+/// Huber loss, squared error for residuals within `delta` & absolute error outside of it.
+/// Less sensitive to outliers than mean squared error.
+#[derive(Clone, Debug)]
+pub struct Huber {
+    pub delta: f64
+}
+
+impl Huber {
+
+    /// Create huber loss with threshold `delta` between squared & absolute error
+    pub fn new(delta: f64) -> Self {
+        Huber { delta }
+    }
+}
+
+impl Operation<Array2<f64>> for Huber {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward huber on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let delta = self.delta;
+        let losses = (y_pred - y_true.clone()).mapv(|r| {
+            if r.abs() <= delta { 0.5 * r * r } else { delta * (r.abs() - 0.5 * delta) }
+        });
+
+        let val = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), val) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward huber on node index: {:?}",
+            curr_idx
+        );
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let delta = self.delta;
+        let grad = (y_pred - y_true).mapv(|r| r.clamp(-delta, delta)) * weights;
+        nodes[curr_idx].set_grad_output(grad.clone());
+        nodes[inputs[0]].set_grad_output(grad.clone());
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.delta]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<Array2<f64>>> {
+        Box::new(Huber::new(parameters[0]))
+    }
+}
+
+
+impl Operation<f64> for Huber {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        let r = nodes[inputs[0]].output() - nodes[inputs[1]].output();
+        let loss = if r.abs() <= self.delta {
+            0.5 * r * r
+        } else {
+            self.delta * (r.abs() - 0.5 * self.delta)
+        };
+        scalar_weight(nodes, &inputs) * loss
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let r = nodes[inputs[0]].output() - nodes[inputs[1]].output();
+        let grad = scalar_weight(nodes, &inputs) * r.clamp(-self.delta, self.delta);
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.delta]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<f64>> {
+        Box::new(Huber::new(parameters[0]))
+    }
+}
+
+
+/// Quantile (pinball) loss, `max(tau * (y - p), (tau - 1) * (y - p))`.
+/// Predictions estimate the `tau` quantile of the targets instead of the mean.
+#[derive(Clone, Debug)]
+pub struct Quantile {
+    pub tau: f64
+}
+
+impl Quantile {
+
+    /// Create quantile loss for quantile `tau` between 0 & 1
+    pub fn new(tau: f64) -> Self {
+        Quantile { tau }
+    }
+
+    /// Derivative of the pinball loss with respect to the prediction
+    fn slope(&self, residual: f64) -> f64 {
+        if residual > 0.0 { -self.tau } else { 1.0 - self.tau }
+    }
+}
+
+impl Operation<Array2<f64>> for Quantile {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward quantile on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let tau = self.tau;
+        let losses = (y_true.clone() - y_pred).mapv(|r| (tau * r).max((tau - 1.0) * r));
+        let val = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), val) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward quantile on node index: {:?}",
+            curr_idx
+        );
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let grad = (y_true - y_pred).mapv(|r| self.slope(r)) * weights;
+        nodes[curr_idx].set_grad_output(grad.clone());
+        nodes[inputs[0]].set_grad_output(grad.clone());
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.tau]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<Array2<f64>>> {
+        Box::new(Quantile::new(parameters[0]))
+    }
+}
+
+
+impl Operation<f64> for Quantile {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        let r = nodes[inputs[1]].output() - nodes[inputs[0]].output();
+        scalar_weight(nodes, &inputs) * (self.tau * r).max((self.tau - 1.0) * r)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let r = nodes[inputs[1]].output() - nodes[inputs[0]].output();
+        let grad = scalar_weight(nodes, &inputs) * self.slope(r);
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.tau]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<f64>> {
+        Box::new(Quantile::new(parameters[0]))
+    }
+}
+
+
+/// Numerically stable `ln(cosh(x))`
+fn log_cosh(x: f64) -> f64 {
+    let a = x.abs();
+    a + (-2.0 * a).exp().ln_1p() - std::f64::consts::LN_2
+}
+
+
+/// Log-cosh loss, `ln(cosh(p - y))`, smooth loss that is quadratic for small 
+/// residuals & linear for large residuals
+#[derive(Clone, Debug)]
+pub struct LogCosh;
+
+impl Operation<Array2<f64>> for LogCosh {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward log cosh on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let losses = (y_pred - y_true.clone()).mapv(log_cosh);
+        let val = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), val) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward log cosh on node index: {:?}",
+            curr_idx
+        );
+
+        let inputs = nodes[curr_idx].inputs();
+        let y_pred = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let grad = (y_pred - y_true).mapv(f64::tanh) * weights;
+        nodes[curr_idx].set_grad_output(grad.clone());
+        nodes[inputs[0]].set_grad_output(grad.clone());
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+}
+
+
+impl Operation<f64> for LogCosh {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        scalar_weight(nodes, &inputs) * log_cosh(nodes[inputs[0]].output() - nodes[inputs[1]].output())
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let r = nodes[inputs[0]].output() - nodes[inputs[1]].output();
+        let grad = scalar_weight(nodes, &inputs) * r.tanh();
+        nodes[curr_idx].set_grad_output(grad);
+        nodes[inputs[0]].set_grad_output(grad);
+        nodes[inputs[1]].set_grad_output(grad);
+    }
+}
+
+
+/// Labels of 0 or 1 as signs of -1 or 1 used by margin losses
+fn label_signs(y_true: &Array2<f64>) -> Array2<f64> {
+    y_true.mapv(|y| 2.0 * y - 1.0)
+}
+
+
+/// Hinge loss `max(0, 1 - t * s)` of decision scores `s` with labels of 0 or 1 (as signs `t`).
+/// Each column is a separate one vs rest classifier.
+#[derive(Clone, Debug)]
+pub struct Hinge;
+
+impl Operation<Array2<f64>> for Hinge {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward hinge on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let scores = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        if scores.shape() != y_true.shape() {
+            panic!(
+                "Value shapes for hinge loss not equal {:?} != {:?}",
+                scores.shape(), y_true.shape()
+            );
+        }
+
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+        let margins = label_signs(&y_true) * scores;
+        let losses = margins.mapv(|m| (1.0 - m).max(0.0));
+        let val = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), val) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward hinge on node index: {:?}",
+            curr_idx
+        );
+
+        let inputs = nodes[curr_idx].inputs();
+        let scores = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let signs = label_signs(&y_true);
+        let grad = ndarray::Zip::from(&signs)
+            .and(&scores)
+            .map_collect(|&t, &s| if t * s < 1.0 { -t } else { 0.0 });
+
+        nodes[curr_idx].set_grad_output(grad * weights); 
+    }
+}
+
+
+impl Operation<f64> for Hinge {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        let t = 2.0 * nodes[inputs[1]].output() - 1.0;
+        (1.0 - t * nodes[inputs[0]].output()).max(0.0)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let t = 2.0 * nodes[inputs[1]].output() - 1.0;
+        let grad = if t * nodes[inputs[0]].output() < 1.0 { -t } else { 0.0 };
+        nodes[curr_idx].set_grad_output(grad);
+    }
+}
+
+
+/// Squared hinge loss `max(0, 1 - t * s)^2`, smooth version of hinge loss
+/// that penalizes margin violations quadratically
+#[derive(Clone, Debug)]
+pub struct SquaredHinge;
+
+impl Operation<Array2<f64>> for SquaredHinge {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!(
+            "Performing forward squared hinge on node index: {:?}",
+            curr_idx
+        ); 
+
+        let inputs = nodes[curr_idx].inputs();
+        let scores = nodes[inputs[0]].output();
+        let y_true = nodes[inputs[1]].output();
+        if scores.shape() != y_true.shape() {
+            panic!(
+                "Value shapes for squared hinge loss not equal {:?} != {:?}",
+                scores.shape(), y_true.shape()
+            );
+        }
+
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+        let margins = label_signs(&y_true) * scores;
+        let losses = margins.mapv(|m| (1.0 - m).max(0.0).powi(2));
+        let val = (losses * weights).sum() / y_true.len() as f64;
+        Array2::from_elem((1, 1), val) 
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!(
+            "Performing backward squared hinge on node index: {:?}",
+            curr_idx
+        );
+
+        let inputs = nodes[curr_idx].inputs();
+        let scores = nodes[inputs[0]].output(); 
+        let y_true = nodes[inputs[1]].output();
+        let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+        let signs = label_signs(&y_true);
+        let grad = ndarray::Zip::from(&signs)
+            .and(&scores)
+            .map_collect(|&t, &s| -2.0 * t * (1.0 - t * s).max(0.0));
+
+        nodes[curr_idx].set_grad_output(grad * weights); 
+    }
+}
+
+
+impl Operation<f64> for SquaredHinge {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        let t = 2.0 * nodes[inputs[1]].output() - 1.0;
+        (1.0 - t * nodes[inputs[0]].output()).max(0.0).powi(2)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let t = 2.0 * nodes[inputs[1]].output() - 1.0;
+        let grad = -2.0 * t * (1.0 - t * nodes[inputs[0]].output()).max(0.0);
+        nodes[curr_idx].set_grad_output(grad);
+    }
+}
+
+
+/// Unit deviance of a tweedie distribution with variance power `power`,
+/// normal (0), poisson (1), compound poisson gamma (between 1 & 2) and gamma (2)
+fn unit_deviance(y: f64, mu: f64, power: f64) -> f64 {
+
+    let mu = mu.max(f64::MIN_POSITIVE);
+    if power == 0.0 {
+        (y - mu).powi(2)
+    } else if power == 1.0 {
+        let y_log = if y > 0.0 { y * (y / mu).ln() } else { 0.0 };
+        2.0 * (y_log - (y - mu))
+    } else if power == 2.0 {
+        2.0 * ((mu / y).ln() + y / mu - 1.0)
+    } else {
+        2.0 * (
+            y.max(0.0).powf(2.0 - power) / ((1.0 - power) * (2.0 - power)) 
+            - y * mu.powf(1.0 - power) / (1.0 - power) 
+            + mu.powf(2.0 - power) / (2.0 - power)
+        )
+    }
+}
+
+/// Derivative of half the unit deviance with respect to the mean, `(mu - y) / mu^power`
+fn unit_deviance_grad(y: f64, mu: f64, power: f64) -> f64 {
+    let mu = mu.max(f64::MIN_POSITIVE);
+    (mu - y) / mu.powf(power)
+}
+
+/// Mean tweedie deviance of predicted means (first input) & targets (second input)
+fn deviance_forward(nodes: &[Node<Array2<f64>>], curr_idx: usize, power: f64) -> Array2<f64> {
+
+    let inputs = nodes[curr_idx].inputs();
+    let mu = nodes[inputs[0]].output();
+    let y_true = nodes[inputs[1]].output();
+    if mu.shape() != y_true.shape() {
+        panic!(
+            "Value shapes for deviance not equal {:?} != {:?}",
+            mu.shape(), y_true.shape()
+        );
+    }
+
+    let weights = row_weights(nodes, &inputs, y_true.nrows());
+    let deviance = ndarray::Zip::from(&y_true)
+        .and(&mu)
+        .map_collect(|&y, &m| unit_deviance(y, m, power));
+
+    let val = (deviance * weights).sum() / y_true.len() as f64;
+    Array2::from_elem((1, 1), val)
+}
+
+/// Set gradient of half the tweedie deviance for the loss node
+fn deviance_backward(nodes: &mut [Node<Array2<f64>>], curr_idx: usize, power: f64) {
+
+    let inputs = nodes[curr_idx].inputs();
+    let mu = nodes[inputs[0]].output();
+    let y_true = nodes[inputs[1]].output();
+    let weights = row_weights(nodes, &inputs, y_true.nrows());
+
+    let grad = ndarray::Zip::from(&y_true)
+        .and(&mu)
+        .map_collect(|&y, &m| unit_deviance_grad(y, m, power));
+
+    nodes[curr_idx].set_grad_output(grad * weights);
+}
+
+
+/// Poisson deviance `2 * (y * ln(y / mu) - (y - mu))` of predicted means, for count targets
+#[derive(Clone, Debug)]
+pub struct PoissonDeviance;
+
+impl Operation<Array2<f64>> for PoissonDeviance {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!("Performing forward poisson deviance on node index: {:?}", curr_idx); 
+        deviance_forward(nodes, curr_idx, 1.0)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!("Performing backward poisson deviance on node index: {:?}", curr_idx); 
+        deviance_backward(nodes, curr_idx, 1.0);
+    }
+}
+
+
+impl Operation<f64> for PoissonDeviance {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        unit_deviance(nodes[inputs[1]].output(), nodes[inputs[0]].output(), 1.0)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let grad = unit_deviance_grad(nodes[inputs[1]].output(), nodes[inputs[0]].output(), 1.0);
+        nodes[curr_idx].set_grad_output(grad);
+    }
+}
+
+
+/// Gamma deviance `2 * (ln(mu / y) + y / mu - 1)` of predicted means, for positive skewed targets
+#[derive(Clone, Debug)]
+pub struct GammaDeviance;
+
+impl Operation<Array2<f64>> for GammaDeviance {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!("Performing forward gamma deviance on node index: {:?}", curr_idx); 
+        deviance_forward(nodes, curr_idx, 2.0)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!("Performing backward gamma deviance on node index: {:?}", curr_idx); 
+        deviance_backward(nodes, curr_idx, 2.0);
+    }
+}
+
+
+impl Operation<f64> for GammaDeviance {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        unit_deviance(nodes[inputs[1]].output(), nodes[inputs[0]].output(), 2.0)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let grad = unit_deviance_grad(nodes[inputs[1]].output(), nodes[inputs[0]].output(), 2.0);
+        nodes[curr_idx].set_grad_output(grad);
+    }
+}
+
+
+/// Tweedie deviance of predicted means with variance power `power`.
+/// Powers between 1 & 2 model non negative targets with exact zeros, like insurance claims.
+#[derive(Clone, Debug)]
+pub struct TweedieDeviance {
+    pub power: f64
+}
+
+impl TweedieDeviance {
+
+    /// Create tweedie deviance with variance power
+    pub fn new(power: f64) -> Self {
+        TweedieDeviance { power }
+    }
+}
+
+impl Operation<Array2<f64>> for TweedieDeviance {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) -> Array2<f64> {
+
+        debug!("Performing forward tweedie deviance on node index: {:?}", curr_idx); 
+        deviance_forward(nodes, curr_idx, self.power)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<Array2<f64>>>, 
+        curr_idx: usize) {
+
+        debug!("Performing backward tweedie deviance on node index: {:?}", curr_idx); 
+        deviance_backward(nodes, curr_idx, self.power);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.power]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<Array2<f64>>> {
+        Box::new(TweedieDeviance::new(parameters[0]))
+    }
+}
+
+
+impl Operation<f64> for TweedieDeviance {
+
+    fn forward(
+        &self, 
+        nodes: &Vec<Node<f64>>, 
+        curr_idx: usize) -> f64 {
+
+        let inputs = nodes[curr_idx].inputs();
+        unit_deviance(nodes[inputs[1]].output(), nodes[inputs[0]].output(), self.power)
+    }
+
+    fn backward(
+        &self, 
+        nodes: &mut Vec<Node<f64>>, 
+        curr_idx: usize) {
+
+        let inputs = nodes[curr_idx].inputs();
+        let grad = unit_deviance_grad(
+            nodes[inputs[1]].output(), nodes[inputs[0]].output(), self.power
+        );
+        nodes[curr_idx].set_grad_output(grad);
+    }
+
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.power]
+    }
+
+    fn with_parameters(&self, parameters: &[f64]) -> Box<dyn Operation<f64>> {
+        Box::new(TweedieDeviance::new(parameters[0]))
+    }
+}
+
+
+#[cfg(test)]
+mod loss_ops_test {
+
+    use crate::autodiff::graph::*;
+    use crate::autodiff::operations::arithmetic::*; 
+    use crate::autodiff::operations::loss::*; 
+    use ndarray::{arr2};
+
+
+    #[test]
+    fn test_mse() {
+
+        let a = arr2(&[[1.0], [2.0], [3.0]]); 
+        let b = arr2(&[[1.0], [2.0], [3.0]]); 
+        let c = arr2(&[[1.0], [1.0], [1.0]]); 
+
+        let mut graph = ComputationGraph::new();
+        graph.add(vec![a, b]);
+        graph.mse(c);
+
+        assert_eq!(graph.nodes().len(), 5);
+
+        assert_eq!(graph.node(0).inputs().len(), 0); 
+        assert_eq!(graph.node(0).output().shape(), vec![3, 1]); 
+
+        assert_eq!(graph.node(1).inputs().len(), 0); 
+        assert_eq!(graph.node(1).output().shape(), vec![3, 1]); 
+
+        assert_eq!(graph.node(2).inputs().len(), 2); 
+        assert_eq!(graph.node(2).output().shape(), vec![0, 0]); 
+
+        graph.forward();
+
+        assert_eq!(graph.node(2).output().shape(), vec![3, 1]); 
+        assert_eq!(
+            graph.node(2).output(), 
+            arr2(&[[2.0],[4.0],[6.0]])
+        );
+
+        graph.backward();
+
+        assert_eq!(
+            graph.node(2).grad(), 
+            arr2(&[[1.0],[3.0],[5.0]])
+        );
+
+    }
+
+    #[test]
+    fn test_mae() {
+
+        let a = arr2(&[[5.0], [10.0], [6.0],[9.0],[10.0]]); 
+        let b = arr2(&[[5.0], [5.0], [6.0],[9.0],[10.0]]); 
+        let c = arr2(&[[12.0], [15.0], [10.0], [20.0], [18.0]]); 
+
+        let mut graph = ComputationGraph::new();
+        graph.add(vec![a, b]);
+        graph.mae(c);
+
+        graph.forward();
+        graph.backward();
+
+        assert_eq!(graph.curr_node().output().as_slice().unwrap()[0], 1.6);
+        assert_eq!(
+            graph.curr_node().grad(), 
+            arr2(&[[-0.2],[0.2],[0.2],[-0.2],[0.2]])
+        );
+
+    }
+
+
+    #[test]
+    fn test_weighted_loss() {
+
+        let pred = arr2(&[[1.0], [2.0], [3.0]]);
+        let bias = arr2(&[[0.0], [0.0], [0.0]]);
+        let y = arr2(&[[2.0], [2.0], [5.0]]);
+
+        // uniform weights match the unweighted operation
+        let mut graph = ComputationGraph::new();
+        graph.add(vec![pred.clone(), bias.clone()]);
+        graph.mse(y.clone());
+        graph.forward();
+        graph.backward();
+
+        let mut weighted = ComputationGraph::new();
+        weighted.add(vec![pred.clone(), bias.clone()]);
+        weighted.weighted_mse(y.clone(), arr2(&[[3.0], [3.0], [3.0]]));
+
+        assert_eq!(weighted.nodes().len(), 6);
+        assert_eq!(weighted.curr_node().inputs(), vec![2, 3, 4]);
+
+        weighted.forward();
+        weighted.backward();
+
+        let diff = weighted.curr_node().output() - graph.curr_node().output();
+        assert!(diff[[0, 0]].abs() < 1e-12);
+        assert_eq!(weighted.node(2).grad(), graph.node(2).grad());
+
+        // weights are rescaled to a mean of 1
+        weighted.mut_node_output(4, arr2(&[[0.0], [1.0], [2.0]]));
+        weighted.forward();
+        weighted.backward();
+
+        assert!((weighted.curr_node().output()[[0, 0]] - 8.0 / 3.0).abs() < 1e-12);
+        assert_eq!(weighted.node(2).grad(), arr2(&[[0.0], [0.0], [-4.0]]));
+
+        // weighted cross entropy ignores rows with zero weight
+        let probs = arr2(&[[0.9], [0.2], [0.7]]);
+        let labels = arr2(&[[1.0], [1.0], [1.0]]);
+        let mut bce = ComputationGraph::new();
+        bce.add(vec![probs, bias.clone()]);
+        bce.weighted_bce(labels, arr2(&[[1.0], [0.0], [1.0]]));
+        bce.forward();
+
+        let expected = -(0.9_f64.ln() + 0.7_f64.ln()) * 1.5 / 3.0;
+        assert!((bce.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+
+        let logits = arr2(&[[2.0, 0.0], [0.0, 2.0]]);
+        let one_hot = arr2(&[[1.0, 0.0], [1.0, 0.0]]);
+        let mut cce = ComputationGraph::new();
+        cce.add(vec![logits, arr2(&[[0.0, 0.0]])]);
+        cce.weighted_cce(one_hot, arr2(&[[1.0], [0.0]]));
+        cce.forward();
+        cce.backward();
+
+        let grad = cce.curr_node().grad();
+        assert_eq!(grad.row(1).sum(), 0.0);
+        assert!(grad[[0, 0]] < 0.0);
+
+        // scalar weighted cross entropy scales the loss of a single sample
+        let mut scalar: ComputationGraph<f64> = ComputationGraph::new();
+        scalar.add(vec![0.7, 0.1]);
+        scalar.weighted_bce(1.0, 2.0);
+        scalar.forward();
+        scalar.backward();
+
+        assert!((scalar.curr_node().output() + 2.0 * 0.8_f64.ln()).abs() < 1e-12);
+        assert!((scalar.curr_node().grad() + 2.0 / 0.8).abs() < 1e-9);
+
+        // softmax losses need rows of class scores, so they are only registered for matrices
+        assert!(!scalar.registry.contains_key("WeightedCategoricalCrossEntropy"));
+        assert!(cce.registry.contains_key("WeightedCategoricalCrossEntropy"));
+    }
+
+    #[test]
+    fn test_class_weighted_and_focal_loss() {
+
+        let probs = arr2(&[[0.9], [0.2], [0.7], [0.4]]);
+        let labels = arr2(&[[1.0], [1.0], [0.0], [0.0]]);
+        let bias = arr2(&[[0.0], [0.0], [0.0], [0.0]]);
+
+        let mut bce = ComputationGraph::new();
+        bce.add(vec![probs.clone(), bias.clone()]);
+        bce.bce(labels.clone());
+        bce.forward();
+        bce.backward();
+        let bce_loss = bce.curr_node().output()[[0, 0]];
+
+        // unit class weights match binary cross entropy
+        let mut graph = ComputationGraph::new();
+        graph.add(vec![probs.clone(), bias.clone()]);
+        graph.bce(labels.clone());
+
+        let loss_idx = graph.nodes().len() - 1;
+        graph.nodes[loss_idx].set_operation(Box::new(ClassWeightedBinaryCrossEntropy::new(1.0, 1.0)));
+        graph.forward();
+        graph.backward();
+
+        assert!((graph.curr_node().output()[[0, 0]] - bce_loss).abs() < 1e-12);
+        assert_eq!(graph.curr_node().grad(), bce.curr_node().grad());
+
+        // positive class weight scales only positive rows
+        graph.nodes[loss_idx].set_operation(Box::new(ClassWeightedBinaryCrossEntropy::new(1.0, 3.0)));
+        graph.forward();
+        graph.backward();
+
+        let grad = graph.curr_node().grad();
+        let base = bce.curr_node().grad();
+        assert!((grad[[0, 0]] - 3.0 * base[[0, 0]]).abs() < 1e-9);
+        assert!((grad[[2, 0]] - base[[2, 0]]).abs() < 1e-9);
+
+        let balanced = ClassWeightedBinaryCrossEntropy::balanced(&arr2(&[[1.0], [0.0], [0.0], [0.0]]));
+        assert_eq!(balanced.class_weights, vec![4.0 / 6.0, 2.0]);
+
+        // focal loss without focusing is half of binary cross entropy
+        graph.nodes[loss_idx].set_operation(Box::new(BinaryFocalLoss::new(0.0, 0.5)));
+        graph.forward();
+        graph.backward();
+        assert!((graph.curr_node().output()[[0, 0]] - 0.5 * bce_loss).abs() < 1e-12);
+
+        // focal gradient matches finite differences
+        graph.nodes[loss_idx].set_operation(Box::new(BinaryFocalLoss::new(2.0, 0.25)));
+        graph.forward();
+        graph.backward();
+        let grad = graph.curr_node().grad();
+
+        let h = 1e-6;
+        for row in 0..probs.nrows() {
+            let mut loss_at = |delta: f64| {
+                let mut shifted = probs.clone();
+                shifted[[row, 0]] += delta;
+                graph.mut_node_output(0, shifted);
+                graph.forward();
+                graph.curr_node().output()[[0, 0]]
+            };
+            let numeric = (loss_at(h) - loss_at(-h)) / (2.0 * h) * probs.nrows() as f64;
+            assert!((numeric - grad[[row, 0]]).abs() < 1e-5);
+        }
+
+        // categorical focal loss without focusing matches categorical cross entropy
+        let logits = arr2(&[[2.0, 0.5, -1.0], [0.0, 1.0, 0.5]]);
+        let one_hot = arr2(&[[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        let mut cce = ComputationGraph::new();
+        cce.add(vec![logits.clone(), arr2(&[[0.0, 0.0, 0.0]])]);
+        cce.cce(one_hot.clone());
+        cce.forward();
+        cce.backward();
+
+        let mut focal = ComputationGraph::new();
+        focal.add(vec![logits.clone(), arr2(&[[0.0, 0.0, 0.0]])]);
+        focal.cce(one_hot.clone());
+        let loss_idx = focal.nodes().len() - 1;
+        focal.nodes[loss_idx].set_operation(Box::new(CategoricalFocalLoss::new(0.0)));
+        focal.forward();
+        focal.backward();
+
+        let diff = focal.curr_node().output() - cce.curr_node().output();
+        assert!(diff[[0, 0]].abs() < 1e-12);
+        let diff = focal.curr_node().grad() - cce.curr_node().grad();
+        assert!(diff.iter().all(|x| x.abs() < 1e-12));
+
+        // categorical focal gradient matches finite differences
+        focal.nodes[loss_idx].set_operation(Box::new(CategoricalFocalLoss::new(2.0)));
+        focal.forward();
+        focal.backward();
+        let grad = focal.curr_node().grad();
+
+        for ((i, j), g) in grad.indexed_iter() {
+            let mut loss_at = |delta: f64| {
+                let mut shifted = logits.clone();
+                shifted[[i, j]] += delta;
+                focal.mut_node_output(0, shifted);
+                focal.forward();
+                focal.curr_node().output()[[0, 0]]
+            };
+            let numeric = (loss_at(h) - loss_at(-h)) / (2.0 * h) * logits.nrows() as f64;
+            assert!((numeric - g).abs() < 1e-5);
+        }
+
+        // class weights scale categorical cross entropy rows by their class
+        focal.mut_node_output(0, logits);
+        focal.nodes[loss_idx].set_operation(Box::new(
+            ClassWeightedCategoricalCrossEntropy::new(vec![2.0, 1.0, 1.0])
+        ));
+        focal.forward();
+        focal.backward();
+
+        let grad = focal.curr_node().grad();
+        let base = cce.curr_node().grad();
+        assert!((grad[[0, 1]] - 2.0 * base[[0, 1]]).abs() < 1e-12);
+        assert!((grad[[1, 1]] - base[[1, 1]]).abs() < 1e-12);
+
+        // scalar binary losses match a single row, scaled by the sample weight input
+        let mut scalar: ComputationGraph<f64> = ComputationGraph::new();
+        scalar.add(vec![0.9, 0.0]);
+        scalar.weighted_bce(1.0, 2.0);
+        let loss_idx = scalar.nodes().len() - 1;
+        scalar.nodes[loss_idx].set_operation(Box::new(ClassWeightedBinaryCrossEntropy::new(1.0, 3.0)));
+        scalar.forward();
+        scalar.backward();
+        assert!((scalar.curr_node().output() + 6.0 * 0.9_f64.ln()).abs() < 1e-12);
+        assert!((scalar.curr_node().grad() + 6.0 / 0.9).abs() < 1e-9);
+
+        let mut row = ComputationGraph::new();
+        row.add(vec![arr2(&[[0.9]]), arr2(&[[0.0]])]);
+        row.bce(arr2(&[[1.0]]));
+        let row_idx = row.nodes().len() - 1;
+        row.nodes[row_idx].set_operation(Box::new(BinaryFocalLoss::new(2.0, 0.25)));
+        row.forward();
+        row.backward();
+
+        scalar.nodes[loss_idx].set_operation(Box::new(BinaryFocalLoss::new(2.0, 0.25)));
+        scalar.forward();
+        scalar.backward();
+        assert!((scalar.curr_node().output() - 2.0 * row.curr_node().output()[[0, 0]]).abs() < 1e-12);
+        assert!((scalar.curr_node().grad() - 2.0 * row.curr_node().grad()[[0, 0]]).abs() < 1e-12);
+
+        // softmax losses need rows of class scores, so they are only registered for matrices
+        assert!(!scalar.registry.contains_key("CategoricalFocalLoss"));
+        assert!(!scalar.registry.contains_key("ClassWeightedCategoricalCrossEntropy"));
+        assert!(focal.registry.contains_key("CategoricalFocalLoss"));
+    }
+
+    #[test]
+    fn test_robust_loss() {
+
+        let pred = arr2(&[[1.5], [-1.0], [4.0]]);
+        let bias = arr2(&[[0.0], [0.0], [0.0]]);
+        let y = arr2(&[[1.0], [2.0], [4.0]]);
+
+        let mut huber = ComputationGraph::new();
+        huber.add(vec![pred.clone(), bias.clone()]);
+        huber.huber(y.clone(), 1.0);
+        huber.forward();
+        huber.backward();
+
+        // quadratic inside delta, linear outside
+        let expected = (0.5 * 0.25 + (3.0 - 0.5)) / 3.0;
+        assert!((huber.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+        assert_eq!(huber.curr_node().grad(), arr2(&[[0.5], [-1.0], [0.0]]));
+
+        let mut quantile = ComputationGraph::new();
+        quantile.add(vec![pred.clone(), bias.clone()]);
+        quantile.quantile(y.clone(), 0.9);
+        quantile.forward();
+        quantile.backward();
+
+        let expected = (0.1 * 0.5 + 0.9 * 3.0) / 3.0;
+        assert!((quantile.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+        let diff = quantile.curr_node().grad() - arr2(&[[0.1], [-0.9], [0.1]]);
+        assert!(diff.iter().all(|d| d.abs() < 1e-12));
+
+        let mut log_cosh = ComputationGraph::new();
+        log_cosh.add(vec![pred.clone(), bias.clone()]);
+        log_cosh.log_cosh(y.clone());
+        log_cosh.forward();
+        log_cosh.backward();
+
+        let expected = (0.5_f64.cosh().ln() + 3.0_f64.cosh().ln()) / 3.0;
+        assert!((log_cosh.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+        let diff = log_cosh.curr_node().grad() - arr2(&[[0.5_f64.tanh()], [-(3.0_f64.tanh())], [0.0]]);
+        assert!(diff.iter().all(|d| d.abs() < 1e-12));
+
+        // scalar losses are scaled by the sample weight input
+        let mut scalar: ComputationGraph<f64> = ComputationGraph::new();
+        scalar.add(vec![1.5, 0.0]);
+        scalar.weighted_bce(4.0, 2.0);
+        let loss_idx = scalar.nodes().len() - 1;
+
+        let cases: Vec<(Box<dyn Operation<f64>>, f64, f64)> = vec![
+            (Box::new(Huber::new(1.0)), 4.0, -2.0),
+            (Box::new(Quantile::new(0.9)), 4.5, -1.8),
+            (Box::new(LogCosh), 2.0 * 2.5_f64.cosh().ln(), -2.0 * 2.5_f64.tanh())
+        ];
+        for (operation, loss, grad) in cases {
+            scalar.nodes[loss_idx].set_operation(operation);
+            scalar.forward();
+            scalar.backward();
+            assert!((scalar.curr_node().output() - loss).abs() < 1e-12);
+            assert!((scalar.curr_node().grad() - grad).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_hinge_loss() {
+
+        let scores = arr2(&[[2.0], [0.5], [-0.5], [0.2]]);
+        let bias = arr2(&[[0.0], [0.0], [0.0], [0.0]]);
+        let labels = arr2(&[[1.0], [1.0], [0.0], [0.0]]);
+
+        let mut hinge = ComputationGraph::new();
+        hinge.add(vec![scores.clone(), bias.clone()]);
+        hinge.hinge(labels.clone());
+        hinge.forward();
+        hinge.backward();
+
+        // only points inside the margin contribute
+        let expected = (0.0 + 0.5 + 0.5 + 1.2) / 4.0;
+        assert!((hinge.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+        assert_eq!(hinge.curr_node().grad(), arr2(&[[0.0], [-1.0], [1.0], [1.0]]));
+
+        let mut squared = ComputationGraph::new();
+        squared.add(vec![scores, bias]);
+        squared.squared_hinge(labels);
+        squared.forward();
+        squared.backward();
+
+        let expected = (0.25 + 0.25 + 1.44) / 4.0;
+        assert!((squared.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+        let diff = squared.curr_node().grad() - arr2(&[[0.0], [-1.0], [1.0], [2.4]]);
+        assert!(diff.iter().all(|d| d.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_deviance_loss() {
+
+        let mu = arr2(&[[1.0], [2.0], [4.0]]);
+        let bias = arr2(&[[0.0], [0.0], [0.0]]);
+        let y = arr2(&[[0.0], [2.0], [2.0]]);
+
+        let mut poisson = ComputationGraph::new();
+        poisson.add(vec![mu.clone(), bias.clone()]);
+        poisson.poisson_deviance(y.clone());
+        poisson.forward();
+        poisson.backward();
+
+        // zero counts only contribute the mean
+        let expected = 2.0 * (1.0 + 0.0 + (2.0 * 0.5_f64.ln() + 2.0)) / 3.0;
+        assert!((poisson.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+        assert_eq!(poisson.curr_node().grad(), arr2(&[[1.0], [0.0], [0.5]]));
+
+        let mut gamma = ComputationGraph::new();
+        gamma.add(vec![mu.clone(), bias.clone()]);
+        gamma.gamma_deviance(arr2(&[[1.0], [2.0], [2.0]]));
+        gamma.forward();
+        gamma.backward();
+
+        let expected = 2.0 * (2.0_f64.ln() + 0.5 - 1.0) / 3.0;
+        assert!((gamma.curr_node().output()[[0, 0]] - expected).abs() < 1e-12);
+        assert_eq!(gamma.curr_node().grad(), arr2(&[[0.0], [0.0], [0.125]]));
+
+        // tweedie deviance is continuous in the power
+        for (power, reference) in [(1.0, poisson), (2.0, gamma)] {
+            let targets = reference.node(3).output();
+            for shift in [-1e-6, 1e-6] {
+                let mut tweedie = ComputationGraph::new();
+                tweedie.add(vec![mu.clone(), bias.clone()]);
+                tweedie.tweedie_deviance(targets.clone(), power + shift);
+                tweedie.forward();
+                let diff = tweedie.curr_node().output() - reference.curr_node().output();
+                assert!(diff[[0, 0]].abs() < 1e-4);
+            }
+        }
     }
 
     #[test]
@@ -13,10 +13,11 @@ pub trait DefaultOperations<T> {
 }
 
 /// Registry of supported operations by default. 
-/// Operation registry for computation graph can be extended using this trait pattern implementation
+/// Operation registry for computation graph can be extended using this trait pattern implementation,
+/// operations only defined for one value type are passed after it.
 macro_rules! default_ops {
 
-    ($t:ty) => {
+    ($t:ty $(, $name:literal => $op:expr)*) => {
 
         impl DefaultOperations<$t> for ComputationGraph<$t> {
 
@@ -33,6 +34,9 @@ macro_rules! default_ops {
                 // default activation operations
                 self.register("Tanh", Box::new(Tanh)); 
                 self.register("Sigmoid", Box::new(Sigmoid)); 
+                self.register("Exp", Box::new(Exp)); 
+                self.register("Identity", Box::new(Identity)); 
+                self.register("Reciprocal", Box::new(Reciprocal)); 
 
                 // default loss functions
                 self.register("MSE", Box::new(MSE)); 
@@ -44,11 +48,35 @@ macro_rules! default_ops {
                     "CategoricalCrossEntropy", 
                     Box::new(CategoricalCrossEntropy)
                 );
+                self.register("WeightedMSE", Box::new(WeightedMSE)); 
+                self.register(
+                    "WeightedBinaryCrossEntropy", 
+                    Box::new(WeightedBinaryCrossEntropy)
+                );
+                self.register(
+                    "ClassWeightedBinaryCrossEntropy", 
+                    Box::new(ClassWeightedBinaryCrossEntropy::new(1.0, 1.0))
+                );
+                self.register(
+                    "BinaryFocalLoss", 
+                    Box::new(BinaryFocalLoss::new(2.0, 0.25))
+                );
+                self.register("Huber", Box::new(Huber::new(1.0))); 
+                self.register("Quantile", Box::new(Quantile::new(0.5))); 
+                self.register("LogCosh", Box::new(LogCosh)); 
+                self.register("Hinge", Box::new(Hinge)); 
+                self.register("SquaredHinge", Box::new(SquaredHinge)); 
+                self.register("PoissonDeviance", Box::new(PoissonDeviance)); 
+                self.register("GammaDeviance", Box::new(GammaDeviance)); 
+                self.register("TweedieDeviance", Box::new(TweedieDeviance::new(1.5))); 
                 self.register(
                     "DefaultLossFunction", 
                     Box::new(DefaultLossFunction)
                 ); 
 
+                // operations for value type only
+                $(self.register($name, Box::new($op));)*
+
             }
 
         }
@@ -58,5 +86,12 @@ macro_rules! default_ops {
 }
 
 default_ops!(f64); 
-default_ops!(Array2<f64>); 
+
+// losses over rows of class scores
+default_ops!(
+    Array2<f64>,
+    "WeightedCategoricalCrossEntropy" => WeightedCategoricalCrossEntropy,
+    "ClassWeightedCategoricalCrossEntropy" => ClassWeightedCategoricalCrossEntropy::new(vec![]),
+    "CategoricalFocalLoss" => CategoricalFocalLoss::new(2.0)
+); 
 
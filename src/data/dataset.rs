@@ -0,0 +1,763 @@
+//! Datasets with seeded shuffling, train/test/validation splits & lazy batch iteration
+
+use std::fs::File;
+
+use csv::{Position, StringRecord};
+use ndarray::{Array2, Axis};
+use rand::{thread_rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::prelude::SliceRandom;
+
+use crate::data::tabular::*;
+use crate::metrics::classification::class_rows;
+
+
+/// Rows of a dataset loaded into memory
+#[derive(Debug, Clone)]
+pub struct Batch {
+
+    /// Input features
+    pub x: Array2<f64>,
+
+    /// Target labels
+    pub y: Array2<f64>,
+
+    /// Weight of each sample (if provided)
+    pub sample_weight: Option<Array2<f64>>
+}
+
+
+/// Source of rows that are read on demand, so data doesn't need to fit in memory
+pub trait Dataset {
+
+    /// Number of rows
+    fn len(&self) -> usize;
+
+    /// Check if dataset has no rows
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read rows by index, in the order given
+    fn rows(&self, indices: &[usize]) -> Result<Batch, String>;
+
+    /// Targets of every row, used to stratify splits
+    fn targets(&self) -> Result<Array2<f64>, String> {
+        let indices: Vec<usize> = (0..self.len()).collect();
+        Ok(self.rows(&indices)?.y)
+    }
+
+}
+
+
+/// Dataset of arrays held in memory
+#[derive(Debug, Clone)]
+pub struct ArrayDataset {
+    data: Batch
+}
+
+
+impl ArrayDataset {
+
+    /// Create dataset from features and targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features.
+    /// * `y` - Target labels.
+    ///
+    pub fn new(x: &Array2<f64>, y: &Array2<f64>) -> Result<Self, String> {
+        ArrayDataset::from_batch(Batch {
+            x: x.clone(),
+            y: y.clone(),
+            sample_weight: None
+        })
+    }
+
+    /// Create dataset from features, targets and the weight of each sample
+    pub fn with_sample_weight(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        sample_weight: &Array2<f64>) -> Result<Self, String> {
+        ArrayDataset::from_batch(Batch {
+            x: x.clone(),
+            y: y.clone(),
+            sample_weight: Some(sample_weight.clone())
+        })
+    }
+
+    /// Create dataset from rows loaded in memory
+    pub fn from_batch(data: Batch) -> Result<Self, String> {
+
+        let weight_rows = data.sample_weight.as_ref().map_or(data.x.nrows(), |w| w.nrows());
+        if data.x.nrows() != data.y.nrows() || data.x.nrows() != weight_rows {
+            return Err(
+                "Features, labels and sample weights must have same number of rows".to_string()
+            );
+        }
+
+        Ok(ArrayDataset { data })
+    }
+
+    /// Retrieve all rows of the dataset
+    pub fn data(&self) -> &Batch {
+        &self.data
+    }
+
+}
+
+
+impl Dataset for ArrayDataset {
+
+    fn len(&self) -> usize {
+        self.data.x.nrows()
+    }
+
+    fn rows(&self, indices: &[usize]) -> Result<Batch, String> {
+
+        if let Some(idx) = indices.iter().find(|idx| **idx >= self.len()) {
+            return Err(format!("Row {} out of range for dataset of {} rows", idx, self.len()));
+        }
+
+        Ok(Batch {
+            x: self.data.x.select(Axis(0), indices),
+            y: self.data.y.select(Axis(0), indices),
+            sample_weight: self.data.sample_weight
+                .as_ref()
+                .map(|weights| weights.select(Axis(0), indices))
+        })
+    }
+
+    fn targets(&self) -> Result<Array2<f64>, String> {
+        Ok(self.data.y.clone())
+    }
+
+}
+
+
+/// Dataset of delimited text (CSV/TSV) read lazily from disk.
+/// Only the byte position of each row is kept in memory, rows are parsed when a batch reads them.
+#[derive(Debug, Clone)]
+pub struct CsvDataset {
+
+    /// Path of the file
+    filepath: String,
+
+    /// Delimiter, header handling & missing value markers
+    options: CsvOptions,
+
+    /// Name of each column
+    headers: Vec<String>,
+
+    /// Column indices of input features
+    features: Vec<usize>,
+
+    /// Column indices of target labels
+    targets: Vec<usize>,
+
+    /// Byte position of each data row
+    positions: Vec<Position>
+}
+
+
+impl CsvDataset {
+
+    /// Number of rows used to infer the header
+    const HEADER_SAMPLE: usize = 100;
+
+    /// Index rows of a delimited file.
+    ///
+    /// # Arguments
+    ///
+    /// * `filepath` - Path of the file.
+    /// * `features` - Names of numeric feature columns.
+    /// * `targets` - Names of numeric target columns.
+    /// * `options` - Delimiter, header handling & missing value markers.
+    ///
+    pub fn new(
+        filepath: &str,
+        features: &[&str],
+        targets: &[&str],
+        options: CsvOptions) -> Result<Self, String> {
+
+        let file = File::open(filepath).map_err(|err| format!("{}: {}", filepath, err))?;
+        let mut reader = csv_reader(file, &options);
+
+        let mut record = StringRecord::new();
+        let mut sample: Vec<Vec<String>> = vec![];
+        let mut positions = vec![];
+        while reader.read_record(&mut record).map_err(|err| err.to_string())? {
+            positions.push(record.position().unwrap().clone());
+            if sample.len() < CsvDataset::HEADER_SAMPLE {
+                sample.push(record.iter().map(|cell| cell.to_string()).collect());
+            }
+        }
+
+        if sample.is_empty() {
+            return Err("File contains no rows".to_string());
+        }
+
+        let is_missing = |cell: &String| options.missing_values.contains(cell);
+        let has_header = match options.header {
+            Header::Present => true,
+            Header::Absent => false,
+            Header::Infer => Table::infer_header(&sample, &is_missing)
+        };
+
+        let headers = if has_header {
+            positions.remove(0);
+            sample.remove(0)
+        } else {
+            (0..sample[0].len()).map(|idx| idx.to_string()).collect()
+        };
+
+        let column_index = |name: &&str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or(format!("Column '{}' not found, columns are {:?}", name, headers))
+        };
+
+        if features.is_empty() || targets.is_empty() {
+            return Err("Feature and target columns must be selected".to_string());
+        }
+
+        let features = features.iter().map(column_index).collect::<Result<Vec<usize>, String>>()?;
+        let targets = targets.iter().map(column_index).collect::<Result<Vec<usize>, String>>()?;
+
+        Ok(CsvDataset {
+            filepath: filepath.to_string(),
+            options,
+            headers,
+            features,
+            targets,
+            positions
+        })
+    }
+
+    /// Retrieve column names
+    pub fn headers(&self) -> &Vec<String> {
+        &self.headers
+    }
+
+    /// Read columns of rows, seeking only when rows aren't consecutive
+    fn read(&self, indices: &[usize], columns: &[&Vec<usize>]) -> Result<Vec<Array2<f64>>, String> {
+
+        let file = File::open(&self.filepath).map_err(|err| format!("{}: {}", self.filepath, err))?;
+        let mut reader = csv_reader(file, &self.options);
+        let mut record = StringRecord::new();
+
+        let mut outputs: Vec<Array2<f64>> = columns
+            .iter()
+            .map(|cols| Array2::zeros((indices.len(), cols.len())))
+            .collect();
+
+        let mut next = None;
+        for (row, idx) in indices.iter().enumerate() {
+
+            if next != Some(*idx) {
+                let position = self.positions.get(*idx).ok_or(format!(
+                    "Row {} out of range for dataset of {} rows", idx, self.len()
+                ))?;
+                reader.seek(position.clone()).map_err(|err| err.to_string())?;
+            }
+
+            if !reader.read_record(&mut record).map_err(|err| err.to_string())? {
+                return Err(format!("{}: file ended before row {}", self.filepath, idx));
+            }
+            next = Some(idx + 1);
+
+            for (output, cols) in outputs.iter_mut().zip(columns.iter()) {
+                for (col, column) in cols.iter().enumerate() {
+                    output[[row, col]] = self.parse(&record[*column], *column, *idx)?;
+                }
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    /// Parse cell as number, missing values are NaN
+    fn parse(&self, cell: &str, column: usize, row: usize) -> Result<f64, String> {
+        if self.options.missing_values.iter().any(|missing| missing == cell) {
+            return Ok(f64::NAN);
+        }
+
+        cell.parse::<f64>().map_err(|_| {
+            format!(
+                "Column '{}' row {}: '{}' is not numeric",
+                self.headers[column], row, cell
+            )
+        })
+    }
+
+}
+
+
+impl Dataset for CsvDataset {
+
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn rows(&self, indices: &[usize]) -> Result<Batch, String> {
+        let mut outputs = self.read(indices, &[&self.features, &self.targets])?;
+        let y = outputs.pop().unwrap();
+        let x = outputs.pop().unwrap();
+        Ok(Batch { x, y, sample_weight: None })
+    }
+
+    fn targets(&self) -> Result<Array2<f64>, String> {
+        let indices: Vec<usize> = (0..self.len()).collect();
+        Ok(self.read(&indices, &[&self.targets])?.pop().unwrap())
+    }
+
+}
+
+
+/// Rows of a dataset selected by index
+#[derive(Debug, Clone)]
+pub struct Subset<'a, D: Dataset + ?Sized> {
+    dataset: &'a D,
+    indices: Vec<usize>
+}
+
+
+impl<'a, D: Dataset + ?Sized> Subset<'a, D> {
+
+    /// Create subset of rows of a dataset
+    pub fn new(dataset: &'a D, indices: Vec<usize>) -> Result<Self, String> {
+
+        if let Some(idx) = indices.iter().find(|idx| **idx >= dataset.len()) {
+            return Err(format!("Row {} out of range for dataset of {} rows", idx, dataset.len()));
+        }
+
+        Ok(Subset { dataset, indices })
+    }
+
+    /// Retrieve row indices of the subset in the parent dataset
+    pub fn indices(&self) -> &Vec<usize> {
+        &self.indices
+    }
+
+}
+
+
+impl<D: Dataset + ?Sized> Dataset for Subset<'_, D> {
+
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn rows(&self, indices: &[usize]) -> Result<Batch, String> {
+        let parent = indices
+            .iter()
+            .map(|idx| self.indices.get(*idx).cloned().ok_or(format!(
+                "Row {} out of range for dataset of {} rows", idx, self.len()
+            )))
+            .collect::<Result<Vec<usize>, String>>()?;
+        self.dataset.rows(&parent)
+    }
+
+    fn targets(&self) -> Result<Array2<f64>, String> {
+        Ok(self.dataset.targets()?.select(Axis(0), &self.indices))
+    }
+
+}
+
+
+/// Options for splitting a dataset into train, test & validation rows
+#[derive(Debug, Clone, Copy)]
+pub struct SplitOptions {
+
+    /// Fraction of rows held out for testing
+    pub test: f64,
+
+    /// Fraction of rows held out for validation
+    pub validation: f64,
+
+    /// Seed for shuffling rows before splitting, rows are not shuffled when `None`
+    pub seed: Option<u64>,
+
+    /// Preserve class proportions of the targets (non negative integers or one hot rows) in each split
+    pub stratified: bool
+}
+
+
+impl Default for SplitOptions {
+
+    fn default() -> Self {
+        SplitOptions {
+            test: 0.2,
+            validation: 0.0,
+            seed: None,
+            stratified: false
+        }
+    }
+
+}
+
+
+/// Train, test & validation subsets of a dataset
+#[derive(Debug, Clone)]
+pub struct Splits<'a, D: Dataset + ?Sized> {
+
+    /// Rows used for training
+    pub train: Subset<'a, D>,
+
+    /// Rows held out for testing
+    pub test: Subset<'a, D>,
+
+    /// Rows held out for validation (empty when the validation fraction is 0)
+    pub validation: Subset<'a, D>
+}
+
+
+/// Split rows of dataset into train, test & validation subsets.
+/// Rows of each subset keep their order in the dataset, so files are read sequentially.
+///
+/// ```
+/// use ndarray::Array2;
+/// use dendritic::data::prelude::*;
+///
+/// let x = Array2::from_shape_fn((10, 2), |(i, j)| (i + j) as f64);
+/// let y = Array2::from_shape_fn((10, 1), |(i, _)| (i % 2) as f64);
+/// let dataset = ArrayDataset::new(&x, &y).unwrap();
+///
+/// let options = SplitOptions { test: 0.2, validation: 0.2, seed: Some(7), stratified: true };
+/// let splits = split(&dataset, options).unwrap();
+/// assert_eq!(splits.train.len(), 6);
+/// assert_eq!(splits.test.targets().unwrap().sum(), 1.0);
+/// ```
+pub fn split<D: Dataset + ?Sized>(dataset: &D, options: SplitOptions) -> Result<Splits<'_, D>, String> {
+
+    let held_out = options.test + options.validation;
+    if options.test < 0.0 || options.validation < 0.0 || held_out >= 1.0 {
+        return Err(
+            "Test and validation fractions must be non negative and sum to less than 1".to_string()
+        );
+    }
+
+    let shuffle = |indices: &mut Vec<usize>| {
+        if let Some(seed) = options.seed {
+            indices.shuffle(&mut StdRng::seed_from_u64(seed));
+        }
+    };
+
+    let groups: Vec<Vec<usize>> = if options.stratified {
+        class_rows(&dataset.targets()?)?
+    } else {
+        vec![(0..dataset.len()).collect()]
+    };
+
+    let (mut train, mut test, mut validation) = (vec![], vec![], vec![]);
+    for mut group in groups {
+        shuffle(&mut group);
+        let rows = group.len() as f64;
+        let n_test = (rows * options.test).round() as usize;
+        let n_val = ((rows * options.validation).round() as usize).min(group.len() - n_test);
+        test.extend_from_slice(&group[..n_test]);
+        validation.extend_from_slice(&group[n_test..n_test + n_val]);
+        train.extend_from_slice(&group[n_test + n_val..]);
+    }
+
+    if train.is_empty() {
+        return Err("Split leaves no rows for training".to_string());
+    }
+
+    train.sort_unstable();
+    test.sort_unstable();
+    validation.sort_unstable();
+
+    Ok(Splits {
+        train: Subset::new(dataset, train)?,
+        test: Subset::new(dataset, test)?,
+        validation: Subset::new(dataset, validation)?
+    })
+}
+
+
+/// Options for iterating a dataset in batches
+#[derive(Debug, Clone, Copy)]
+pub struct LoaderOptions {
+
+    /// Number of rows in each batch
+    pub batch_size: usize,
+
+    /// Shuffle rows every epoch
+    pub shuffle: bool,
+
+    /// Seed for shuffling, each epoch uses `seed + epoch`. Shuffling is random when `None`
+    pub seed: Option<u64>,
+
+    /// Skip the final batch when it has fewer than `batch_size` rows
+    pub drop_last: bool
+}
+
+
+impl Default for LoaderOptions {
+
+    fn default() -> Self {
+        LoaderOptions {
+            batch_size: 32,
+            shuffle: true,
+            seed: None,
+            drop_last: false
+        }
+    }
+
+}
+
+
+/// Loader of batches from a dataset, rows are only read when a batch is requested
+#[derive(Debug, Clone)]
+pub struct DataLoader<'a, D: Dataset + ?Sized> {
+
+    /// Dataset to read rows from
+    dataset: &'a D,
+
+    /// Batch size, shuffling & final batch handling
+    options: LoaderOptions,
+
+    /// Number of passes made over the dataset
+    epoch: u64
+}
+
+
+impl<'a, D: Dataset + ?Sized> DataLoader<'a, D> {
+
+    /// Create data loader.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset` - Dataset to read rows from.
+    /// * `options` - Batch size, shuffling & final batch handling.
+    ///
+    /// ```
+    /// use ndarray::Array2;
+    /// use dendritic::data::prelude::*;
+    ///
+    /// let x = Array2::from_shape_fn((5, 2), |(i, j)| (i + j) as f64);
+    /// let y = Array2::from_shape_fn((5, 1), |(i, _)| i as f64);
+    /// let dataset = ArrayDataset::new(&x, &y).unwrap();
+    ///
+    /// let options = LoaderOptions { batch_size: 2, seed: Some(1), ..LoaderOptions::default() };
+    /// let mut loader = DataLoader::new(&dataset, options).unwrap();
+    /// for batch in loader.batches() {
+    ///     let batch = batch.unwrap();
+    ///     assert!(batch.x.nrows() <= 2);
+    /// }
+    /// ```
+    pub fn new(dataset: &'a D, options: LoaderOptions) -> Result<Self, String> {
+
+        if options.batch_size == 0 {
+            return Err("Batch size must be greater than 0".to_string());
+        }
+
+        Ok(DataLoader {
+            dataset,
+            options,
+            epoch: 0
+        })
+    }
+
+    /// Retrieve dataset of the loader
+    pub fn dataset(&self) -> &'a D {
+        self.dataset
+    }
+
+    /// Retrieve options of the loader
+    pub fn options(&self) -> &LoaderOptions {
+        &self.options
+    }
+
+    /// Number of batches in each pass over the dataset
+    pub fn n_batches(&self) -> usize {
+        let rows = self.dataset.len();
+        match self.options.drop_last {
+            true => rows / self.options.batch_size,
+            false => rows.div_ceil(self.options.batch_size)
+        }
+    }
+
+    /// Batches of a single pass over the dataset, rows are shuffled again on every call
+    pub fn batches(&mut self) -> Batches<'a, D> {
+
+        let mut indices: Vec<usize> = (0..self.dataset.len()).collect();
+        if self.options.shuffle {
+            match self.options.seed {
+                Some(seed) => {
+                    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(self.epoch));
+                    indices.shuffle(&mut rng);
+                },
+                None => indices.shuffle(&mut thread_rng())
+            }
+        }
+        self.epoch += 1;
+
+        let batch_size = self.options.batch_size;
+        let drop_last = self.options.drop_last;
+        let batches: Vec<Vec<usize>> = indices
+            .chunks(batch_size)
+            .filter(|batch| !drop_last || batch.len() == batch_size)
+            .map(|batch| batch.to_vec())
+            .collect();
+
+        Batches {
+            dataset: self.dataset,
+            batches: batches.into_iter()
+        }
+    }
+
+}
+
+
+/// Iterator reading the batches of a single pass over a dataset
+pub struct Batches<'a, D: Dataset + ?Sized> {
+    dataset: &'a D,
+    batches: std::vec::IntoIter<Vec<usize>>
+}
+
+
+impl<D: Dataset + ?Sized> Iterator for Batches<'_, D> {
+
+    type Item = Result<Batch, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.batches.next().map(|indices| self.dataset.rows(&indices))
+    }
+
+}
+
+
+#[cfg(test)]
+mod dataset_tests {
+
+    use std::fs;
+    use ndarray::{arr2, Array2};
+    use crate::data::dataset::*;
+
+
+    #[test]
+    fn test_dataset_splits() {
+
+        let x = Array2::from_shape_fn((20, 2), |(i, j)| (i * 2 + j) as f64);
+        let y = Array2::from_shape_fn((20, 1), |(i, _)| (i < 5) as u8 as f64);
+        let dataset = ArrayDataset::new(&x, &y).unwrap();
+
+        assert!(ArrayDataset::new(&x, &arr2(&[[1.0]])).is_err());
+        assert!(dataset.rows(&[20]).is_err());
+
+        let unshuffled = split(&dataset, SplitOptions::default()).unwrap();
+        assert_eq!(unshuffled.test.indices(), &vec![0, 1, 2, 3]);
+        assert_eq!(unshuffled.train.len(), 16);
+        assert!(unshuffled.validation.is_empty());
+
+        let options = SplitOptions { test: 0.2, validation: 0.2, seed: Some(3), stratified: false };
+        let first = split(&dataset, options).unwrap();
+        let second = split(&dataset, options).unwrap();
+        assert_eq!(first.test.indices(), second.test.indices());
+        assert_eq!((first.train.len(), first.test.len(), first.validation.len()), (12, 4, 4));
+
+        let mut all: Vec<usize> = first.train.indices().clone();
+        all.extend(first.test.indices());
+        all.extend(first.validation.indices());
+        all.sort();
+        assert_eq!(all, (0..20).collect::<Vec<usize>>());
+
+        // a quarter of the rows are class 1 in every split
+        let stratified = SplitOptions { stratified: true, ..options };
+        let splits = split(&dataset, stratified).unwrap();
+        assert_eq!(splits.test.targets().unwrap().sum(), 1.0);
+        assert_eq!(splits.validation.targets().unwrap().sum(), 1.0);
+        assert_eq!(splits.train.targets().unwrap().sum(), 3.0);
+
+        // stratifying needs non negative integer labels
+        let negative = ArrayDataset::new(&x, &y.mapv(|v| v - 1.0)).unwrap();
+        assert!(split(&negative, stratified).is_err());
+        let fractional = ArrayDataset::new(&x, &y.mapv(|v| v + 0.5)).unwrap();
+        assert!(split(&fractional, stratified).is_err());
+
+        // rows of a subset map back to the dataset
+        let batch = splits.test.rows(&[0]).unwrap();
+        let parent = splits.test.indices()[0];
+        assert_eq!(batch.x.row(0), x.row(parent));
+
+        assert!(split(&dataset, SplitOptions { test: 0.6, validation: 0.4, ..options }).is_err());
+        assert!(split(&dataset, SplitOptions { test: -0.1, ..options }).is_err());
+    }
+
+
+    #[test]
+    fn test_data_loader() {
+
+        let x = Array2::from_shape_fn((7, 1), |(i, _)| i as f64);
+        let dataset = ArrayDataset::with_sample_weight(&x, &x, &Array2::ones((7, 1))).unwrap();
+
+        let empty = LoaderOptions { batch_size: 0, ..LoaderOptions::default() };
+        assert!(DataLoader::new(&dataset, empty).is_err());
+
+        let options = LoaderOptions { batch_size: 3, shuffle: false, seed: None, drop_last: false };
+        let mut ordered = DataLoader::new(&dataset, options).unwrap();
+        let batches: Vec<Batch> = ordered.batches().map(|b| b.unwrap()).collect();
+        assert_eq!(ordered.n_batches(), 3);
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[2].x, arr2(&[[6.0]]));
+        assert_eq!(batches[0].sample_weight.as_ref().unwrap().nrows(), 3);
+
+        let mut dropped = DataLoader::new(&dataset, LoaderOptions { drop_last: true, ..options }).unwrap();
+        assert_eq!(dropped.n_batches(), 2);
+        assert_eq!(dropped.batches().count(), 2);
+
+        // same seed gives the same order, every epoch is shuffled differently
+        let seeded = LoaderOptions { shuffle: true, seed: Some(11), ..options };
+        let order = |loader: &mut DataLoader<ArrayDataset>| -> Vec<f64> {
+            loader.batches().flat_map(|b| b.unwrap().x.into_iter()).collect()
+        };
+
+        let mut first = DataLoader::new(&dataset, seeded).unwrap();
+        let mut second = DataLoader::new(&dataset, seeded).unwrap();
+        let epoch_one = order(&mut first);
+        assert_eq!(epoch_one, order(&mut second));
+        assert_ne!(epoch_one, order(&mut first));
+
+        let mut sorted = epoch_one.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(sorted, x.column(0).to_vec());
+    }
+
+
+    #[test]
+    fn test_csv_dataset() {
+
+        fs::create_dir_all("data/csv_dataset").unwrap();
+        let path = "data/csv_dataset/rows.csv";
+        fs::write(path, "a,b,label\n1,2,0\n2,NA,1\n3,6,0\n4,8,1\n5,\"10\",0\n").unwrap();
+
+        let dataset = CsvDataset::new(path, &["a", "b"], &["label"], CsvOptions::default()).unwrap();
+        assert_eq!(dataset.len(), 5);
+        assert_eq!(dataset.headers()[2], "label");
+
+        let batch = dataset.rows(&[4, 1, 2]).unwrap();
+        assert_eq!(batch.x[[0, 1]], 10.0);
+        assert!(batch.x[[1, 1]].is_nan());
+        assert_eq!(batch.y.column(0).to_vec(), vec![0.0, 1.0, 0.0]);
+        assert_eq!(dataset.targets().unwrap().sum(), 2.0);
+        assert!(dataset.rows(&[5]).is_err());
+
+        let options = LoaderOptions { batch_size: 2, seed: Some(0), ..LoaderOptions::default() };
+        let mut loader = DataLoader::new(&dataset, options).unwrap();
+        let rows: usize = loader.batches().map(|b| b.unwrap().x.nrows()).sum();
+        assert_eq!(rows, 5);
+
+        assert!(CsvDataset::new(path, &["c"], &["label"], CsvOptions::default()).is_err());
+        let text = CsvDataset::new(path, &["0"], &["2"], CsvOptions {
+            header: Header::Absent,
+            ..CsvOptions::default()
+        }).unwrap();
+        assert!(text.rows(&[0]).is_err());
+
+        fs::remove_dir_all("data/csv_dataset").unwrap();
+    }
+
+}
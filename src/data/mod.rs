@@ -0,0 +1,33 @@
+//! # Data loading module
+//!
+//! This module provides utilities for loading datasets stored as delimited text files (CSV/TSV)
+//! into the `Array2<f64>` features & targets used by the models, and for splitting & batching datasets for training.
+//! Numeric columns are parsed to `f64`, categorical columns are passed to the encoders of the preprocessing module.
+//!
+//! ## Features
+//! - **Delimited Files**: Reading comma, tab or custom delimited files with quoted fields.
+//! - **Header Detection**: Headers are inferred from the first row, or set as present or absent.
+//! - **Column Selection**: Selecting numeric or categorical columns by name.
+//! - **Missing Values**: Markers such as `NA`, `?` or empty fields load as NaN, ready for the imputers.
+//! - **Datasets**: Arrays in memory or CSV files read lazily from disk, split into (stratified) train, test & validation subsets.
+//! - **Data Loader**: Batches with seeded shuffling, read one at a time for data larger than memory.
+//!
+//! ```rust
+//! use dendritic::data::prelude::*;
+//! use dendritic::preprocessing::prelude::*;
+//!
+//! let text = "rooms,city,price\n3,austin,200\n4,denver,NA\n2,austin,150\n";
+//! let table = Table::from_reader(text.as_bytes(), CsvOptions::default()).unwrap();
+//!
+//! let (rooms, price) = table.features_targets(&["rooms"], &["price"]).unwrap();
+//! let mut encoder = OrdinalEncoder::new(None);
+//! let city = table.encode(&["city"], &mut encoder).unwrap();
+//!
+//! assert_eq!(rooms.dim(), (3, 1));
+//! assert_eq!(city.column(0).to_vec(), vec![0.0, 1.0, 0.0]);
+//! assert!(price[[1, 0]].is_nan());
+//! ```
+
+pub mod tabular;
+pub mod dataset;
+pub mod prelude;
@@ -0,0 +1,2 @@
+pub use super::tabular::*;
+pub use super::dataset::*;
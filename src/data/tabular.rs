@@ -0,0 +1,412 @@
+//! Loading delimited text files (CSV/TSV) into feature & target arrays
+
+use std::fs::File;
+use std::io::Read;
+
+use csv::{Reader, ReaderBuilder, Trim};
+use ndarray::Array2;
+
+use crate::preprocessing::categorical::CategoricalEncoder;
+
+
+/// Whether the first row of a file holds column names
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Header {
+
+    /// Detect the header from the values of the first row
+    Infer,
+
+    /// First row holds column names
+    Present,
+
+    /// Every row is data, columns are named by their index ("0", "1", ...)
+    Absent
+}
+
+
+/// Type of the values of a column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnType {
+
+    /// Every value parses as a number or is missing
+    Numeric,
+
+    /// Values are category names
+    Categorical
+}
+
+
+/// Options for reading delimited text files
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+
+    /// Field delimiter
+    pub delimiter: u8,
+
+    /// Header handling of the first row
+    pub header: Header,
+
+    /// Values treated as missing, numeric columns load them as NaN
+    pub missing_values: Vec<String>
+}
+
+
+impl CsvOptions {
+
+    /// Default options with a tab delimiter
+    pub fn tsv() -> Self {
+        CsvOptions {
+            delimiter: b'\t',
+            ..CsvOptions::default()
+        }
+    }
+
+}
+
+
+impl Default for CsvOptions {
+
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            header: Header::Infer,
+            missing_values: ["", "NA", "N/A", "NaN", "nan", "null", "?"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect()
+        }
+    }
+
+}
+
+
+/// Reader of delimited text, every row is read as a record (headers are handled by the caller)
+pub(crate) fn csv_reader<R: Read>(reader: R, options: &CsvOptions) -> Reader<R> {
+    ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(false)
+        .trim(Trim::All)
+        .from_reader(reader)
+}
+
+
+/// Table of named columns read from a delimited text file.
+/// Cells are kept as text until columns are selected as numeric or categorical values.
+#[derive(Debug, Clone)]
+pub struct Table {
+
+    /// Name of each column
+    headers: Vec<String>,
+
+    /// Cells of each row, missing values are `None`
+    rows: Vec<Vec<Option<String>>>
+}
+
+
+impl Table {
+
+    /// Read comma separated file with default options
+    pub fn read_csv(filepath: &str) -> Result<Self, String> {
+        Table::from_path(filepath, CsvOptions::default())
+    }
+
+    /// Read tab separated file with default options
+    pub fn read_tsv(filepath: &str) -> Result<Self, String> {
+        Table::from_path(filepath, CsvOptions::tsv())
+    }
+
+    /// Read delimited file.
+    ///
+    /// # Arguments
+    ///
+    /// * `filepath` - Path of the file.
+    /// * `options` - Delimiter, header handling & missing value markers.
+    pub fn from_path(filepath: &str, options: CsvOptions) -> Result<Self, String> {
+        let file = File::open(filepath).map_err(|err| format!("{}: {}", filepath, err))?;
+        Table::from_reader(file, options)
+    }
+
+    /// Read delimited text from any reader.
+    ///
+    /// ```
+    /// use dendritic::data::prelude::*;
+    ///
+    /// let text = "size,city,price\n1.0,paris,10\n2.0,rome,NA\n3.0,paris,30\n";
+    /// let table = Table::from_reader(text.as_bytes(), CsvOptions::default()).unwrap();
+    ///
+    /// let x = table.numeric(&["size"]).unwrap();
+    /// let y = table.numeric(&["price"]).unwrap();
+    /// assert_eq!(x.dim(), (3, 1));
+    /// assert!(y[[1, 0]].is_nan());
+    /// assert_eq!(table.column_type("city").unwrap(), ColumnType::Categorical);
+    /// ```
+    pub fn from_reader<R: Read>(reader: R, options: CsvOptions) -> Result<Self, String> {
+
+        let mut reader = csv_reader(reader, &options);
+
+        let mut records: Vec<Vec<String>> = vec![];
+        for record in reader.records() {
+            let record = record.map_err(|err| err.to_string())?;
+            records.push(record.iter().map(|cell| cell.to_string()).collect());
+        }
+
+        if records.is_empty() {
+            return Err("File contains no rows".to_string());
+        }
+
+        let is_missing = |cell: &String| options.missing_values.contains(cell);
+        let has_header = match options.header {
+            Header::Present => true,
+            Header::Absent => false,
+            Header::Infer => Table::infer_header(&records, &is_missing)
+        };
+
+        let headers = if has_header {
+            records.remove(0)
+        } else {
+            (0..records[0].len()).map(|idx| idx.to_string()).collect()
+        };
+
+        let rows = records
+            .into_iter()
+            .map(|record| {
+                record
+                    .into_iter()
+                    .map(|cell| if is_missing(&cell) { None } else { Some(cell) })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Table { headers, rows })
+    }
+
+    /// First row is a header when a column has a text first value above numeric values,
+    /// or when no column is numeric and no first value repeats in its column
+    pub(crate) fn infer_header(records: &[Vec<String>], is_missing: &dyn Fn(&String) -> bool) -> bool {
+
+        let first = &records[0];
+        let rest = &records[1..];
+        let is_numeric = |cell: &String| cell.parse::<f64>().is_ok();
+
+        if rest.is_empty() {
+            return first.iter().any(|cell| !is_missing(cell) && !is_numeric(cell));
+        }
+
+        let mut all_text = true;
+        for (idx, name) in first.iter().enumerate() {
+            let values: Vec<&String> = rest
+                .iter()
+                .map(|record| &record[idx])
+                .filter(|cell| !is_missing(cell))
+                .collect();
+
+            let numeric = !values.is_empty() && values.iter().all(|cell| is_numeric(cell));
+            if numeric && !is_missing(name) && !is_numeric(name) {
+                return true;
+            }
+
+            all_text &= !numeric;
+        }
+
+        all_text && first.iter().enumerate().all(|(idx, name)| {
+            !is_missing(name) && rest.iter().all(|record| &record[idx] != name)
+        })
+    }
+
+    /// Retrieve column names
+    pub fn headers(&self) -> &Vec<String> {
+        &self.headers
+    }
+
+    /// Number of data rows
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Number of columns
+    pub fn ncols(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Index of column by name
+    pub fn column_index(&self, name: &str) -> Result<usize, String> {
+        self.headers
+            .iter()
+            .position(|header| header == name)
+            .ok_or(format!("Column '{}' not found, columns are {:?}", name, self.headers))
+    }
+
+    /// Type of column, numeric when every value that isn't missing parses as a number
+    pub fn column_type(&self, name: &str) -> Result<ColumnType, String> {
+        let idx = self.column_index(name)?;
+        let numeric = self.rows
+            .iter()
+            .filter_map(|row| row[idx].as_ref())
+            .all(|cell| cell.parse::<f64>().is_ok());
+
+        match numeric {
+            true => Ok(ColumnType::Numeric),
+            false => Ok(ColumnType::Categorical)
+        }
+    }
+
+    /// Names of numeric columns
+    pub fn numeric_columns(&self) -> Vec<&str> {
+        self.headers
+            .iter()
+            .filter(|name| self.column_type(name) == Ok(ColumnType::Numeric))
+            .map(|name| name.as_str())
+            .collect()
+    }
+
+    /// Values of numeric columns, missing values are NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - Names of the columns, in output order.
+    pub fn numeric(&self, columns: &[&str]) -> Result<Array2<f64>, String> {
+
+        let indices = self.indices(columns)?;
+        let mut output = Array2::zeros((self.nrows(), indices.len()));
+        for (row_idx, row) in self.rows.iter().enumerate() {
+            for (col_idx, idx) in indices.iter().enumerate() {
+                output[[row_idx, col_idx]] = match &row[*idx] {
+                    Some(cell) => cell.parse::<f64>().map_err(|_| {
+                        format!(
+                            "Column '{}' row {}: '{}' is not numeric, load it as categorical",
+                            columns[col_idx], row_idx, cell
+                        )
+                    })?,
+                    None => f64::NAN
+                };
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Category names of columns, missing values form their own empty ("") category
+    pub fn categorical(&self, columns: &[&str]) -> Result<Array2<String>, String> {
+
+        let indices = self.indices(columns)?;
+        let cells: Vec<String> = self.rows
+            .iter()
+            .flat_map(|row| indices.iter().map(|idx| row[*idx].clone().unwrap_or_default()))
+            .collect();
+
+        Array2::from_shape_vec((self.nrows(), indices.len()), cells).map_err(|err| err.to_string())
+    }
+
+    /// Fit categorical encoder to columns and encode them.
+    ///
+    /// ```
+    /// use dendritic::data::prelude::*;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let text = "city\tprice\nparis\t10\nrome\t20\nparis\t30\n";
+    /// let table = Table::from_reader(text.as_bytes(), CsvOptions::tsv()).unwrap();
+    ///
+    /// let mut encoder = OrdinalEncoder::new(None);
+    /// let cities = table.encode(&["city"], &mut encoder).unwrap();
+    /// assert_eq!(cities.column(0).to_vec(), vec![0.0, 1.0, 0.0]);
+    /// ```
+    pub fn encode<E: CategoricalEncoder + ?Sized>(
+        &self,
+        columns: &[&str],
+        encoder: &mut E) -> Result<Array2<f64>, String> {
+        encoder.fit_transform_categories(&self.categorical(columns)?.view())
+    }
+
+    /// Numeric feature & target arrays
+    pub fn features_targets(
+        &self,
+        features: &[&str],
+        targets: &[&str]) -> Result<(Array2<f64>, Array2<f64>), String> {
+        Ok((self.numeric(features)?, self.numeric(targets)?))
+    }
+
+    /// Indices of columns by name
+    fn indices(&self, columns: &[&str]) -> Result<Vec<usize>, String> {
+        if columns.is_empty() {
+            return Err("No columns selected".to_string());
+        }
+        columns.iter().map(|name| self.column_index(name)).collect()
+    }
+
+}
+
+
+#[cfg(test)]
+mod tabular_tests {
+
+    use crate::data::tabular::*;
+    use crate::preprocessing::categorical::*;
+
+
+    #[test]
+    fn test_header_detection() {
+
+        let with_header = "a,b\n1,2\n3,4\n";
+        let table = Table::from_reader(with_header.as_bytes(), CsvOptions::default()).unwrap();
+        assert_eq!(table.headers(), &vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(table.nrows(), 2);
+
+        let without_header = "1,2\n3,4\n";
+        let table = Table::from_reader(without_header.as_bytes(), CsvOptions::default()).unwrap();
+        assert_eq!(table.headers(), &vec!["0".to_string(), "1".to_string()]);
+        assert_eq!(table.numeric(&["0", "1"]).unwrap().row(0).to_vec(), vec![1.0, 2.0]);
+
+        // text only columns, first row values don't repeat below
+        let categories = "color,size\nred,small\nblue,large\nred,large\n";
+        let table = Table::from_reader(categories.as_bytes(), CsvOptions::default()).unwrap();
+        assert_eq!(table.headers()[0], "color");
+        assert_eq!(table.nrows(), 3);
+
+        let repeated = "red,small\nblue,large\nred,small\n";
+        let table = Table::from_reader(repeated.as_bytes(), CsvOptions::default()).unwrap();
+        assert_eq!(table.nrows(), 3);
+
+        let options = CsvOptions { header: Header::Absent, ..CsvOptions::default() };
+        let table = Table::from_reader(with_header.as_bytes(), options).unwrap();
+        assert_eq!(table.nrows(), 3);
+        assert!(table.numeric(&["0"]).is_err());
+
+        assert!(Table::from_reader("".as_bytes(), CsvOptions::default()).is_err());
+        assert!(Table::from_reader("a,b\n1,2,3\n".as_bytes(), CsvOptions::default()).is_err());
+        assert!(Table::read_csv("data/missing_file.csv").is_err());
+    }
+
+
+    #[test]
+    fn test_column_selection() {
+
+        let text = "id;name;score;group\n1;\"Smith; J\";4.5;a\n2;Lee;NA;b\n3;Kim;?;\n";
+        let options = CsvOptions { delimiter: b';', ..CsvOptions::default() };
+        let table = Table::from_reader(text.as_bytes(), options).unwrap();
+
+        assert_eq!(table.ncols(), 4);
+        assert_eq!(table.column_type("score").unwrap(), ColumnType::Numeric);
+        assert_eq!(table.column_type("name").unwrap(), ColumnType::Categorical);
+        assert_eq!(table.numeric_columns(), vec!["id", "score"]);
+        assert!(table.column_index("age").is_err());
+
+        let (x, y) = table.features_targets(&["score", "id"], &["id"]).unwrap();
+        assert_eq!(x.dim(), (3, 2));
+        assert_eq!(x[[0, 0]], 4.5);
+        assert!(x[[1, 0]].is_nan() && x[[2, 0]].is_nan());
+        assert_eq!(y.column(0).to_vec(), vec![1.0, 2.0, 3.0]);
+
+        let names = table.categorical(&["name", "group"]).unwrap();
+        assert_eq!(names[[0, 0]], "Smith; J");
+        assert_eq!(names[[2, 1]], "");
+
+        let err = table.numeric(&["name"]).unwrap_err();
+        assert!(err.contains("'name'"));
+        assert!(table.numeric(&[]).is_err());
+
+        let mut encoder = OrdinalEncoder::new(None);
+        let groups = table.encode(&["group"], &mut encoder).unwrap();
+        assert_eq!(groups.column(0).to_vec(), vec![1.0, 2.0, 0.0]);
+    }
+
+}
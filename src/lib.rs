@@ -10,6 +10,9 @@
 //! - 📐 **Auto-Differentiation**: Reverse-mode autodiff for computing gradients using `ndarray`.
 //! - ⚙️ **Optimizers**: Built-in optimizers like SGD, Adam, etc.
 //! - 📈 **Regression Models**: Traditional regression models (Linear, Logistic).
+//! - 📊 **Metrics**: Evaluation metrics for regression and classification models.
+//! - 🎯 **Model Selection**: K-fold and stratified cross validation, grid, random and successive halving hyperparameter search.
+//! - 📂 **Data Loading**: Reading CSV/TSV files into feature & target arrays with header detection and missing value markers.
 //! - 🔣 **Preprocessing**: Lightweight utilities for common preprocessing tasks (e.g., one-hot encoding).
 //! - 🧱 **Modular**: Designed to be flexible and easy to extend for research or custom pipelines.
 
@@ -30,4 +33,7 @@
 
 pub mod autodiff;
 pub mod optimizer;
+pub mod metrics;
+pub mod selection;
 pub mod preprocessing;
+pub mod data;
@@ -0,0 +1,521 @@
+//! Metrics for classification models
+
+use std::collections::BTreeMap;
+
+use ndarray::{Array2, ArrayView1, Axis};
+
+
+/// Averaging strategy for precision, recall and F1 scores
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Average {
+
+    /// Score of the positive class (label 1) in binary classification
+    Binary,
+
+    /// Unweighted mean of the score of each class
+    Macro,
+
+    /// Score computed from total true positives, false positives and false negatives
+    Micro
+}
+
+
+/// Index of largest value in a row
+fn argmax(row: ArrayView1<f64>) -> usize {
+    row.iter()
+        .enumerate()
+        .fold((0, f64::NEG_INFINITY), |(best, max), (idx, &val)| {
+            if val > max { (idx, val) } else { (best, max) }
+        })
+        .0
+}
+
+
+/// Decode class labels from a column of labels or a one hot encoded matrix
+pub fn true_labels(y_true: &Array2<f64>) -> Vec<usize> {
+    match y_true.ncols() {
+        1 => y_true.column(0).iter().map(|&y| y.round() as usize).collect(),
+        _ => y_true.axis_iter(Axis(0)).map(argmax).collect()
+    }
+}
+
+
+/// Row indices of each class ordered by label, used to stratify splits.
+/// A column of labels must hold non negative integers.
+pub(crate) fn class_rows(y_true: &Array2<f64>) -> Result<Vec<Vec<usize>>, String> {
+
+    if y_true.ncols() == 1 {
+        if let Some(label) = y_true.iter().find(|y| !(**y >= 0.0 && y.fract() == 0.0)) {
+            return Err(format!(
+                "Stratifying requires non negative integer class labels, got {}", label
+            ));
+        }
+    }
+
+    let mut classes: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (idx, label) in true_labels(y_true).into_iter().enumerate() {
+        classes.entry(label).or_default().push(idx);
+    }
+    Ok(classes.into_values().collect())
+}
+
+
+/// Decode predicted class labels from the output of a model.
+/// A single column holds probabilities or labels and is rounded to the nearest class,
+/// 2 or more columns hold probabilities, scores or one hot rows for each class.
+pub fn predicted_labels(y_pred: &Array2<f64>) -> Vec<usize> {
+    match y_pred.ncols() {
+        1 => y_pred.column(0).iter().map(|&y| y.round().max(0.0) as usize).collect(),
+        _ => y_pred.axis_iter(Axis(0)).map(argmax).collect()
+    }
+}
+
+
+/// Decode true and predicted labels, validating number of samples
+fn labels(y_true: &Array2<f64>, y_pred: &Array2<f64>) -> (Vec<usize>, Vec<usize>) {
+
+    if y_true.nrows() != y_pred.nrows() {
+        panic!(
+            "True and predicted values must have same number of samples {:?} != {:?}",
+            y_true.nrows(), y_pred.nrows()
+        );
+    }
+
+    if y_true.nrows() == 0 {
+        panic!("Metrics require at least one sample");
+    }
+
+    (true_labels(y_true), predicted_labels(y_pred))
+}
+
+
+/// Number of classes represented by the labels
+fn num_classes(y_true: &Array2<f64>, truth: &[usize], predicted: &[usize]) -> usize {
+    let max_label = truth.iter().chain(predicted.iter()).max().unwrap();
+    (max_label + 1).max(y_true.ncols()).max(2)
+}
+
+
+/// Fraction of samples where the predicted class matches the true class
+///
+/// # Arguments
+///
+/// * `y_true` - True class labels (column of labels or one hot encoded).
+/// * `y_pred` - Predicted output of model.
+///
+pub fn accuracy(y_true: &Array2<f64>, y_pred: &Array2<f64>) -> f64 {
+    let (truth, predicted) = labels(y_true, y_pred);
+    let correct = truth.iter().zip(predicted.iter()).filter(|(t, p)| t == p).count();
+    correct as f64 / truth.len() as f64
+}
+
+
+/// Confusion matrix of shape `(k, k)`,
+/// rows are the true classes and columns are the predicted classes.
+///
+/// # Arguments
+///
+/// * `y_true` - True class labels (column of labels or one hot encoded).
+/// * `y_pred` - Predicted output of model.
+///
+pub fn confusion_matrix(y_true: &Array2<f64>, y_pred: &Array2<f64>) -> Array2<f64> {
+
+    let (truth, predicted) = labels(y_true, y_pred);
+    let k = num_classes(y_true, &truth, &predicted);
+
+    let mut matrix = Array2::zeros((k, k));
+    for (t, p) in truth.iter().zip(predicted.iter()) {
+        matrix[[*t, *p]] += 1.0;
+    }
+    matrix
+}
+
+
+/// True positives, false positives and false negatives for each class
+fn class_counts(matrix: &Array2<f64>) -> Vec<(f64, f64, f64)> {
+    (0..matrix.nrows())
+        .map(|class| {
+            let tp = matrix[[class, class]];
+            let fp = matrix.column(class).sum() - tp;
+            let fn_ = matrix.row(class).sum() - tp;
+            (tp, fp, fn_)
+        })
+        .collect()
+}
+
+
+/// Ratio of numerator to denominator, zero when denominator is zero
+fn ratio(num: f64, den: f64) -> f64 {
+    if den == 0.0 { 0.0 } else { num / den }
+}
+
+
+/// F1 score from precision and recall
+fn f1(precision: f64, recall: f64) -> f64 {
+    ratio(2.0 * precision * recall, precision + recall)
+}
+
+
+/// Compute averaged score from per class counts
+fn averaged<F: Fn(f64, f64, f64) -> f64>(
+    y_true: &Array2<f64>,
+    y_pred: &Array2<f64>,
+    average: Average,
+    score: F) -> f64 {
+
+    let counts = class_counts(&confusion_matrix(y_true, y_pred));
+
+    match average {
+        Average::Binary => {
+            if counts.len() > 2 {
+                panic!("Binary average requires 2 classes, found {:?}", counts.len());
+            }
+            let (tp, fp, fn_) = counts[1];
+            score(tp, fp, fn_)
+        },
+        Average::Macro => {
+            let total: f64 = counts.iter().map(|(tp, fp, fn_)| score(*tp, *fp, *fn_)).sum();
+            total / counts.len() as f64
+        },
+        Average::Micro => {
+            let (tp, fp, fn_) = counts.iter().fold(
+                (0.0, 0.0, 0.0),
+                |acc, c| (acc.0 + c.0, acc.1 + c.1, acc.2 + c.2)
+            );
+            score(tp, fp, fn_)
+        }
+    }
+}
+
+
+/// Precision score, fraction of predicted positives that are correct
+///
+/// # Arguments
+///
+/// * `y_true` - True class labels (column of labels or one hot encoded).
+/// * `y_pred` - Predicted output of model.
+/// * `average` - Averaging strategy for multiple classes.
+///
+pub fn precision(y_true: &Array2<f64>, y_pred: &Array2<f64>, average: Average) -> f64 {
+    averaged(y_true, y_pred, average, |tp, fp, _| ratio(tp, tp + fp))
+}
+
+
+/// Recall score, fraction of true positives that are predicted
+///
+/// # Arguments
+///
+/// * `y_true` - True class labels (column of labels or one hot encoded).
+/// * `y_pred` - Predicted output of model.
+/// * `average` - Averaging strategy for multiple classes.
+///
+pub fn recall(y_true: &Array2<f64>, y_pred: &Array2<f64>, average: Average) -> f64 {
+    averaged(y_true, y_pred, average, |tp, _, fn_| ratio(tp, tp + fn_))
+}
+
+
+/// F1 score, harmonic mean of precision and recall
+///
+/// # Arguments
+///
+/// * `y_true` - True class labels (column of labels or one hot encoded).
+/// * `y_pred` - Predicted output of model.
+/// * `average` - Averaging strategy for multiple classes.
+///
+pub fn f1_score(y_true: &Array2<f64>, y_pred: &Array2<f64>, average: Average) -> f64 {
+    averaged(y_true, y_pred, average, |tp, fp, fn_| {
+        f1(ratio(tp, tp + fp), ratio(tp, tp + fn_))
+    })
+}
+
+
+/// Cross entropy between true labels and predicted probabilities.
+/// Binary models provide a single column with probability of the positive class,
+/// multi class models provide the full `(n, k)` probability distribution.
+///
+/// # Arguments
+///
+/// * `y_true` - True class labels (column of labels or one hot encoded).
+/// * `y_prob` - Predicted probabilities of model.
+///
+pub fn log_loss(y_true: &Array2<f64>, y_prob: &Array2<f64>) -> f64 {
+
+    let epsilon = 1e-15;
+    let truth = true_labels(y_true);
+
+    if truth.len() != y_prob.nrows() {
+        panic!(
+            "True and predicted values must have same number of samples {:?} != {:?}",
+            truth.len(), y_prob.nrows()
+        );
+    }
+
+    let total: f64 = match y_prob.ncols() {
+        1 => {
+            truth.iter()
+                .zip(y_prob.column(0).iter())
+                .map(|(&t, &p)| {
+                    let p = p.clamp(epsilon, 1.0 - epsilon);
+                    if t == 1 { -p.ln() } else { -(1.0 - p).ln() }
+                })
+                .sum()
+        },
+        _ => {
+            truth.iter()
+                .zip(y_prob.axis_iter(Axis(0)))
+                .map(|(&t, row)| {
+                    let sum = row.sum();
+                    -(row[t] / sum).clamp(epsilon, 1.0).ln()
+                })
+                .sum()
+        }
+    };
+
+    total / truth.len() as f64
+}
+
+
+/// Binary labels and positive class scores sorted by decreasing score,
+/// NaN scores are ranked above every other score rather than panicking
+fn ranked_scores(y_true: &Array2<f64>, y_score: &Array2<f64>) -> Vec<(f64, bool)> {
+
+    if y_score.ncols() != 1 {
+        panic!("Scores must be a single column of positive class probabilities");
+    }
+
+    let truth = true_labels(y_true);
+    if truth.len() != y_score.nrows() {
+        panic!(
+            "True and predicted values must have same number of samples {:?} != {:?}",
+            truth.len(), y_score.nrows()
+        );
+    }
+
+    let mut ranked: Vec<(f64, bool)> = y_score
+        .column(0)
+        .iter()
+        .zip(truth.iter())
+        .map(|(&score, &t)| (score, t == 1))
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranked
+}
+
+
+/// Cumulative true and false positive counts at each distinct score threshold
+fn threshold_counts(ranked: &[(f64, bool)]) -> Vec<(f64, f64, f64)> {
+
+    let mut counts = Vec::new();
+    let (mut tp, mut fp) = (0.0, 0.0);
+
+    for (idx, (score, positive)) in ranked.iter().enumerate() {
+        if *positive { tp += 1.0 } else { fp += 1.0 }
+        let last = idx + 1 == ranked.len() || ranked[idx + 1].0 != *score;
+        if last {
+            counts.push((*score, tp, fp));
+        }
+    }
+    counts
+}
+
+
+/// Receiver operating characteristic (ROC) curve for binary classification.
+/// Returns false positive rates, true positive rates and the decreasing thresholds used.
+///
+/// # Arguments
+///
+/// * `y_true` - True binary labels.
+/// * `y_score` - Probability of positive class predicted by model.
+///
+pub fn roc_curve(
+    y_true: &Array2<f64>,
+    y_score: &Array2<f64>) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+
+    let counts = threshold_counts(&ranked_scores(y_true, y_score));
+    let (_, positives, negatives) = *counts.last().unwrap();
+
+    let mut fpr = vec![0.0];
+    let mut tpr = vec![0.0];
+    let mut thresholds = vec![f64::INFINITY];
+
+    for (score, tp, fp) in counts {
+        fpr.push(ratio(fp, negatives));
+        tpr.push(ratio(tp, positives));
+        thresholds.push(score);
+    }
+
+    (fpr, tpr, thresholds)
+}
+
+
+/// Area under the ROC curve using the trapezoidal rule
+///
+/// # Arguments
+///
+/// * `y_true` - True binary labels.
+/// * `y_score` - Probability of positive class predicted by model.
+///
+pub fn roc_auc(y_true: &Array2<f64>, y_score: &Array2<f64>) -> f64 {
+    let (fpr, tpr, _) = roc_curve(y_true, y_score);
+    fpr.windows(2)
+        .zip(tpr.windows(2))
+        .map(|(x, y)| (x[1] - x[0]) * (y[1] + y[0]) / 2.0)
+        .sum()
+}
+
+
+/// Precision recall curve for binary classification.
+/// Returns precision, recall and the decreasing thresholds used.
+///
+/// # Arguments
+///
+/// * `y_true` - True binary labels.
+/// * `y_score` - Probability of positive class predicted by model.
+///
+pub fn precision_recall_curve(
+    y_true: &Array2<f64>,
+    y_score: &Array2<f64>) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+
+    let counts = threshold_counts(&ranked_scores(y_true, y_score));
+    let (_, positives, _) = *counts.last().unwrap();
+
+    let mut precisions = Vec::new();
+    let mut recalls = Vec::new();
+    let mut thresholds = Vec::new();
+
+    for (score, tp, fp) in counts {
+        precisions.push(ratio(tp, tp + fp));
+        recalls.push(ratio(tp, positives));
+        thresholds.push(score);
+    }
+
+    (precisions, recalls, thresholds)
+}
+
+
+/// Area under the precision recall curve, computed as average precision
+/// (precision at each threshold weighted by the increase in recall).
+///
+/// # Arguments
+///
+/// * `y_true` - True binary labels.
+/// * `y_score` - Probability of positive class predicted by model.
+///
+pub fn pr_auc(y_true: &Array2<f64>, y_score: &Array2<f64>) -> f64 {
+    let (precisions, recalls, _) = precision_recall_curve(y_true, y_score);
+    let mut prev_recall = 0.0;
+    let mut area = 0.0;
+    for (p, r) in precisions.iter().zip(recalls.iter()) {
+        area += (r - prev_recall) * p;
+        prev_recall = *r;
+    }
+    area
+}
+
+
+#[cfg(test)]
+mod classification_metrics_test {
+
+    use ndarray::{arr2, concatenate};
+    use crate::metrics::classification::*;
+
+    fn round(val: f64) -> f64 {
+        (val * 10000.0).round() / 10000.0
+    }
+
+    #[test]
+    fn test_binary_metrics() {
+
+        let y_true = arr2(&[[0.0], [1.0], [1.0], [0.0], [1.0], [1.0]]);
+        let y_prob = arr2(&[[0.2], [0.7], [0.4], [0.6], [0.9], [0.8]]);
+
+        assert_eq!(round(accuracy(&y_true, &y_prob)), 0.6667);
+        assert_eq!(
+            confusion_matrix(&y_true, &y_prob),
+            arr2(&[[1.0, 1.0], [1.0, 3.0]])
+        );
+
+        assert_eq!(precision(&y_true, &y_prob, Average::Binary), 0.75);
+        assert_eq!(recall(&y_true, &y_prob, Average::Binary), 0.75);
+        assert_eq!(f1_score(&y_true, &y_prob, Average::Binary), 0.75);
+        assert_eq!(round(precision(&y_true, &y_prob, Average::Macro)), 0.625);
+        assert_eq!(round(f1_score(&y_true, &y_prob, Average::Micro)), 0.6667);
+
+        assert_eq!(round(log_loss(&y_true, &y_prob)), 0.4568);
+        assert_eq!(round(roc_auc(&y_true, &y_prob)), 0.875);
+        assert_eq!(round(pr_auc(&y_true, &y_prob)), 0.95);
+
+        let (fpr, tpr, thresholds) = roc_curve(&y_true, &y_prob);
+        assert_eq!(fpr, vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 1.0]);
+        assert_eq!(tpr, vec![0.0, 0.25, 0.5, 0.75, 0.75, 1.0, 1.0]);
+        assert_eq!(thresholds[1], 0.9);
+
+        // NaN scores rank first instead of panicking the sort
+        let y_nan = arr2(&[[0.2], [0.7], [0.4], [f64::NAN], [0.9], [0.8]]);
+        let (fpr, tpr, thresholds) = roc_curve(&y_true, &y_nan);
+        assert!(thresholds[1].is_nan());
+        assert_eq!(fpr[1], 0.5);
+        assert_eq!(tpr[1], 0.0);
+        assert!(roc_auc(&y_true, &y_nan).is_finite());
+
+        // probabilities of both classes and 2 class one hot rows decode by argmax
+        let y_proba = y_prob.mapv(|p| 1.0 - p);
+        let y_proba = concatenate![Axis(1), y_proba, y_prob];
+        assert_eq!(accuracy(&y_true, &y_proba), accuracy(&y_true, &y_prob));
+
+        let y_onehot = arr2(&[[1.0, 0.0], [0.0, 1.0], [1.0, 0.0], [0.0, 1.0], [0.0, 1.0], [0.0, 1.0]]);
+        assert_eq!(predicted_labels(&y_onehot), vec![0, 1, 0, 1, 1, 1]);
+        assert_eq!(round(accuracy(&y_true, &y_onehot)), 0.6667);
+    }
+
+    #[test]
+    fn test_multi_class_metrics() {
+
+        let y_true = arr2(&[
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0]
+        ]);
+
+        // one hot class predictions
+        let y_pred = arr2(&[
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0]
+        ]);
+
+        assert_eq!(round(accuracy(&y_true, &y_pred)), 0.6667);
+        assert_eq!(
+            confusion_matrix(&y_true, &y_pred),
+            arr2(&[
+                [1.0, 1.0, 0.0],
+                [0.0, 2.0, 0.0],
+                [1.0, 0.0, 1.0]
+            ])
+        );
+
+        assert_eq!(round(precision(&y_true, &y_pred, Average::Macro)), 0.7222);
+        assert_eq!(round(recall(&y_true, &y_pred, Average::Macro)), 0.6667);
+        assert_eq!(round(f1_score(&y_true, &y_pred, Average::Macro)), 0.6556);
+        assert_eq!(round(precision(&y_true, &y_pred, Average::Micro)), 0.6667);
+
+        let y_prob = arr2(&[
+            [0.8, 0.1, 0.1],
+            [0.5, 0.4, 0.1],
+            [0.2, 0.7, 0.1],
+            [0.1, 0.8, 0.1],
+            [0.1, 0.1, 0.8],
+            [0.2, 0.2, 0.6]
+        ]);
+
+        assert_eq!(accuracy(&y_true, &y_prob), 1.0);
+        assert_eq!(round(log_loss(&y_true, &y_prob)), 0.3717);
+    }
+
+}
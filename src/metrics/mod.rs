@@ -0,0 +1,36 @@
+//! # Evaluation metrics
+//!
+//! This module provides metrics for measuring how well a trained model performs.
+//! Metrics take the true labels and the predictions of a model, both as `Array2<f64>`
+//! in the same shape that `Model::predict` returns for the model being evaluated.
+//! Every metric returning a single score matches the `MetricFn` signature, 
+//! so it can be passed to validation while training a model.
+//!
+//! ## Features
+//! - **Regression**: R², mean absolute error, root mean squared error and mean absolute percentage error.
+//! - **Classification**: Accuracy, precision, recall, F1, confusion matrix, log loss, ROC and precision recall curves.
+//!
+//! ## Label Shapes
+//!
+//! Classification metrics decode labels from the shapes produced by `Logistic`:
+//!
+//! - **True labels**: A single column of class labels, or a one hot encoded matrix of `(n, k)`.
+//! - **Predictions**: A single column of probabilities or labels (rounded to the nearest class),
+//!   or a matrix of class probabilities, scores or one hot rows with 2 or more columns
+//!   (class with highest score is predicted). For multi class `Logistic` models pass the output
+//!   of `predict_proba` or `predict_class`, `predict` returns the `(class, probability)` of each row.
+//!
+//! ```rust
+//! use ndarray::arr2;
+//! use dendritic::metrics::prelude::*;
+//!
+//! let y_true = arr2(&[[0.0], [0.0], [1.0], [1.0]]);
+//! let y_prob = arr2(&[[0.1], [0.6], [0.8], [0.9]]);
+//!
+//! println!("Accuracy: {:?}", accuracy(&y_true, &y_prob));
+//! println!("F1: {:?}", f1_score(&y_true, &y_prob, Average::Binary));
+//! println!("AUC: {:?}", roc_auc(&y_true, &y_prob));
+//! ```
+pub mod regression;
+pub mod classification;
+pub mod prelude;
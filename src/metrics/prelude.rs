@@ -0,0 +1,2 @@
+pub use super::regression::*;
+pub use super::classification::*;
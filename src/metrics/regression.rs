@@ -0,0 +1,142 @@
+//! Metrics for regression models
+
+use ndarray::{Array2, Axis};
+
+
+/// Validate that true and predicted values have matching shapes
+fn check_shapes(y_true: &Array2<f64>, y_pred: &Array2<f64>) {
+    if y_true.shape() != y_pred.shape() {
+        panic!(
+            "True and predicted values must have same shape {:?} != {:?}",
+            y_true.shape(), y_pred.shape()
+        );
+    }
+
+    if y_true.is_empty() {
+        panic!("Metrics require at least one sample");
+    }
+}
+
+
+/// Coefficient of determination (R²).
+/// For multiple targets the score of each column is averaged.
+///
+/// # Arguments
+///
+/// * `y_true` - True target values.
+/// * `y_pred` - Predicted values of model.
+///
+pub fn r2_score(y_true: &Array2<f64>, y_pred: &Array2<f64>) -> f64 {
+
+    check_shapes(y_true, y_pred);
+
+    let scores: Vec<f64> = y_true
+        .axis_iter(Axis(1))
+        .zip(y_pred.axis_iter(Axis(1)))
+        .map(|(col_true, col_pred)| {
+            let mean = col_true.mean().unwrap();
+            let ss_res: f64 = col_true
+                .iter()
+                .zip(col_pred.iter())
+                .map(|(t, p)| (t - p).powi(2))
+                .sum();
+            let ss_tot: f64 = col_true.iter().map(|t| (t - mean).powi(2)).sum();
+
+            match (ss_res == 0.0, ss_tot == 0.0) {
+                (true, _) => 1.0,
+                (false, true) => 0.0,
+                _ => 1.0 - ss_res / ss_tot
+            }
+        })
+        .collect();
+
+    scores.iter().sum::<f64>() / scores.len() as f64
+}
+
+
+/// Mean absolute error (MAE) over all values
+///
+/// # Arguments
+///
+/// * `y_true` - True target values.
+/// * `y_pred` - Predicted values of model.
+///
+pub fn mean_absolute_error(y_true: &Array2<f64>, y_pred: &Array2<f64>) -> f64 {
+    check_shapes(y_true, y_pred);
+    (y_true - y_pred).mapv(f64::abs).mean().unwrap()
+}
+
+
+/// Root mean squared error (RMSE) over all values
+///
+/// # Arguments
+///
+/// * `y_true` - True target values.
+/// * `y_pred` - Predicted values of model.
+///
+pub fn root_mean_squared_error(y_true: &Array2<f64>, y_pred: &Array2<f64>) -> f64 {
+    check_shapes(y_true, y_pred);
+    (y_true - y_pred).mapv(|x| x * x).mean().unwrap().sqrt()
+}
+
+
+/// Mean absolute percentage error (MAPE) over all values, returned as a fraction.
+/// True values of zero are clamped to machine epsilon to avoid division by zero.
+///
+/// # Arguments
+///
+/// * `y_true` - True target values.
+/// * `y_pred` - Predicted values of model.
+///
+pub fn mean_absolute_percentage_error(y_true: &Array2<f64>, y_pred: &Array2<f64>) -> f64 {
+
+    check_shapes(y_true, y_pred);
+
+    let total: f64 = y_true
+        .iter()
+        .zip(y_pred.iter())
+        .map(|(t, p)| (t - p).abs() / t.abs().max(f64::EPSILON))
+        .sum();
+
+    total / y_true.len() as f64
+}
+
+
+#[cfg(test)]
+mod regression_metrics_test {
+
+    use ndarray::arr2;
+    use crate::metrics::regression::*;
+
+    #[test]
+    fn test_regression_metrics() {
+
+        let y_true = arr2(&[[3.0], [-0.5], [2.0], [7.0]]);
+        let y_pred = arr2(&[[2.5], [0.0], [2.0], [8.0]]);
+
+        let r2 = r2_score(&y_true, &y_pred);
+        assert_eq!((r2 * 10000.0).round() / 10000.0, 0.9486);
+        assert_eq!(mean_absolute_error(&y_true, &y_pred), 0.5);
+        assert_eq!(
+            root_mean_squared_error(&y_true, &y_pred),
+            0.375_f64.sqrt()
+        );
+
+        let mape = mean_absolute_percentage_error(&y_true, &y_pred);
+        assert_eq!((mape * 10000.0).round() / 10000.0, 0.3274);
+
+        assert_eq!(r2_score(&y_true, &y_true), 1.0);
+        assert_eq!(mean_absolute_error(&y_true, &y_true), 0.0);
+    }
+
+    #[test]
+    fn test_multi_target_r2() {
+
+        let y_true = arr2(&[[0.5, 1.0], [-1.0, 1.0], [7.0, -6.0]]);
+        let y_pred = arr2(&[[0.0, 2.0], [-1.0, 2.0], [8.0, -5.0]]);
+
+        let r2 = r2_score(&y_true, &y_pred);
+        assert_eq!((r2 * 10000.0).round() / 10000.0, 0.9368);
+    }
+
+}
@@ -10,6 +10,11 @@
 //! - **Model/Optimizer Abstractions**: Contains interfaces for creating models and optimizers. 
 //! - **Train**: Utilities for training loops for single datasets or batches of data with optimizers.
 //! - **Regression**: Suite of linear & logistic regression models.
+//! - **Generalized Linear Models**: Poisson, gamma & tweedie deviance with log, identity or inverse links.
+//! - **Linear SVM**: Hinge & squared hinge loss classifier with L2 regularization, one vs rest for multiple classes.
+//! - **Regularizers**: L1, L2, elastic net, group lasso & max norm penalties that attach to any model.
+//! - **Sparse Solvers**: Proximal gradient (ISTA/FISTA) & coordinate descent solvers with regularization paths for `Lasso` & `Elastic`.
+//! - **Direct Solvers**: Normal equation, QR & Cholesky solvers with an optional ridge term for `SGD` & `Ridge`.
 //! - **Registry**: Operation registry for managing and looking up operations. 
 //! - **Default Operations**: Suite of default arithmetic, activation, and loss functions.
 //!
@@ -166,12 +171,17 @@
 //!     let output = loaded.predict(&x1);
 //!
 //!     println!("Class Predictions: {:?}", output);
+//!
+//!     // Remove saved parameters
+//!     std::fs::remove_dir_all("data/logistic")?;
+//!     std::fs::remove_dir_all("data/multiclass_logistic")?;
 //!     Ok(())
 //! }
 //! ```
 
 pub mod model;
 pub mod train;
+pub mod regularizer;
 pub mod regression;
 pub mod optimizers;
 pub mod prelude; 
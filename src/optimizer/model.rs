@@ -2,6 +2,7 @@ use ndarray::{Array2};
 
 use crate::autodiff::graph::{ComputationGraph};
 use crate::autodiff::prelude::Operation;  
+use crate::optimizer::regularizer::Regularizer;
 
 /// Model trait for sharing reusable optimizer logic
 pub trait Model {
@@ -42,9 +43,99 @@ pub trait Model {
     /// Update all parameters in model (without optimizer)
     fn update_parameters(&mut self);
 
-    /// Update specific parameter index in computation graph
-    fn update_parameter(&mut self, idx: usize, val: Array2<f64>);
+    /// Update specific parameter index in computation graph,
+    /// `step` holds the step size taken for each entry and scales the proximal operator of the regularizer
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>);
 
+    /// Regularizer applied to weights of model
+    fn regularizer(&self) -> Option<&dyn Regularizer>;
+
+    /// Attach regularizer to weights of model (replaces existing penalty)
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>);
+
+    /// Weights of each sample (row) in the loss, `None` for models created without sample weights
+    fn sample_weight(&self) -> Option<Array2<f64>>;
+
+    /// Set weights of each sample (row) loaded in the model.
+    /// Setting outputs resets sample weights to uniform, so call this after `set_output`.
+    fn set_sample_weight(&mut self, weights: &Array2<f64>);
+
+}
+
+
+/// Options for fitting linear models
+#[derive(Debug, Clone)]
+pub struct ModelOptions {
+
+    /// Learn bias added after weights multiplication (bias stays at zero when disabled)
+    pub fit_intercept: bool,
+
+    /// Weight of each sample (row) in the loss, of shape `(n_samples, 1)`
+    pub sample_weight: Option<Array2<f64>>
+}
+
+
+impl Default for ModelOptions {
+
+    fn default() -> Self {
+        ModelOptions {
+            fit_intercept: true,
+            sample_weight: None
+        }
+    }
+}
+
+
+impl ModelOptions {
+
+    /// Validate sample weights against number of samples, returns weights as a column
+    pub fn sample_weight_column(&self, rows: usize) -> Result<Option<Array2<f64>>, String> {
+
+        let weights = match &self.sample_weight {
+            Some(weights) => weights,
+            None => return Ok(None)
+        };
+
+        if weights.len() != rows {
+            return Err(format!(
+                "Sample weights of length {} do not match number of samples {}",
+                weights.len(), rows
+            ));
+        }
+
+        if weights.iter().any(|w| *w < 0.0 || !w.is_finite()) {
+            return Err("Sample weights must be finite and non negative".to_string());
+        }
+
+        if weights.sum() <= 0.0 {
+            return Err("Sample weights must have a positive sum".to_string());
+        }
+
+        Ok(Some(weights.to_shape((rows, 1)).unwrap().to_owned()))
+    }
+}
+
+
+/// Models saved before the intercept option existed always fit a bias
+pub fn default_fit_intercept() -> bool {
+    true
+}
+
+
+/// Error for missing values (NaN) in training data, which would turn the loss into NaN
+pub fn check_missing(x: &Array2<f64>, y: &Array2<f64>) -> Result<(), String> {
+    if x.iter().chain(y.iter()).any(|v| v.is_nan()) {
+        return Err(
+            "Training data contains missing values (NaN), impute them first (see `SimpleImputer`)".to_string()
+        );
+    }
+    Ok(())
+}
+
+
+/// Index of the sample weight node when the loss function of a graph is weighted
+pub fn sample_weight_node(graph: &ComputationGraph<Array2<f64>>) -> Option<usize> {
+    graph.curr_node().inputs().get(2).copied()
 }
 
 /// Model serialization trait for saving and loading model parameters
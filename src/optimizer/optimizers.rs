@@ -29,7 +29,8 @@ impl Optimizer for DefaultOptimizer {
             let parameter = model.graph().node(param);
             let grad = parameter.grad() * self.alpha;
             let delta = parameter.output() - grad;
-            model.update_parameter(param, delta);
+            let step = Array2::from_elem(delta.dim(), self.alpha);
+            model.update_parameter(param, delta, &step);
         }
     }
 
@@ -53,16 +54,21 @@ impl Nesterov {
 
     /// Nesterov optimization technique with default parameters initialized
     pub fn default(model: &SGD) -> Self {
+        Nesterov::new(model, model.learning_rate)
+    }
+
+    /// Nesterov optimizer for any model with learning rate
+    pub fn new<M: Model>(model: &M, alpha: f64) -> Self {
 
         let mut velocity_vector: Vec<Array2<f64>> = Vec::new();
-        for param in model.graph.parameters() {
-            let parameter_node = model.graph.node(param);
+        for param in model.graph().parameters() {
+            let parameter_node = model.graph().node(param);
             let parameter_shape = parameter_node.output().dim();
             velocity_vector.push(Array2::zeros(parameter_shape));
         }
 
         Nesterov {
-            alpha: model.learning_rate,
+            alpha,
             beta: 0.9,
             v: velocity_vector
         }
@@ -78,13 +84,14 @@ impl Optimizer for Nesterov {
         let params = model.graph().parameters();
         for (idx, param) in params.into_iter().enumerate() {
             let parameter = model.graph().node(param);
+            let step = Array2::from_elem(parameter.output().dim(), self.alpha);
             let lookahead = parameter.output() - (self.beta * self.v[idx].clone());
-            model.update_parameter(param, lookahead);
+            model.update_parameter(param, lookahead, &step);
 
             let grad = parameter.grad() * self.alpha;
             self.v[idx] = grad + (self.beta * self.v[idx].clone());
             let new_param = parameter.output() - self.v[idx].clone();
-            model.update_parameter(param, new_param);
+            model.update_parameter(param, new_param, &step);
         }
     }
 
@@ -111,16 +118,21 @@ impl Adagrad {
 
     /// Default adagrad constructor using SGD
     pub fn default(model: &SGD) -> Self {
+        Adagrad::new(model, model.learning_rate)
+    }
+
+    /// Adagrad optimizer for any model with learning rate
+    pub fn new<M: Model>(model: &M, alpha: f64) -> Self {
 
         let mut s_vector: Vec<Array2<f64>> = Vec::new();
-        for param in model.graph.parameters() {
-            let parameter_node = model.graph.node(param);
+        for param in model.graph().parameters() {
+            let parameter_node = model.graph().node(param);
             let parameter_shape = parameter_node.output().dim();
             s_vector.push(Array2::zeros(parameter_shape));
         }
 
         Adagrad {
-            alpha: model.learning_rate,
+            alpha,
             epsilon: 1e-8,
             s: s_vector
         }
@@ -141,8 +153,8 @@ impl Optimizer for Adagrad {
             self.s[idx] += &grad_squared;
 
             let ada = self.alpha / (self.s[idx].mapv(f64::sqrt) + self.epsilon);
-            let param_new = parameter.output() - (ada * grad); 
-            model.update_parameter(param, param_new);
+            let param_new = parameter.output() - (&ada * grad); 
+            model.update_parameter(param, param_new, &ada);
 
         }
     }
@@ -172,16 +184,21 @@ impl RMSProp {
 
     /// Default RMSProp method using gradient descent
     pub fn default(model: &SGD) -> Self {
+        RMSProp::new(model, model.learning_rate)
+    }
+
+    /// RMSProp optimizer for any model with learning rate
+    pub fn new<M: Model>(model: &M, alpha: f64) -> Self {
 
         let mut s_vector: Vec<Array2<f64>> = Vec::new();
-        for param in model.graph.parameters() {
-            let parameter_node = model.graph.node(param);
+        for param in model.graph().parameters() {
+            let parameter_node = model.graph().node(param);
             let parameter_shape = parameter_node.output().dim();
             s_vector.push(Array2::zeros(parameter_shape));
         }
 
         RMSProp {
-            alpha: model.learning_rate,
+            alpha,
             epsilon: 1e-8,
             decay_rate: 0.9,
             s: s_vector
@@ -203,8 +220,8 @@ impl Optimizer for RMSProp {
 
             self.s[idx] = self.decay_rate * self.s[idx].clone() + (1.0 - self.decay_rate) * grad_squared;
             let rms = self.alpha / (self.s[idx].mapv(f64::sqrt) + self.epsilon);
-            let new = parameter.output() - (rms * grad); 
-            model.update_parameter(param, new);
+            let new = parameter.output() - (&rms * grad); 
+            model.update_parameter(param, new, &rms);
         }
     }
 
@@ -238,11 +255,16 @@ impl Adadelta {
 
     /// Default Adadelta method using gradient descent
     pub fn default(model: &SGD) -> Self {
+        Adadelta::new(model)
+    }
+
+    /// Adadelta optimizer for any model (learning rate is adapted per parameter)
+    pub fn new<M: Model>(model: &M) -> Self {
 
         let mut s_vector: Vec<Array2<f64>> = Vec::new();
         let mut u_vector: Vec<Array2<f64>> = Vec::new();
-        for param in model.graph.parameters() {
-            let parameter_node = model.graph.node(param);
+        for param in model.graph().parameters() {
+            let parameter_node = model.graph().node(param);
             let parameter_shape = parameter_node.output().dim();
             s_vector.push(Array2::zeros(parameter_shape)); 
             u_vector.push(Array2::zeros(parameter_shape));
@@ -270,10 +292,11 @@ impl Optimizer for Adadelta {
             let grad_squared = grad.mapv(|x| x * x);
             
             self.s[idx] = self.y_s * self.s[idx].clone() + (1.0 - self.y_s) * grad_squared;
-            let delta = ((self.u[idx].mapv(f64::sqrt) + self.epsilon) / (self.s[idx].mapv(f64::sqrt) + self.epsilon)) * grad;
+            let rate = (self.u[idx].mapv(f64::sqrt) + self.epsilon) / (self.s[idx].mapv(f64::sqrt) + self.epsilon);
+            let delta = &rate * grad;
             self.u[idx] = self.y_x * self.u[idx].clone() + (1.0 - self.y_x) * delta.mapv(|x| x * x); 
             let new = parameter.output() + (delta * -1.0);
-            model.update_parameter(param, new); 
+            model.update_parameter(param, new, &rate); 
         }
     }
 }
@@ -310,9 +333,14 @@ impl Adam {
 
     /// Default Adam optimizer using gradient descent
     pub fn default(model: &SGD) -> Self {
+        Adam::new(model, model.learning_rate)
+    }
+
+    /// Adam optimizer for any model with learning rate
+    pub fn new<M: Model>(model: &M, alpha: f64) -> Self {
 
         let mut obj = Adam {
-            alpha: model.learning_rate,
+            alpha,
             epsilon: 1e-6,
             y_v: 0.9,
             y_s: 0.999,
@@ -322,8 +350,8 @@ impl Adam {
         };
 
         obj.parameter_momentum_init(
-            model.graph.parameters(), 
-            model.graph.nodes()
+            model.graph().parameters(), 
+            model.graph().nodes()
         );
         obj
 
@@ -391,8 +419,9 @@ impl Optimizer for Adam {
 
             let v_hat = self.v_delta[idx].clone() / (1.0 - self.y_v.powf(self.k as f64)); 
             let s_hat = self.s_delta[idx].clone() / (1.0 - self.y_s.powf(self.k as f64)); 
-            let param_delta = self.alpha * v_hat / (s_hat.mapv(f64::sqrt) + self.epsilon); 
-            model.update_parameter(param, parameter.output() - param_delta);  
+            let step = self.alpha / (s_hat.mapv(f64::sqrt) + self.epsilon);
+            let param_delta = &step * v_hat; 
+            model.update_parameter(param, parameter.output() - param_delta, &step);  
         }
     }
 
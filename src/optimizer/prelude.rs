@@ -1,7 +1,12 @@
 pub use super::model::*;
 pub use super::train::*;
+pub use super::regularizer::*;
 pub use super::optimizers::*;
 pub use super::regression::sgd::*;
 pub use super::regression::elastic::*;
 pub use super::regression::lasso::*;
 pub use super::regression::ridge::*;
+pub use super::regression::sparse::*;
+pub use super::regression::closed_form::*;
+pub use super::regression::svm::*;
+pub use super::regression::glm::*;
@@ -0,0 +1,408 @@
+//! Closed form & direct solvers for linear and ridge regression
+//!
+//! Solvers compute the exact minimizer of the objective that the update rules of `SGD` & `Ridge` descend,
+//! `1/2 * ||Xw + b - y||^2 + lambda * ||w||^2`, where the bias is not penalized.
+//! The ridge term is read from the L2 penalty attached to the model (`Ridge`, or `SGD` with `set_regularizer`),
+//! penalties with an L1 part or constraints can't be solved directly and return an error.
+//! Features & targets are centered so the bias is recovered from their means,
+//! models created with sample weights or without an intercept are solved with the same options.
+//! Solutions are written to the weight (node 1) & bias (node 3) parameters of the model graph,
+//! so they can be used as an exact baseline or as a warm start for iterative training.
+
+use ndarray::{concatenate, s, Array2, Axis};
+
+use crate::optimizer::model::*;
+use crate::optimizer::regularizer::Regularizer;
+use crate::optimizer::regression::sgd::*;
+use crate::optimizer::regression::ridge::*;
+use crate::optimizer::regression::sparse::{center, reduce};
+
+
+/// Direct solver used to fit linear models
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DirectSolver {
+
+    /// Solve the normal equations `(X^T X + 2 * lambda * I) w = X^T y` with gaussian elimination
+    NormalEquation,
+
+    /// Householder QR decomposition of the (augmented) feature matrix, avoids forming `X^T X`
+    Qr,
+
+    /// Cholesky decomposition of the normal equations, requires a positive definite system
+    Cholesky
+}
+
+
+/// Direct solvers for linear models
+pub trait DirectFit {
+
+    /// Replace weights & bias of model with the exact least squares solution
+    ///
+    /// # Arguments
+    ///
+    /// * `solver` - Solver used to compute the solution.
+    ///
+    fn fit_direct(&mut self, solver: DirectSolver) -> Result<(), String>;
+
+}
+
+
+/// Compute weights & bias of a linear model with a direct solver.
+/// Returns weights of shape `(features, targets)` and bias of shape `(1, targets)`.
+///
+/// # Arguments
+///
+/// * `x` - Input features.
+/// * `y` - Target values.
+/// * `lambda` - Strength of ridge term (0.0 for ordinary least squares).
+/// * `solver` - Solver used to compute the solution.
+///
+pub fn solve_direct(
+    x: &Array2<f64>,
+    y: &Array2<f64>,
+    lambda: f64,
+    solver: DirectSolver) -> Result<(Array2<f64>, Array2<f64>), String> {
+
+    if x.nrows() != y.nrows() {
+        return Err(format!(
+            "Number of samples in features ({}) and targets ({}) must match",
+            x.nrows(), y.nrows()
+        ));
+    }
+
+    if lambda < 0.0 {
+        return Err("Ridge term lambda must be non negative".to_string());
+    }
+
+    let (xc, yc, x_mean, y_mean) = center(x, y);
+    let weights = solve_reduced(&xc, &yc, lambda, solver)?;
+    let bias = (&y_mean - &x_mean.dot(&weights)).insert_axis(Axis(0));
+    Ok((weights, bias))
+}
+
+
+/// Solve a problem without bias (see `reduce`) with a direct solver
+fn solve_reduced(
+    xc: &Array2<f64>,
+    yc: &Array2<f64>,
+    lambda: f64,
+    solver: DirectSolver) -> Result<Array2<f64>, String> {
+
+    let ridge = Array2::<f64>::eye(xc.ncols()) * (2.0 * lambda);
+
+    match solver {
+        DirectSolver::NormalEquation => {
+            gaussian_elimination(xc.t().dot(xc) + ridge, xc.t().dot(yc))
+        },
+        DirectSolver::Cholesky => {
+            cholesky(xc.t().dot(xc) + ridge, xc.t().dot(yc))
+        },
+        DirectSolver::Qr => {
+            let augmented_x = concatenate![Axis(0), xc.view(), ridge.mapv(f64::sqrt).view()];
+            let augmented_y = concatenate![
+                Axis(0), yc.view(), Array2::zeros((xc.ncols(), yc.ncols())).view()
+            ];
+            householder_qr(augmented_x, augmented_y)
+        }
+    }
+}
+
+
+/// Threshold below which a pivot is treated as zero, relative to the scale of the system
+fn pivot_tolerance(a: &Array2<f64>) -> f64 {
+    let scale = a.iter().fold(0.0, |max: f64, val| max.max(val.abs()));
+    scale.max(1.0) * 1e-12
+}
+
+
+/// Solve `a * w = b` with gaussian elimination & partial pivoting
+fn gaussian_elimination(
+    mut a: Array2<f64>,
+    mut b: Array2<f64>) -> Result<Array2<f64>, String> {
+
+    let n = a.nrows();
+    let tol = pivot_tolerance(&a);
+
+    for col in 0..n {
+
+        let pivot = (col..n)
+            .max_by(|i, j| a[[*i, col]].abs().total_cmp(&a[[*j, col]].abs()))
+            .unwrap();
+
+        if a[[pivot, col]].abs() < tol {
+            return Err(
+                "Normal equations are singular, features are linearly dependent (add a ridge term)".to_string()
+            );
+        }
+
+        if pivot != col {
+            for k in 0..n {
+                a.swap([col, k], [pivot, k]);
+            }
+            for k in 0..b.ncols() {
+                b.swap([col, k], [pivot, k]);
+            }
+        }
+
+        for row in col + 1..n {
+            let factor = a[[row, col]] / a[[col, col]];
+            if factor == 0.0 {
+                continue;
+            }
+            let pivot_row = a.row(col).to_owned();
+            a.row_mut(row).scaled_add(-factor, &pivot_row);
+            let pivot_target = b.row(col).to_owned();
+            b.row_mut(row).scaled_add(-factor, &pivot_target);
+        }
+    }
+
+    Ok(back_substitution(&a, &b))
+}
+
+
+/// Solve `a * w = b` with the cholesky decomposition `a = L L^T`
+fn cholesky(a: Array2<f64>, b: Array2<f64>) -> Result<Array2<f64>, String> {
+
+    let n = a.nrows();
+    let tol = pivot_tolerance(&a);
+    let mut l: Array2<f64> = Array2::zeros((n, n));
+
+    for j in 0..n {
+        let diag = a[[j, j]] - l.slice(s![j, ..j]).dot(&l.slice(s![j, ..j]));
+        if diag <= tol {
+            return Err(
+                "Normal equations are not positive definite, features are linearly dependent (add a ridge term)".to_string()
+            );
+        }
+        l[[j, j]] = diag.sqrt();
+
+        for i in j + 1..n {
+            let dot = l.slice(s![i, ..j]).dot(&l.slice(s![j, ..j]));
+            l[[i, j]] = (a[[i, j]] - dot) / l[[j, j]];
+        }
+    }
+
+    // forward substitution L z = b
+    let mut z = b;
+    for i in 0..n {
+        for k in 0..i {
+            let factor = l[[i, k]];
+            let row = z.row(k).to_owned();
+            z.row_mut(i).scaled_add(-factor, &row);
+        }
+        let diag = l[[i, i]];
+        z.row_mut(i).mapv_inplace(|val| val / diag);
+    }
+
+    Ok(back_substitution(&l.t().to_owned(), &z))
+}
+
+
+/// Solve least squares `min ||a * w - b||` with householder reflections
+fn householder_qr(
+    mut a: Array2<f64>,
+    mut b: Array2<f64>) -> Result<Array2<f64>, String> {
+
+    let (m, n) = a.dim();
+    if m < n {
+        return Err(format!(
+            "QR requires at least as many samples ({m}) as features ({n}) without a ridge term"
+        ));
+    }
+
+    let tol = pivot_tolerance(&a);
+
+    for col in 0..n {
+
+        let mut v = a.slice(s![col.., col]).to_owned();
+        let norm = v.dot(&v).sqrt();
+        if norm < tol {
+            return Err(
+                "Feature matrix is rank deficient, features are linearly dependent (add a ridge term)".to_string()
+            );
+        }
+
+        let alpha = if v[0] > 0.0 { -norm } else { norm };
+        v[0] -= alpha;
+        let v_norm = v.dot(&v);
+        if v_norm == 0.0 {
+            continue;
+        }
+
+        // apply H = I - 2 v v^T / (v^T v) to remaining columns & targets
+        for mut column in a.slice_mut(s![col.., col..]).axis_iter_mut(Axis(1)) {
+            let factor = 2.0 * v.dot(&column) / v_norm;
+            column.scaled_add(-factor, &v);
+        }
+        for mut column in b.slice_mut(s![col.., ..]).axis_iter_mut(Axis(1)) {
+            let factor = 2.0 * v.dot(&column) / v_norm;
+            column.scaled_add(-factor, &v);
+        }
+    }
+
+    let r = a.slice(s![..n, ..]).to_owned();
+    let qt_b = b.slice(s![..n, ..]).to_owned();
+    Ok(back_substitution(&r, &qt_b))
+}
+
+
+/// Solve upper triangular system `r * w = b`
+fn back_substitution(r: &Array2<f64>, b: &Array2<f64>) -> Array2<f64> {
+
+    let n = r.nrows();
+    let mut w = b.clone();
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            let factor = r[[i, k]];
+            let row = w.row(k).to_owned();
+            w.row_mut(i).scaled_add(-factor, &row);
+        }
+        let diag = r[[i, i]];
+        w.row_mut(i).mapv_inplace(|val| val / diag);
+    }
+    w
+}
+
+
+/// Ridge term of the penalty attached to a model (no penalty solves least squares)
+fn ridge_term(regularizer: Option<&dyn Regularizer>) -> Result<f64, String> {
+    match regularizer {
+        None => Ok(0.0),
+        Some(regularizer) => match regularizer.elastic_net() {
+            Some((lambda, 0.0)) => Ok(0.5 * lambda),
+            _ => Err(format!(
+                "Direct solvers solve L2 penalties, model penalty is {:?}", regularizer
+            ))
+        }
+    }
+}
+
+
+/// Write direct solution to parameters of linear model graph (weights node 1, bias node 3)
+macro_rules! direct_fit {
+
+    ($t:ty, $model:ident => $sgd:expr) => {
+
+        impl DirectFit for $t {
+
+            fn fit_direct(&mut self, solver: DirectSolver) -> Result<(), String> {
+
+                let lambda = ridge_term(self.regularizer())?;
+                let $model = self;
+                let sgd: &mut SGD = &mut $sgd;
+                let (x, y) = (sgd.input(), sgd.output());
+                if lambda < 0.0 {
+                    return Err("Ridge term lambda must be non negative".to_string());
+                }
+
+                let loss = sgd.graph.curr_node().operation();
+                if !loss.is_squared_error() {
+                    return Err(format!(
+                        "Direct solvers minimize squared error, model loss is {:?}", loss
+                    ));
+                }
+
+                let sample_weight = sgd.sample_weight();
+                let (xr, yr, x_mean, y_mean) = reduce(
+                    &x, &y, sample_weight.as_ref(), sgd.fit_intercept
+                );
+                let weights = solve_reduced(&xr, &yr, lambda, solver)?;
+                let bias = (&y_mean - &x_mean.dot(&weights)).insert_axis(Axis(0));
+
+                if weights.dim() != sgd.graph.node(1).output().dim() {
+                    return Err(format!(
+                        "Solution of shape {:?} does not match weights of model", weights.dim()
+                    ));
+                }
+
+                // exact solution is written as is, without the proximal step of iterative updates
+                sgd.graph.mut_node_output(1, weights);
+                sgd.graph.mut_node_output(3, bias);
+                sgd.forward();
+                Ok(())
+            }
+
+        }
+
+    }
+
+}
+
+
+direct_fit!(SGD, model => *model);
+direct_fit!(Ridge, model => model.sgd);
+
+
+#[cfg(test)]
+mod closed_form_test {
+
+    use ndarray::arr2;
+    use crate::optimizer::regression::closed_form::*;
+
+    fn load_data() -> (Array2<f64>, Array2<f64>) {
+
+        let x = arr2(&[
+            [1.0, 0.5, 2.0],
+            [2.0, -1.0, 0.0],
+            [3.0, 0.0, 1.0],
+            [4.0, 1.5, -1.0],
+            [5.0, -0.5, 3.0],
+            [6.0, 1.0, 2.0]
+        ]);
+
+        // two targets with exact linear relationships
+        let y1 = x.column(0).mapv(|v| 3.0 * v) - x.column(2).mapv(|v| 2.0 * v) + 1.0;
+        let y2 = x.column(1).mapv(|v| 0.5 * v) - 4.0;
+        let y = ndarray::stack![Axis(1), y1, y2];
+        (x, y)
+    }
+
+    fn max_diff(a: &Array2<f64>, b: &Array2<f64>) -> f64 {
+        (a - b).mapv(f64::abs).fold(0.0, |max: f64, val| max.max(*val))
+    }
+
+    #[test]
+    fn test_solvers_agree() {
+
+        let (x, y) = load_data();
+        let expected_w = arr2(&[[3.0, 0.0], [0.0, 0.5], [-2.0, 0.0]]);
+        let expected_b = arr2(&[[1.0, -4.0]]);
+
+        for solver in [DirectSolver::NormalEquation, DirectSolver::Qr, DirectSolver::Cholesky] {
+            let (w, b) = solve_direct(&x, &y, 0.0, solver).unwrap();
+            assert!(max_diff(&w, &expected_w) < 1e-9);
+            assert!(max_diff(&b, &expected_b) < 1e-9);
+        }
+
+        // ridge solutions agree and satisfy the stationary condition
+        let lambda = 1.5;
+        let (w, b) = solve_direct(&x, &y, lambda, DirectSolver::NormalEquation).unwrap();
+        for solver in [DirectSolver::Qr, DirectSolver::Cholesky] {
+            let (w_other, b_other) = solve_direct(&x, &y, lambda, solver).unwrap();
+            assert!(max_diff(&w, &w_other) < 1e-9);
+            assert!(max_diff(&b, &b_other) < 1e-9);
+        }
+
+        let residual = x.dot(&w) + &b - &y;
+        let grad_w = x.t().dot(&residual) + 2.0 * lambda * &w;
+        assert!(grad_w.mapv(f64::abs).sum() < 1e-9);
+        assert!(residual.sum_axis(Axis(0)).mapv(f64::abs).sum() < 1e-9);
+    }
+
+    #[test]
+    fn test_singular_system() {
+
+        // second feature is a multiple of the first
+        let x = arr2(&[[1.0, 2.0], [2.0, 4.0], [3.0, 6.0], [4.0, 8.0]]);
+        let y = arr2(&[[1.0], [2.0], [3.0], [4.0]]);
+
+        for solver in [DirectSolver::NormalEquation, DirectSolver::Qr, DirectSolver::Cholesky] {
+            assert!(solve_direct(&x, &y, 0.0, solver).is_err());
+            assert!(solve_direct(&x, &y, 0.1, solver).is_ok());
+        }
+
+        assert!(solve_direct(&x, &y, -1.0, DirectSolver::Qr).is_err());
+        assert!(solve_direct(&x, &arr2(&[[1.0]]), 0.0, DirectSolver::Qr).is_err());
+    }
+
+}
@@ -6,7 +6,7 @@ use std::io::{Write, BufWriter, BufReader};
 
 use uuid::Uuid;
 use chrono::{Datelike, Utc};  
-use ndarray::{Array2};
+use ndarray::{Array1, Array2};
 use serde::{Serialize, Deserialize}; 
 
 use crate::autodiff::operations::base::Operation; 
@@ -14,19 +14,15 @@ use crate::autodiff::graph::{ComputationGraph, GraphSerialize};
 
 use crate::optimizer::model::*; 
 use crate::optimizer::regression::sgd::*;
+use crate::optimizer::regularizer::*;
 
 
 /// Elastic regression model instance
 pub struct Elastic {
     
-    /// Instance of linear regression structure
-    pub sgd: SGD,
-
-    /// lambda parameter to regularize weights
-    pub lambda: f64,
-
-    /// alpha mixing value for regularization
-    pub alpha: f64
+    /// Instance of linear regression structure, lambda & alpha are read
+    /// from its attached penalty (`lambda`, `alpha`, `set_lambda`, `set_alpha`)
+    pub sgd: SGD
 }
 
 /// Serialization structure for elastic model instance
@@ -84,6 +80,9 @@ impl Elastic {
     ///     let output = loaded_model.predict(&x);
     ///     println!("Predictions: {:?}", output); 
     ///
+    ///     // Remove saved parameters
+    ///     std::fs::remove_dir_all("data/linear").unwrap();
+    ///
     /// }
 
     /// ```
@@ -94,11 +93,67 @@ impl Elastic {
         lambda: f64,
         alpha: f64) -> Result<Self, String> {
 
-        Ok(Self {
-            sgd: SGD::new(x, y, learning_rate).unwrap(),
-            lambda: lambda,
-            alpha: alpha
-        })
+        Self::with_options(x, y, learning_rate, lambda, alpha, ModelOptions::default())
+    }
+
+    /// Create instance of model with intercept & sample weight options (see `SGD::with_options`).
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `lambda` - Regularization strength.
+    /// * `alpha` - Mixing of L1 (1.0) and L2 (0.0) penalties.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn with_options(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        lambda: f64,
+        alpha: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        let mut sgd = SGD::with_options(x, y, learning_rate, options)?;
+        sgd.set_regularizer(Box::new(ElasticNet::new(lambda, alpha)));
+
+        Ok(Self { sgd })
+    }
+
+    /// Strength & mixing of the attached penalty written as an elastic net,
+    /// follows penalties attached with `set_regularizer`
+    fn elastic_net(&self) -> (f64, f64) {
+        self.sgd
+            .regularizer()
+            .and_then(|regularizer| regularizer.elastic_net())
+            .unwrap_or((0.0, 0.0))
+    }
+
+    /// Regularization strength of the attached penalty
+    /// (0 for penalties outside of the elastic net family)
+    pub fn lambda(&self) -> f64 {
+        self.elastic_net().0
+    }
+
+    /// Mixing of L1 (1.0) and L2 (0.0) penalties of the attached penalty
+    pub fn alpha(&self) -> f64 {
+        self.elastic_net().1
+    }
+
+    /// Set regularization strength of model, keeping the mixing of the attached penalty
+    pub fn set_lambda(&mut self, lambda: f64) {
+        self.sgd.set_regularizer(Box::new(ElasticNet::new(lambda, self.alpha())));
+    }
+
+    /// Set mixing of L1 & L2 penalties of model, keeping the strength of the attached penalty
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.sgd.set_regularizer(Box::new(ElasticNet::new(self.lambda(), alpha)));
+    }
+
+    /// Mean squared error of each target column from the last forward pass
+    pub fn target_loss(&self) -> Array1<f64> {
+        self.sgd.target_loss()
     }
 }
 
@@ -130,7 +185,7 @@ impl Model for Elastic {
     }
 
     fn backward(&mut self) {
-        self.sgd.graph.backward();
+        self.sgd.backward();
     }
 
     fn predicted(&self) -> Array2<f64> {
@@ -142,14 +197,7 @@ impl Model for Elastic {
     }
 
     fn loss(&mut self) -> f64 {
-
-        let loss_node = self.sgd.graph.curr_node();
-        let loss = loss_node.output();
-        let weights = self.sgd.graph.node(1).output();
-        let l1 = weights.mapv(|x| x.abs()).sum();
-        let l2 = weights.mapv(|x| (x as f64).powf(2.0)).sum();
-        let exp = self.alpha * l1 + 0.5 * (1.0 - self.alpha) * l2;
-        loss.as_slice().unwrap()[0] + (self.lambda * exp)    
+        self.sgd.loss()
     }
 
 
@@ -158,22 +206,27 @@ impl Model for Elastic {
     }
 
     fn update_parameters(&mut self) {
+        self.sgd.update_parameters();
+    }
+
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>) {
+        self.sgd.update_parameter(idx, val, step);
+    }
+
+    fn regularizer(&self) -> Option<&dyn Regularizer> {
+        self.sgd.regularizer()
+    }
+
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>) {
+        self.sgd.set_regularizer(regularizer);
+    }
 
-        let lr = self.sgd.learning_rate;
-        let w = self.sgd.graph.node(1);
-        let sig_w = self.sgd.graph.node(1).output().mapv(|x| x.signum());
-        let w_grad = self.lambda * (self.alpha * sig_w) + ((1.0 - self.alpha) * w.grad()); 
-        let w_new = w.output() - (w_grad * lr);
-        self.sgd.graph.mut_node_output(1, w_new); 
-
-        let b = self.sgd.graph.node(3);
-        let b_grad = b.grad() * lr;
-        let b_delta = b.output() - b_grad;
-        self.sgd.graph.mut_node_output(3, b_delta);
+    fn sample_weight(&self) -> Option<Array2<f64>> {
+        self.sgd.sample_weight()
     }
 
-    fn update_parameter(&mut self, idx: usize, val: Array2<f64>) {
-        self.sgd.update_parameter(idx, val);
+    fn set_sample_weight(&mut self, weights: &Array2<f64>) {
+        self.sgd.set_sample_weight(weights);
     }
 
 }
@@ -191,10 +244,12 @@ impl ModelSerialize for Elastic {
                 graph_path: format!("{filepath}/regression_exp"),
                 weight_dim: self.sgd.weight_dim,
                 bias_dim: self.sgd.bias_dim,
-                learning_rate: self.sgd.learning_rate
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
             },
-            lambda: self.lambda,
-            alpha: self.alpha
+            lambda: self.lambda(),
+            alpha: self.alpha()
         };
 
         let _ = self.sgd.graph.save(&obj.sgd.graph_path); 
@@ -224,10 +279,12 @@ impl ModelSerialize for Elastic {
                 graph_path: format!("{namespace}/regression_exp"),
                 weight_dim: self.sgd.weight_dim,
                 bias_dim: self.sgd.bias_dim,
-                learning_rate: self.sgd.learning_rate
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
             },
-            lambda: self.lambda,
-            alpha: self.alpha
+            lambda: self.lambda(),
+            alpha: self.alpha()
         };
 
         let _ = self.sgd.graph.save(&obj.sgd.graph_path); 
@@ -251,14 +308,12 @@ impl ModelSerialize for Elastic {
             graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
             weight_dim: obj.sgd.weight_dim,
             bias_dim: obj.sgd.bias_dim,
-            learning_rate: obj.sgd.learning_rate
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(ElasticNet::new(obj.lambda, obj.alpha)))),
+            fit_intercept: obj.sgd.fit_intercept
         };
 
-        Ok(Elastic {
-            sgd: sgd,
-            lambda: obj.lambda,
-            alpha: obj.alpha
-        }) 
+        Ok(Elastic { sgd }) 
     }
  
     fn load_snapshot(
@@ -282,14 +337,12 @@ impl ModelSerialize for Elastic {
             graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
             weight_dim: obj.sgd.weight_dim,
             bias_dim: obj.sgd.bias_dim,
-            learning_rate: obj.sgd.learning_rate
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(ElasticNet::new(obj.lambda, obj.alpha)))),
+            fit_intercept: obj.sgd.fit_intercept
         };
 
-        Ok(Elastic {
-            sgd: sgd,
-            lambda: obj.lambda,
-            alpha: obj.alpha
-        })
+        Ok(Elastic { sgd })
     }
 
 }
@@ -0,0 +1,601 @@
+//! Generalized linear model (GLM) implementation
+
+use std::fs;
+use std::fs::File;
+use std::io::{Write, BufWriter, BufReader};
+
+use uuid::Uuid;
+use chrono::{Datelike, Utc};
+use ndarray::{Array2, Axis};
+use serde::{Serialize, Deserialize};
+
+use crate::autodiff::operations::arithmetic::*;
+use crate::autodiff::operations::loss::*;
+use crate::autodiff::operations::activation::*;
+use crate::autodiff::operations::base::Operation;
+use crate::autodiff::graph::{ComputationGraph, GraphConstruction, GraphSerialize};
+
+use crate::optimizer::model::*;
+use crate::optimizer::regularizer::Regularizer;
+
+
+/// Distribution of targets, selects the deviance minimized by the model
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Family {
+
+    /// Non negative counts
+    Poisson,
+
+    /// Positive continuous values with variance growing with the mean squared
+    Gamma,
+
+    /// Tweedie distribution with variance power (0 normal, 1 poisson, between 1 & 2 compound poisson gamma, 2 gamma)
+    Tweedie(f64)
+}
+
+/// Link between the mean of targets & linear predictor `g(mu) = x * w + b`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Link {
+
+    /// `ln(mu)`, predicted means are always positive
+    Log,
+
+    /// `mu`
+    Identity,
+
+    /// `1 / mu`
+    Inverse
+}
+
+
+impl Family {
+
+    /// Variance power of the family
+    pub fn power(&self) -> f64 {
+        match self {
+            Family::Poisson => 1.0,
+            Family::Gamma => 2.0,
+            Family::Tweedie(power) => *power
+        }
+    }
+
+    /// Deviance loss operation of the family
+    pub fn loss(&self) -> Box<dyn Operation<Array2<f64>>> {
+        match self {
+            Family::Poisson => Box::new(PoissonDeviance),
+            Family::Gamma => Box::new(GammaDeviance),
+            Family::Tweedie(power) => Box::new(TweedieDeviance::new(*power))
+        }
+    }
+
+    /// Check targets are in the support of the distribution
+    fn validate(&self, y: &Array2<f64>) -> Result<(), String> {
+
+        let power = self.power();
+        if power.is_nan() || (power != 0.0 && power < 1.0) {
+            return Err(format!(
+                "Tweedie power must be 0 or at least 1, got {power}"
+            ));
+        }
+
+        if power >= 1.0 && y.iter().any(|val| *val < 0.0) {
+            return Err(format!("{:?} targets must be non negative", self));
+        }
+
+        if power >= 2.0 && y.iter().any(|val| *val <= 0.0) {
+            return Err(format!("{:?} targets must be positive", self));
+        }
+
+        Ok(())
+    }
+}
+
+
+impl Link {
+
+    /// Apply link function to a mean
+    pub fn link(&self, mu: f64) -> f64 {
+        match self {
+            Link::Log => mu.ln(),
+            Link::Identity => mu,
+            Link::Inverse => 1.0 / mu
+        }
+    }
+
+    /// Apply inverse link function to a linear predictor
+    pub fn inverse(&self, eta: f64) -> f64 {
+        match self {
+            Link::Log => eta.exp(),
+            Link::Identity => eta,
+            Link::Inverse => 1.0 / eta
+        }
+    }
+}
+
+
+/// Generalized linear model, linear predictor mapped to the mean of targets
+/// by an inverse link function & trained on the deviance of a distribution family.
+pub struct GLM {
+
+    /// Underlying computation graph with operations for optimizer
+    pub graph: ComputationGraph<Array2<f64>>,
+
+    /// Coefficients associated with each feature
+    pub weight_dim: (usize, usize),
+
+    /// Bias to add after weights multiplication
+    pub bias_dim: (usize, usize),
+
+    /// Learning rate to control how fast to decrease
+    pub learning_rate: f64,
+
+    /// Distribution family of targets
+    pub family: Family,
+
+    /// Link function of the mean
+    pub link: Link,
+
+    /// Penalty applied to weights
+    pub regularizer: Option<Box<dyn Regularizer>>,
+
+    /// Learn bias added after weights multiplication
+    pub fit_intercept: bool
+}
+
+
+/// Serialization structure for generalized linear models
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GLMSerialize {
+
+    /// Underlying computation graph with operations for optimizer
+    pub graph_path: String,
+
+    /// Coefficients associated with each feature
+    pub weight_dim: (usize, usize),
+
+    /// Bias to add after weights multiplication
+    pub bias_dim: (usize, usize),
+
+    /// Learning rate to control how fast to decrease
+    pub learning_rate: f64,
+
+    /// Distribution family of targets
+    pub family: Family,
+
+    /// Link function of the mean
+    pub link: Link,
+
+    /// Penalty applied to weights
+    #[serde(default)]
+    pub regularizer: Option<Box<dyn Regularizer>>,
+
+    /// Learn bias added after weights multiplication
+    #[serde(default = "default_fit_intercept")]
+    pub fit_intercept: bool
+}
+
+
+impl GLM {
+
+
+    /// Create instance of generalized linear model.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `family` - Distribution family of the targets.
+    /// * `link` - Link function of the mean.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::optimizer::prelude::*;
+    ///
+    ///
+    /// fn main() {
+    ///
+    ///     let x = arr2(&[[0.0], [0.5], [1.0], [1.5], [2.0]]);
+    ///     let y = arr2(&[[1.0], [2.0], [3.0], [4.0], [8.0]]);
+    ///
+    ///     // counts with a log link
+    ///     let mut model = GLM::new(&x, &y, Family::Poisson, Link::Log, 0.01).unwrap();
+    ///
+    ///     // Save model train and save results
+    ///     model.train(1000);
+    ///     model.save("data/poisson_glm").unwrap();
+    ///
+    ///     // Load model and predict means
+    ///     let mut loaded_model = GLM::load("data/poisson_glm").unwrap();
+    ///     let output = loaded_model.predict(&x);
+    ///     println!("Predictions: {:?}", output);
+    ///
+    ///     // Remove saved parameters
+    ///     std::fs::remove_dir_all("data/poisson_glm").unwrap();
+    ///
+    /// }
+    /// ```
+    pub fn new(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        family: Family,
+        link: Link,
+        learning_rate: f64) -> Result<Self, String> {
+
+        GLM::with_options(x, y, family, link, learning_rate, ModelOptions::default())
+    }
+
+    /// Create instance of poisson regression with a log link.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Non negative counts for training.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    ///
+    pub fn poisson(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64) -> Result<Self, String> {
+
+        GLM::new(x, y, Family::Poisson, Link::Log, learning_rate)
+    }
+
+    /// Create instance of gamma regression with a log link.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Positive targets for training.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    ///
+    pub fn gamma(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64) -> Result<Self, String> {
+
+        GLM::new(x, y, Family::Gamma, Link::Log, learning_rate)
+    }
+
+    /// Create instance of tweedie regression with a log link.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Targets for training.
+    /// * `power` - Variance power of the tweedie distribution.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    ///
+    pub fn tweedie(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        power: f64,
+        learning_rate: f64) -> Result<Self, String> {
+
+        GLM::new(x, y, Family::Tweedie(power), Link::Log, learning_rate)
+    }
+
+    /// Create instance of generalized linear model with intercept & sample weight options.
+    /// The intercept starts at the link of the mean of targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `family` - Distribution family of the targets.
+    /// * `link` - Link function of the mean.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn with_options(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        family: Family,
+        link: Link,
+        learning_rate: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        if !(0.0..=1.0).contains(&learning_rate) {
+            return Err(
+                "Learning rate must be between 0 and 1".to_string()
+            );
+        }
+
+        if x.nrows() != y.nrows() {
+            return Err(
+                "Number of samples in inputs and targets must match".to_string()
+            );
+        }
+
+        if y.ncols() == 0 {
+            return Err(
+                "Targets must have at least one column".to_string()
+            );
+        }
+
+        if link == Link::Inverse && !options.fit_intercept {
+            return Err(
+                "Inverse link requires an intercept".to_string()
+            );
+        }
+
+        check_missing(x, y)?;
+        family.validate(y)?;
+        let sample_weight = options.sample_weight_column(x.nrows())?;
+
+        let mut glm = GLM {
+            graph: ComputationGraph::new(),
+            weight_dim: (x.shape()[1], y.ncols()),
+            bias_dim: (1, y.ncols()),
+            learning_rate,
+            family,
+            link,
+            regularizer: None,
+            fit_intercept: options.fit_intercept
+        };
+
+        let bias = match glm.fit_intercept {
+            true => y
+                .mean_axis(Axis(0))
+                .unwrap()
+                .mapv(|mean| link.link(mean.max(f64::EPSILON)))
+                .insert_axis(Axis(0)),
+            false => Array2::zeros(glm.bias_dim)
+        };
+
+        glm.graph.mul(vec![x.clone(), Array2::zeros(glm.weight_dim)]);
+        glm.graph.add(vec![bias]);
+
+        match link {
+            Link::Log => glm.graph.exp(),
+            Link::Identity => glm.graph.identity(),
+            Link::Inverse => glm.graph.reciprocal()
+        };
+
+        match sample_weight {
+            Some(weights) => glm.graph.weighted(y.clone(), weights, family.loss()),
+            None => glm.graph.unary(y.clone(), family.loss())
+        };
+
+        glm.graph.add_parameter(1);
+        if glm.fit_intercept {
+            glm.graph.add_parameter(3);
+        }
+        Ok(glm)
+    }
+
+    /// Number of target columns predicted by model
+    pub fn n_targets(&self) -> usize {
+        self.weight_dim.1
+    }
+
+    /// Linear predictor `x * w + b` from the last forward pass
+    pub fn linear_predictor(&self) -> Array2<f64> {
+        self.graph.node(4).output()
+    }
+
+}
+
+
+impl Model for GLM {
+
+    fn input(&self) -> Array2<f64> {
+        self.graph.node(0).output()
+    }
+
+    fn output(&self) -> Array2<f64> {
+        self.graph.node(6).output()
+    }
+
+    fn set_input(&mut self, x: &Array2<f64>) {
+        self.graph.mut_node_output(0, x.to_owned());
+    }
+
+    fn set_output(&mut self, y: &Array2<f64>) {
+        self.graph.mut_node_output(6, y.to_owned());
+        if let Some(idx) = sample_weight_node(&self.graph) {
+            self.graph.mut_node_output(idx, Array2::ones((y.nrows(), 1)));
+        }
+    }
+
+    fn graph(&self) -> &ComputationGraph<Array2<f64>> {
+        &self.graph
+    }
+
+    fn forward(&mut self) {
+        self.graph.forward();
+    }
+
+    fn backward(&mut self) {
+        self.graph.backward();
+        if let Some(regularizer) = &self.regularizer {
+            let w = self.graph.node(1);
+            let w_grad = w.grad() + regularizer.gradient(&w.output());
+            self.graph.nodes[1].set_grad_output(w_grad);
+        }
+    }
+
+    fn predicted(&self) -> Array2<f64> {
+        self.graph.node(5).output()
+    }
+
+    fn predict(&mut self, x: &Array2<f64>) -> Array2<f64> {
+        self.set_output(&Array2::ones((x.nrows(), self.n_targets())));
+        self.set_input(x);
+        self.graph.forward();
+        self.predicted()
+    }
+
+    fn loss(&mut self) -> f64 {
+        let loss_node = self.graph.curr_node();
+        let loss = loss_node.output().as_slice().unwrap()[0];
+        match &self.regularizer {
+            Some(regularizer) => loss + regularizer.penalty(&self.graph.node(1).output()),
+            None => loss
+        }
+    }
+
+    fn set_loss(&mut self, op: Box<dyn Operation<Array2<f64>>>) {
+        let idx = self.graph.nodes().len() - 1;
+        self.graph.nodes[idx].set_operation(op);
+    }
+
+    fn update_parameters(&mut self) {
+
+        let w = self.graph.node(1);
+        let w_grad = w.grad() * self.learning_rate;
+        let w_delta = w.output() - w_grad;
+        let step = Array2::from_elem(w_delta.dim(), self.learning_rate);
+        self.update_parameter(1, w_delta, &step);
+
+        if self.fit_intercept {
+            let b = self.graph.node(3);
+            let b_grad = b.grad() * self.learning_rate;
+            let b_delta = b.output() - b_grad;
+            self.graph.mut_node_output(3, b_delta);
+        }
+    }
+
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>) {
+        let val = match (idx, &self.regularizer) {
+            (1, Some(regularizer)) => regularizer.proximal(&val, step),
+            _ => val
+        };
+        self.graph.mut_node_output(idx, val);
+    }
+
+    fn regularizer(&self) -> Option<&dyn Regularizer> {
+        self.regularizer.as_deref()
+    }
+
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>) {
+        self.regularizer = Some(regularizer);
+    }
+
+    fn sample_weight(&self) -> Option<Array2<f64>> {
+        sample_weight_node(&self.graph).map(|idx| self.graph.node(idx).output())
+    }
+
+    fn set_sample_weight(&mut self, weights: &Array2<f64>) {
+        match sample_weight_node(&self.graph) {
+            Some(idx) => {
+                let column = weights.to_shape((weights.len(), 1)).unwrap().to_owned();
+                self.graph.mut_node_output(idx, column);
+            },
+            None => panic!("Model was created without sample weights")
+        }
+    }
+
+}
+
+
+impl ModelSerialize for GLM {
+
+    fn save(&self, filepath: &str) -> std::io::Result<()> {
+
+        fs::create_dir_all(filepath)?;
+        let file_path = format!("{filepath}/parameters.json");
+
+        let obj = GLMSerialize {
+            graph_path: format!("{filepath}/regression_exp"),
+            weight_dim: self.weight_dim,
+            bias_dim: self.bias_dim,
+            learning_rate: self.learning_rate,
+            family: self.family,
+            link: self.link,
+            regularizer: self.regularizer.clone(),
+            fit_intercept: self.fit_intercept
+        };
+
+        let _ = self.graph.save(&obj.graph_path);
+        let file = File::create(&file_path)?;
+        let mut writer = BufWriter::new(file);
+        let json_string = serde_json::to_string_pretty(&obj)?;
+        writer.write_all(json_string.as_bytes())?;
+        Ok(())
+    }
+
+    fn save_snapshot(&self, namespace: &str) -> std::io::Result<()> {
+
+        let now = Utc::now();
+        let (_, year) = now.year_ce();
+        let month = now.month().to_string();
+        let day = now.day().to_string();
+        let curr_year = year.to_string();
+
+        let directory_path = format!("{namespace}/snapshot/{curr_year}/{month}/{day}");
+        fs::create_dir_all(directory_path.clone())?;
+
+        let id = Uuid::new_v4();
+        let file_path = format!("{directory_path}/{id}.json");
+
+        let obj = GLMSerialize {
+            graph_path: format!("{namespace}/regression_exp"),
+            weight_dim: self.weight_dim,
+            bias_dim: self.bias_dim,
+            learning_rate: self.learning_rate,
+            family: self.family,
+            link: self.link,
+            regularizer: self.regularizer.clone(),
+            fit_intercept: self.fit_intercept
+        };
+
+        let _ = self.graph.save(&obj.graph_path);
+        let file = File::create(&file_path)?;
+        let mut writer = BufWriter::new(file);
+        let json_string = serde_json::to_string_pretty(&obj)?;
+        writer.write_all(json_string.as_bytes())?;
+        Ok(())
+    }
+
+    fn load(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+
+        let parameter_path = format!("{filepath}/parameters.json");
+        let obj: GLMSerialize = {
+            let file = File::open(&parameter_path)?;
+            let reader = BufReader::new(file);
+            serde_json::from_reader(reader)?
+        };
+
+        Ok(GLM {
+            graph: ComputationGraph::load(&obj.graph_path).unwrap(),
+            weight_dim: obj.weight_dim,
+            bias_dim: obj.bias_dim,
+            learning_rate: obj.learning_rate,
+            family: obj.family,
+            link: obj.link,
+            regularizer: obj.regularizer,
+            fit_intercept: obj.fit_intercept
+        })
+    }
+
+    fn load_snapshot(
+        namespace: &str,
+        year: &str,
+        month: &str,
+        day: &str,
+        snapshot_id: &str) -> Result<Self, Box<dyn std::error::Error>> {
+
+        let parameter_path = format!(
+            "{namespace}/snapshot/{year}/{month}/{day}/{snapshot_id}.json"
+        );
+
+        let obj: GLMSerialize = {
+            let file = File::open(&parameter_path)?;
+            let reader = BufReader::new(file);
+            serde_json::from_reader(reader)?
+        };
+
+        Ok(GLM {
+            graph: ComputationGraph::load(&obj.graph_path).unwrap(),
+            weight_dim: obj.weight_dim,
+            bias_dim: obj.bias_dim,
+            learning_rate: obj.learning_rate,
+            family: obj.family,
+            link: obj.link,
+            regularizer: obj.regularizer,
+            fit_intercept: obj.fit_intercept
+        })
+    }
+
+}
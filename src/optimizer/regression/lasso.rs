@@ -6,7 +6,7 @@ use std::io::{Write, BufWriter, BufReader};
 
 use uuid::Uuid;
 use chrono::{Datelike, Utc};  
-use ndarray::{Array2};
+use ndarray::{Array1, Array2};
 use serde::{Serialize, Deserialize}; 
 
 use crate::autodiff::operations::base::Operation; 
@@ -14,16 +14,15 @@ use crate::autodiff::graph::{ComputationGraph, GraphSerialize};
 
 use crate::optimizer::model::*; 
 use crate::optimizer::regression::sgd::*;
+use crate::optimizer::regularizer::*;
 
 
 /// Lasso regression
 pub struct Lasso {
     
-    /// Instance of linear regression structure
-    pub sgd: SGD,
-
-    /// lambda parameter to regularize weights
-    pub lambda: f64
+    /// Instance of linear regression structure,
+    /// lambda is read from its attached penalty (`lambda`, `set_lambda`)
+    pub sgd: SGD
 }
 
 /// Serialization structure for lasso regression
@@ -72,13 +71,16 @@ impl Lasso {
     ///     
     ///     // Save model train and save results
     ///     model.train(1000);
-    ///     model.save("lasso").unwrap();
+    ///     model.save("data/lasso").unwrap();
     ///     
     ///     // Load model and make predictions
-    ///     let mut loaded_model = Lasso::load("lasso").unwrap();
+    ///     let mut loaded_model = Lasso::load("data/lasso").unwrap();
     ///     let output = loaded_model.predict(&x);
     ///     println!("Predictions: {:?}", output); 
     ///
+    ///     // Remove saved parameters
+    ///     std::fs::remove_dir_all("data/lasso").unwrap();
+    ///
     /// }
     /// ```
     pub fn new(
@@ -87,12 +89,53 @@ impl Lasso {
         learning_rate: f64, 
         lambda: f64) -> Result<Self, String> {
 
-        Ok(Self {
-            sgd: SGD::new(x, y, learning_rate).unwrap(),
-            lambda: lambda
-        })
+        Self::with_options(x, y, learning_rate, lambda, ModelOptions::default())
     }
 
+    /// Create instance of model with intercept & sample weight options (see `SGD::with_options`).
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `lambda` - Regularization strength.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn with_options(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        lambda: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        let mut sgd = SGD::with_options(x, y, learning_rate, options)?;
+        sgd.set_regularizer(Box::new(L1::new(lambda)));
+
+        Ok(Self { sgd })
+    }
+
+
+    /// Regularization strength of the attached penalty, follows penalties attached with
+    /// `set_regularizer` (0 for penalties outside of the elastic net family)
+    pub fn lambda(&self) -> f64 {
+        self.sgd
+            .regularizer()
+            .and_then(|regularizer| regularizer.elastic_net())
+            .map_or(0.0, |(lambda, _)| lambda)
+    }
+
+
+    /// Set regularization strength of model by attaching an L1 penalty
+    pub fn set_lambda(&mut self, lambda: f64) {
+        self.sgd.set_regularizer(Box::new(L1::new(lambda)));
+    }
+
+
+    /// Mean squared error of each target column from the last forward pass
+    pub fn target_loss(&self) -> Array1<f64> {
+        self.sgd.target_loss()
+    }
 }
 
 
@@ -123,7 +166,7 @@ impl Model for Lasso {
     }
 
     fn backward(&mut self) {
-        self.sgd.graph.backward();
+        self.sgd.backward();
     }
 
     fn predicted(&self) -> Array2<f64> {
@@ -135,13 +178,7 @@ impl Model for Lasso {
     }
 
     fn loss(&mut self) -> f64 {
-        
-        let loss_node = self.sgd.graph.curr_node();
-        let loss = loss_node.output();
-        let weights = self.sgd.graph.node(1).output();
-        let l1 = weights.mapv(|x| x.abs()).sum();
-        let loss_val = loss.clone() + (self.lambda * l1);
-        loss_val.as_slice().unwrap()[0]
+        self.sgd.loss()
     }
 
     fn set_loss(&mut self, op: Box<dyn Operation<Array2<f64>>>) {
@@ -149,22 +186,27 @@ impl Model for Lasso {
     }
 
     fn update_parameters(&mut self) {
+        self.sgd.update_parameters();
+    }
+
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>) {
+        self.sgd.update_parameter(idx, val, step);
+    }
+
+    fn regularizer(&self) -> Option<&dyn Regularizer> {
+        self.sgd.regularizer()
+    }
+
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>) {
+        self.sgd.set_regularizer(regularizer);
+    }
 
-        let lr = self.sgd.learning_rate;
-        let w = self.sgd.graph.node(1);
-        let sig_w = self.sgd.graph.node(1).output().mapv(|x| x.signum());
-        let w_grad = w.clone().grad() + (self.lambda * sig_w);
-        let w_new = w.output() - (w_grad * lr);
-        self.sgd.graph.mut_node_output(1, w_new); 
-
-        let b = self.sgd.graph.node(3);
-        let b_grad = b.grad() * lr;
-        let b_delta = b.output() - b_grad;
-        self.sgd.graph.mut_node_output(3, b_delta);  
+    fn sample_weight(&self) -> Option<Array2<f64>> {
+        self.sgd.sample_weight()
     }
 
-    fn update_parameter(&mut self, idx: usize, val: Array2<f64>) {
-        self.sgd.update_parameter(idx, val);
+    fn set_sample_weight(&mut self, weights: &Array2<f64>) {
+        self.sgd.set_sample_weight(weights);
     }
 
 }
@@ -182,9 +224,11 @@ impl ModelSerialize for Lasso {
                 graph_path: format!("{filepath}/regression_exp"),
                 weight_dim: self.sgd.weight_dim,
                 bias_dim: self.sgd.bias_dim,
-                learning_rate: self.sgd.learning_rate
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
             },
-            lambda: self.lambda
+            lambda: self.lambda()
         };
 
         let _ = self.sgd.graph.save(&obj.sgd.graph_path); 
@@ -214,9 +258,11 @@ impl ModelSerialize for Lasso {
                 graph_path: format!("{namespace}/regression_exp"),
                 weight_dim: self.sgd.weight_dim,
                 bias_dim: self.sgd.bias_dim,
-                learning_rate: self.sgd.learning_rate
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
             },
-            lambda: self.lambda
+            lambda: self.lambda()
         };
 
         let _ = self.sgd.graph.save(&obj.sgd.graph_path); 
@@ -240,13 +286,12 @@ impl ModelSerialize for Lasso {
             graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
             weight_dim: obj.sgd.weight_dim,
             bias_dim: obj.sgd.bias_dim,
-            learning_rate: obj.sgd.learning_rate
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(L1::new(obj.lambda)))),
+            fit_intercept: obj.sgd.fit_intercept
         };
 
-        Ok(Lasso {
-            sgd: sgd,
-            lambda: obj.lambda
-        }) 
+        Ok(Lasso { sgd }) 
     }
  
     fn load_snapshot(
@@ -270,13 +315,12 @@ impl ModelSerialize for Lasso {
             graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
             weight_dim: obj.sgd.weight_dim,
             bias_dim: obj.sgd.bias_dim,
-            learning_rate: obj.sgd.learning_rate
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(L1::new(obj.lambda)))),
+            fit_intercept: obj.sgd.fit_intercept
         };
 
-        Ok(Lasso {
-            sgd: sgd,
-            lambda: obj.lambda
-        })
+        Ok(Lasso { sgd })
     }
 
 }
@@ -6,7 +6,7 @@ use std::io::{Write, BufWriter, BufReader};
 
 use uuid::Uuid;
 use chrono::{Datelike, Utc};  
-use ndarray::{stack,  Array2, Axis};
+use ndarray::{stack, concatenate, Array2, Axis};
 use serde::{Serialize, Deserialize}; 
 
 use crate::autodiff::operations::arithmetic::*; 
@@ -16,6 +16,8 @@ use crate::autodiff::operations::base::Operation;
 use crate::autodiff::graph::{ComputationGraph, GraphConstruction, GraphSerialize};
 
 use crate::optimizer::model::*;
+use crate::optimizer::regularizer::Regularizer;
+use crate::preprocessing::processor::FeatureEncoder;
 
 
 /// Logistic regression classifier
@@ -34,7 +36,13 @@ pub struct Logistic {
     pub learning_rate: f64,
 
     /// Flag for multi or binary classification
-    pub multi_class: bool
+    pub multi_class: bool,
+
+    /// Penalty applied to weights
+    pub regularizer: Option<Box<dyn Regularizer>>,
+
+    /// Learn bias added after weights multiplication
+    pub fit_intercept: bool
 }
 
 
@@ -55,7 +63,15 @@ pub struct LogisticSerialize {
     pub learning_rate: f64,
 
     /// Serialized flag for multi or binary classification
-    pub multi_class: bool
+    pub multi_class: bool,
+
+    /// Penalty applied to weights
+    #[serde(default)]
+    pub regularizer: Option<Box<dyn Regularizer>>,
+
+    /// Learn bias added after weights multiplication
+    #[serde(default = "default_fit_intercept")]
+    pub fit_intercept: bool
 }
 
 
@@ -117,6 +133,9 @@ impl Logistic {
     ///   let output = loaded_model.predict(&x);
     ///   println!("Predictions: {:?}", output);
     ///
+    ///   // Remove saved parameters
+    ///   std::fs::remove_dir_all("data/binary_logistic").unwrap();
+    ///
     /// }
     /// ```
     pub fn new(
@@ -125,12 +144,37 @@ impl Logistic {
         multi_class: bool,
         learning_rate: f64) -> Result<Self, String> {
 
+        Logistic::with_options(x, y, multi_class, learning_rate, ModelOptions::default())
+    }
+
+    /// Create instance of logistic regression model with intercept & sample weight options.
+    /// Sample weights are applied with weighted binary or categorical cross entropy,
+    /// for example to balance classes.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target labels for training.
+    /// * `multi_class` - Flag for multi-class classification.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn with_options(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        multi_class: bool,
+        learning_rate: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
         if learning_rate < 0.0 || learning_rate > 1.0 {
             return Err(
                 "Learning rate must be between 0 and 1".to_string()
             );
         }
 
+        check_missing(x, y)?;
+        let sample_weight = options.sample_weight_column(x.nrows())?;
+
         let mut weight_dim: (usize, usize) = (x.shape()[1], 1);
         let mut bias_dim: (usize, usize) = (1, y.shape()[1]);
 
@@ -144,24 +188,164 @@ impl Logistic {
             weight_dim: weight_dim,
             bias_dim: bias_dim,
             learning_rate: learning_rate,
-            multi_class: multi_class
+            multi_class: multi_class,
+            regularizer: None,
+            fit_intercept: options.fit_intercept
         };
 
         log.graph.mul(vec![x.clone(), Array2::zeros(log.weight_dim)]);
         log.graph.add(vec![Array2::zeros(log.bias_dim)]);
 
-        if log.multi_class {
-            log.graph.cce(y.clone()); 
-        } else {
-            log.graph.sigmoid();
-            log.graph.bce(y.clone()); 
+        match (log.multi_class, sample_weight) {
+            (true, Some(weights)) => {
+                log.graph.weighted_cce(y.clone(), weights);
+            },
+            (true, None) => {
+                log.graph.cce(y.clone()); 
+            },
+            (false, Some(weights)) => {
+                log.graph.sigmoid();
+                log.graph.weighted_bce(y.clone(), weights);
+            },
+            (false, None) => {
+                log.graph.sigmoid();
+                log.graph.bce(y.clone()); 
+            }
         }
 
         log.graph.add_parameter(1);
-        log.graph.add_parameter(3);
+        if log.fit_intercept {
+            log.graph.add_parameter(3);
+        }
         Ok(log)
     }
 
+    /// Class probabilities from the last forward pass, softmax of the linear
+    /// outputs (n, k) for multi-class and columns (1 - p, p) for binary classification
+    pub fn probabilities(&self) -> Array2<f64> {
+
+        if !self.multi_class {
+            let p = self.graph.node(5).output();
+            let q = p.mapv(|v| 1.0 - v);
+            return concatenate(Axis(1), &[q.view(), p.view()]).unwrap();
+        }
+
+        let output = self.graph.node(4).output(); // bias node
+        let samples: Vec<_> = output
+            .axis_iter(Axis(0))
+            .map(|row| {
+                let max = row.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                let exp = row.mapv(|x| (x - max).exp());
+                let sum = exp.sum();
+                exp.mapv(|x| x / sum)
+            })
+            .collect();
+
+        let views: Vec<_> = samples.iter().map(|r| r.view()).collect();
+        stack(Axis(0), &views).unwrap()
+    }
+
+    /// Probability of every class for inputs, each row sums to 1.
+    /// Binary classification returns columns for class 0 & class 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features to classify.
+    ///
+    pub fn predict_proba(&mut self, x: &Array2<f64>) -> Array2<f64> {
+        self.set_output(&Array2::zeros((x.nrows(), self.output().dim().1)));
+        self.set_input(x);
+        self.graph.forward();
+        self.probabilities()
+    }
+
+    /// Predicted classes for inputs in the same layout as the training labels,
+    /// a column of 0 or 1 for binary and one hot rows for multi-class classification.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features to classify.
+    /// * `thresholds` - Probability of the positive class needed to predict 1 for binary
+    ///   classification (one value, default 0.5). Multi-class takes one threshold per class,
+    ///   the most probable class meeting its threshold is chosen, falling back to the
+    ///   most probable class when none do (default argmax).
+    ///
+    pub fn predict_class(
+        &mut self,
+        x: &Array2<f64>,
+        thresholds: Option<&[f64]>) -> Result<Array2<f64>, String> {
+
+        let n_classes = if self.multi_class { self.bias_dim.1 } else { 1 };
+        if let Some(thresholds) = thresholds {
+            if thresholds.len() != n_classes {
+                return Err(format!(
+                    "Expected {} thresholds, got {}", n_classes, thresholds.len()
+                ));
+            }
+
+            if thresholds.iter().any(|t| !(0.0..=1.0).contains(t)) {
+                return Err("Thresholds must be between 0 and 1".to_string());
+            }
+        }
+
+        let probabilities = self.predict_proba(x);
+        if !self.multi_class {
+            let threshold = thresholds.map_or(0.5, |t| t[0]);
+            let classes = probabilities
+                .column(1)
+                .mapv(|p| if p >= threshold { 1.0 } else { 0.0 });
+            return Ok(classes.insert_axis(Axis(1)));
+        }
+
+        let mut classes = Array2::zeros(probabilities.raw_dim());
+        for (idx, row) in probabilities.axis_iter(Axis(0)).enumerate() {
+            let mut class = None;
+            for (k, &prob) in row.iter().enumerate() {
+                let eligible = match thresholds {
+                    Some(t) => prob >= t[k],
+                    None => true
+                };
+                let best = match class {
+                    Some(c) => prob > row[c],
+                    None => true
+                };
+                if eligible && best {
+                    class = Some(k);
+                }
+            }
+
+            // no class meets its threshold, fall back to the most probable
+            let class = class.unwrap_or_else(|| {
+                row.iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .unwrap()
+                    .0
+            });
+            classes[[idx, class]] = 1.0;
+        }
+        Ok(classes)
+    }
+
+    /// Predicted classes decoded back to original labels with the encoder
+    /// used on the training labels, for example `OneHot::inverse_transform`.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features to classify.
+    /// * `thresholds` - Class thresholds (see `predict_class`).
+    /// * `encoder` - Encoder fitted on the training labels.
+    ///
+    pub fn predict_labels(
+        &mut self,
+        x: &Array2<f64>,
+        thresholds: Option<&[f64]>,
+        encoder: &dyn FeatureEncoder) -> Result<Array2<f64>, String> {
+
+        let classes = self.predict_class(x, thresholds)?;
+        encoder.inverse_transform(&classes.view())
+    }
+
 }
 
 
@@ -191,6 +375,10 @@ impl Model for Logistic {
             self.graph.mut_node_output(6, y.to_owned());
             self.graph.mut_node_output(7, y.to_owned());
         }
+
+        if let Some(idx) = sample_weight_node(&self.graph) {
+            self.graph.mut_node_output(idx, Array2::ones((y.nrows(), 1)));
+        }
     }
 
     fn graph(&self) -> &ComputationGraph<Array2<f64>> {
@@ -203,29 +391,19 @@ impl Model for Logistic {
 
     fn backward(&mut self) {
         self.graph.backward();
+        if let Some(regularizer) = &self.regularizer {
+            let w = self.graph.node(1);
+            let w_grad = w.grad() + regularizer.gradient(&w.output());
+            self.graph.nodes[1].set_grad_output(w_grad);
+        }
     }
 
     fn predicted(&self) -> Array2<f64> {
 
         if self.multi_class {
-            let mut row_idx = 0;
-            let mut predictions = Array2::zeros((self.output().nrows(), 2));
-            let output = self.graph.node(4).output(); // bias node
-
-            let samples: Vec<_> = output
-                .axis_iter(Axis(0))
-                .map(|row| {
-                    let max = row.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-                    let exp = row.mapv(|x| (x - max).exp());
-                    let sum = exp.sum();
-                    exp.mapv(|x| x / sum)
-                })
-                .collect();
-
-            let views: Vec<_> = samples.iter().map(|r| r.view()).collect();
-            let softmax = stack(Axis(0), &views).unwrap();
-
-            for row in softmax.axis_iter(Axis(0)) {
+            let probabilities = self.probabilities();
+            let mut predictions = Array2::zeros((probabilities.nrows(), 2));
+            for (row_idx, row) in probabilities.axis_iter(Axis(0)).enumerate() {
                 let (predicted_idx, &prob) = row
                     .iter()
                     .enumerate()
@@ -233,7 +411,6 @@ impl Model for Logistic {
                     .unwrap();
                 predictions[[row_idx, 0]] = predicted_idx as f64;
                 predictions[[row_idx, 1]] = prob;
-                row_idx += 1; 
             }
             predictions
         } else {
@@ -250,8 +427,11 @@ impl Model for Logistic {
 
     fn loss(&mut self) -> f64 {
         let loss_node = self.graph.curr_node();
-        let loss = loss_node.output();
-        loss.as_slice().unwrap()[0]
+        let loss = loss_node.output().as_slice().unwrap()[0];
+        match &self.regularizer {
+            Some(regularizer) => loss + regularizer.penalty(&self.graph.node(1).output()),
+            None => loss
+        }
     }
 
 
@@ -265,19 +445,48 @@ impl Model for Logistic {
         let w = self.graph.node(1);
         let w_grad = w.grad() * self.learning_rate;
         let w_delta = w.output() - w_grad;
-        self.graph.mut_node_output(1, w_delta); 
-
-        let b = self.graph.node(3);
-        let b_grad = b.grad() * self.learning_rate;
-        let b_delta = b.output() - b_grad.clone();
-        self.graph.mut_node_output(3, b_delta); 
+        let step = Array2::from_elem(w_delta.dim(), self.learning_rate);
+        self.update_parameter(1, w_delta, &step); 
+
+        if self.fit_intercept {
+            let b = self.graph.node(3);
+            let b_grad = b.grad() * self.learning_rate;
+            let b_delta = b.output() - b_grad.clone();
+            self.graph.mut_node_output(3, b_delta); 
+        }
 
     }
 
-    fn update_parameter(&mut self, idx: usize, val: Array2<f64>) {
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>) {
+        let val = match (idx, &self.regularizer) {
+            (1, Some(regularizer)) => regularizer.proximal(&val, step),
+            _ => val
+        };
         self.graph.mut_node_output(idx, val);
     }
 
+    fn regularizer(&self) -> Option<&dyn Regularizer> {
+        self.regularizer.as_deref()
+    }
+
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>) {
+        self.regularizer = Some(regularizer);
+    }
+
+    fn sample_weight(&self) -> Option<Array2<f64>> {
+        sample_weight_node(&self.graph).map(|idx| self.graph.node(idx).output())
+    }
+
+    fn set_sample_weight(&mut self, weights: &Array2<f64>) {
+        match sample_weight_node(&self.graph) {
+            Some(idx) => {
+                let column = weights.to_shape((weights.len(), 1)).unwrap().to_owned();
+                self.graph.mut_node_output(idx, column);
+            },
+            None => panic!("Model was created without sample weights")
+        }
+    }
+
 }
 
 
@@ -293,7 +502,9 @@ impl ModelSerialize for Logistic {
             weight_dim: self.weight_dim,
             bias_dim: self.bias_dim,
             learning_rate: self.learning_rate,
-            multi_class: self.multi_class
+            multi_class: self.multi_class,
+            regularizer: self.regularizer.clone(),
+            fit_intercept: self.fit_intercept
         };
 
         let _ = self.graph.save(&obj.graph_path); 
@@ -324,7 +535,9 @@ impl ModelSerialize for Logistic {
             weight_dim: self.weight_dim,
             bias_dim: self.bias_dim,
             learning_rate: self.learning_rate,
-            multi_class: self.multi_class
+            multi_class: self.multi_class,
+            regularizer: self.regularizer.clone(),
+            fit_intercept: self.fit_intercept
         };
 
         let _ = self.graph.save(&obj.graph_path); 
@@ -349,7 +562,9 @@ impl ModelSerialize for Logistic {
             weight_dim: obj.weight_dim,
             bias_dim: obj.bias_dim,
             learning_rate: obj.learning_rate,
-            multi_class: obj.multi_class
+            multi_class: obj.multi_class,
+            regularizer: obj.regularizer,
+            fit_intercept: obj.fit_intercept
         }) 
     }
  
@@ -375,7 +590,9 @@ impl ModelSerialize for Logistic {
             weight_dim: obj.weight_dim,
             bias_dim: obj.bias_dim,
             learning_rate: obj.learning_rate,
-            multi_class: obj.multi_class
+            multi_class: obj.multi_class,
+            regularizer: obj.regularizer,
+            fit_intercept: obj.fit_intercept
         })
     }
 
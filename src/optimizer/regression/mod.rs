@@ -3,3 +3,7 @@ pub mod logistic;
 pub mod ridge; 
 pub mod lasso; 
 pub mod elastic; 
+pub mod sparse;
+pub mod closed_form;
+pub mod svm;
+pub mod glm;
@@ -6,24 +6,23 @@ use std::io::{Write, BufWriter, BufReader};
 
 use uuid::Uuid;
 use chrono::{Datelike, Utc};  
-use ndarray::{Array2};
+use ndarray::{Array1, Array2};
 use serde::{Serialize, Deserialize}; 
 
 use crate::autodiff::graph::{ComputationGraph, GraphSerialize};
 use crate::autodiff::operations::base::Operation; 
 
 use crate::optimizer::model::*; 
-use crate::optimizer::regression::sgd::*; 
+use crate::optimizer::regression::sgd::*;
+use crate::optimizer::regularizer::*; 
 
 
 /// Ridge regression
 pub struct Ridge {
     
-    /// Instance of linear regression structure
-    pub sgd: SGD,
-
-    /// lambda parameter to regularize weights
-    pub lambda: f64
+    /// Instance of linear regression structure,
+    /// lambda is read from its attached penalty (`lambda`, `set_lambda`)
+    pub sgd: SGD
 }
 
 /// Serialization structure for ridge regression
@@ -79,6 +78,9 @@ impl Ridge {
     ///     let output = loaded_model.predict(&x);
     ///     println!("Predictions: {:?}", output); 
     ///
+    ///     // Remove saved parameters
+    ///     std::fs::remove_dir_all("data/ridge").unwrap();
+    ///
     /// }
     /// ```
     pub fn new(
@@ -87,10 +89,52 @@ impl Ridge {
         learning_rate: f64, 
         lambda: f64) -> Result<Self, String> {
 
-        Ok(Self {
-            sgd: SGD::new(x, y, learning_rate).unwrap(),
-            lambda: lambda
-        })
+        Self::with_options(x, y, learning_rate, lambda, ModelOptions::default())
+    }
+
+    /// Create instance of model with intercept & sample weight options (see `SGD::with_options`).
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `lambda` - Regularization strength.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn with_options(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        lambda: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        let mut sgd = SGD::with_options(x, y, learning_rate, options)?;
+        sgd.set_regularizer(Box::new(L2::new(lambda)));
+
+        Ok(Self { sgd })
+    }
+
+
+    /// Strength of the L2 part of the attached penalty (`lambda * ||w||^2`), follows penalties
+    /// attached with `set_regularizer` (0 for penalties outside of the elastic net family)
+    pub fn lambda(&self) -> f64 {
+        self.sgd
+            .regularizer()
+            .and_then(|regularizer| regularizer.elastic_net())
+            .map_or(0.0, |(lambda, alpha)| 0.5 * lambda * (1.0 - alpha))
+    }
+
+
+    /// Set regularization strength of model by attaching an L2 penalty
+    pub fn set_lambda(&mut self, lambda: f64) {
+        self.sgd.set_regularizer(Box::new(L2::new(lambda)));
+    }
+
+
+    /// Mean squared error of each target column from the last forward pass
+    pub fn target_loss(&self) -> Array1<f64> {
+        self.sgd.target_loss()
     }
 
 }
@@ -135,12 +179,7 @@ impl Model for Ridge {
     }
 
     fn loss(&mut self) -> f64 {
-        let loss_node = self.sgd.graph.curr_node();
-        let loss = loss_node.output();
-        let weights = self.sgd.graph.node(1).output();
-        let l2 = weights.mapv(|x| (x as f64).powf(2.0)).sum();
-        let loss_val = loss.clone() + (self.lambda * l2);
-        loss_val.as_slice().unwrap()[0]
+        self.sgd.loss()
     }
 
     fn set_loss(&mut self, op: Box<dyn Operation<Array2<f64>>>) {
@@ -148,21 +187,27 @@ impl Model for Ridge {
     }
  
     fn update_parameters(&mut self) {
+        self.sgd.update_parameters();
+    }
+
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>) {
+        self.sgd.update_parameter(idx, val, step);
+    }
 
-        let lr = self.sgd.learning_rate;
-        let w = self.sgd.graph.node(1);
-        let w_grad = w.grad() + self.lambda * 2.0 * w.output();
-        let w_delta = w.output() - lr * w_grad;
-        self.sgd.graph.mut_node_output(1, w_delta); 
+    fn regularizer(&self) -> Option<&dyn Regularizer> {
+        self.sgd.regularizer()
+    }
+
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>) {
+        self.sgd.set_regularizer(regularizer);
+    }
 
-        let b = self.sgd.graph.node(3);
-        let b_grad = b.grad() * lr;
-        let b_delta = b.output() - b_grad;
-        self.sgd.graph.mut_node_output(3, b_delta); 
+    fn sample_weight(&self) -> Option<Array2<f64>> {
+        self.sgd.sample_weight()
     }
 
-    fn update_parameter(&mut self, idx: usize, val: Array2<f64>) {
-        self.sgd.update_parameter(idx, val);
+    fn set_sample_weight(&mut self, weights: &Array2<f64>) {
+        self.sgd.set_sample_weight(weights);
     }
 
 }
@@ -180,9 +225,11 @@ impl ModelSerialize for Ridge {
                 graph_path: format!("{filepath}/regression_exp"),
                 weight_dim: self.sgd.weight_dim,
                 bias_dim: self.sgd.bias_dim,
-                learning_rate: self.sgd.learning_rate
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
             },
-            lambda: self.lambda
+            lambda: self.lambda()
         };
 
         let _ = self.sgd.graph.save(&obj.sgd.graph_path); 
@@ -212,9 +259,11 @@ impl ModelSerialize for Ridge {
                 graph_path: format!("{namespace}/regression_exp"),
                 weight_dim: self.sgd.weight_dim,
                 bias_dim: self.sgd.bias_dim,
-                learning_rate: self.sgd.learning_rate
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
             },
-            lambda: self.lambda
+            lambda: self.lambda()
         };
 
         let _ = self.sgd.graph.save(&obj.sgd.graph_path); 
@@ -238,13 +287,12 @@ impl ModelSerialize for Ridge {
             graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
             weight_dim: obj.sgd.weight_dim,
             bias_dim: obj.sgd.bias_dim,
-            learning_rate: obj.sgd.learning_rate
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(L2::new(obj.lambda)))),
+            fit_intercept: obj.sgd.fit_intercept
         };
 
-        Ok(Ridge {
-            sgd: sgd,
-            lambda: obj.lambda
-        }) 
+        Ok(Ridge { sgd }) 
     }
  
     fn load_snapshot(
@@ -268,13 +316,12 @@ impl ModelSerialize for Ridge {
             graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
             weight_dim: obj.sgd.weight_dim,
             bias_dim: obj.sgd.bias_dim,
-            learning_rate: obj.sgd.learning_rate
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(L2::new(obj.lambda)))),
+            fit_intercept: obj.sgd.fit_intercept
         };
 
-        Ok(Ridge {
-            sgd: sgd,
-            lambda: obj.lambda
-        })
+        Ok(Ridge { sgd })
     }
 
 }
@@ -6,7 +6,7 @@ use std::io::{Write, BufWriter, BufReader};
 
 use uuid::Uuid;
 use chrono::{Datelike, Utc};  
-use ndarray::{Array2};
+use ndarray::{Array1, Array2, Axis};
 use serde::{Serialize, Deserialize}; 
 
 use crate::autodiff::operations::arithmetic::*; 
@@ -14,7 +14,8 @@ use crate::autodiff::operations::loss::*;
 use crate::autodiff::operations::base::Operation; 
 use crate::autodiff::graph::{ComputationGraph, GraphConstruction, GraphSerialize};
 
-use crate::optimizer::model::*; 
+use crate::optimizer::model::*;
+use crate::optimizer::regularizer::Regularizer; 
 
 /// Stochastic gradient descent (SGD) optimizer
 pub struct SGD {
@@ -29,7 +30,13 @@ pub struct SGD {
     pub bias_dim: (usize, usize),
 
     /// Learning rate to control how fast to decrease
-    pub learning_rate: f64
+    pub learning_rate: f64,
+
+    /// Penalty applied to weights
+    pub regularizer: Option<Box<dyn Regularizer>>,
+
+    /// Learn bias added after weights multiplication
+    pub fit_intercept: bool
 }
 
 /// Serialization structure for SGD (stochastic gradient descent)
@@ -46,7 +53,15 @@ pub struct SGDSerialize {
     pub bias_dim: (usize, usize),
 
     /// Learning rate to control how fast to decrease
-    pub learning_rate: f64
+    pub learning_rate: f64,
+
+    /// Penalty applied to weights
+    #[serde(default)]
+    pub regularizer: Option<Box<dyn Regularizer>>,
+
+    /// Learn bias added after weights multiplication
+    #[serde(default = "default_fit_intercept")]
+    pub fit_intercept: bool
 }
 
 
@@ -58,7 +73,7 @@ impl SGD {
     /// # Arguments
     ///
     /// * `x` - Input features for training.
-    /// * `y` - Target labels for training.
+    /// * `y` - Target values for training, one column for each target.
     /// * `learning_rate` - The learning rate for the optimizer.
     ///
     pub fn new(
@@ -66,28 +81,163 @@ impl SGD {
         y: &Array2<f64>,
         learning_rate: f64) -> Result<Self, String> {
 
+        SGD::with_options(x, y, learning_rate, ModelOptions::default())
+    }
+
+    /// Create instance of SGD model with intercept & sample weight options.
+    /// Sample weights are applied with a weighted mean squared error loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn with_options(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
         if learning_rate < 0.0 || learning_rate > 1.0 {
             return Err(
                 "Learning rate must be between 0 and 1".to_string()
             );
         }
 
+        if x.nrows() != y.nrows() {
+            return Err(
+                "Number of samples in inputs and targets must match".to_string()
+            );
+        }
+
+        if y.ncols() == 0 {
+            return Err(
+                "Targets must have at least one column".to_string()
+            );
+        }
+
+        check_missing(x, y)?;
+        let sample_weight = options.sample_weight_column(x.nrows())?;
+
         let mut sgd = SGD {
             graph: ComputationGraph::new(),
-            weight_dim: (x.shape()[1], 1),
-            bias_dim: (1, 1),
-            learning_rate: learning_rate
+            weight_dim: (x.shape()[1], y.ncols()),
+            bias_dim: (1, y.ncols()),
+            learning_rate: learning_rate,
+            regularizer: None,
+            fit_intercept: options.fit_intercept
         };
 
         sgd.graph.mul(vec![x.clone(), Array2::zeros(sgd.weight_dim)]); 
         sgd.graph.add(vec![Array2::zeros(sgd.bias_dim)]);
-        sgd.graph.mse(y.clone());
+
+        match sample_weight {
+            Some(weights) => sgd.graph.weighted_mse(y.clone(), weights),
+            None => sgd.graph.mse(y.clone())
+        };
 
         sgd.graph.add_parameter(1);
-        sgd.graph.add_parameter(3);
+        if sgd.fit_intercept {
+            sgd.graph.add_parameter(3);
+        }
         Ok(sgd)
     }
 
+    /// Create instance of SGD model minimizing huber loss, 
+    /// robust to outliers in the targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `delta` - Residual size where loss changes from squared to absolute error.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn huber(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        delta: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        if delta <= 0.0 {
+            return Err("Huber delta must be positive".to_string());
+        }
+
+        SGD::with_loss(x, y, learning_rate, Box::new(Huber::new(delta)), options)
+    }
+
+    /// Create instance of SGD model minimizing quantile (pinball) loss, 
+    /// predictions estimate the `tau` quantile of the targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `tau` - Quantile to predict, between 0 and 1.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn quantile(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        tau: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        if tau <= 0.0 || tau >= 1.0 {
+            return Err("Quantile tau must be between 0 and 1".to_string());
+        }
+
+        SGD::with_loss(x, y, learning_rate, Box::new(Quantile::new(tau)), options)
+    }
+
+    /// Create instance of SGD model minimizing log-cosh loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target values for training, one column for each target.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn log_cosh(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        SGD::with_loss(x, y, learning_rate, Box::new(LogCosh), options)
+    }
+
+    /// Create model with options and replace its squared error loss,
+    /// losses read sample weights from the same graph input as the weighted squared error
+    fn with_loss(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        loss: Box<dyn Operation<Array2<f64>>>,
+        options: ModelOptions) -> Result<Self, String> {
+
+        let mut sgd = SGD::with_options(x, y, learning_rate, options)?;
+        sgd.set_loss(loss);
+        Ok(sgd)
+    }
+
+    /// Number of target columns predicted by model
+    pub fn n_targets(&self) -> usize {
+        self.weight_dim.1
+    }
+
+    /// Mean squared error of each target column from the last forward pass
+    pub fn target_loss(&self) -> Array1<f64> {
+        let diff = self.predicted() - self.output();
+        diff.mapv(|x| x * x).mean_axis(Axis(0)).unwrap()
+    }
+
 }
 
 
@@ -115,11 +265,19 @@ impl Model for SGD {
 
     fn backward(&mut self) {
         self.graph.backward();
+        if let Some(regularizer) = &self.regularizer {
+            let w = self.graph.node(1);
+            let w_grad = w.grad() + regularizer.gradient(&w.output());
+            self.graph.nodes[1].set_grad_output(w_grad);
+        }
     }
 
     fn set_output(&mut self, y: &Array2<f64>) {
         self.graph.mut_node_output(4, y.to_owned());
         self.graph.mut_node_output(5, y.to_owned());
+        if let Some(idx) = sample_weight_node(&self.graph) {
+            self.graph.mut_node_output(idx, Array2::ones((y.nrows(), 1)));
+        }
     }
 
     fn predicted(&self) -> Array2<f64> {
@@ -127,7 +285,7 @@ impl Model for SGD {
     }
 
     fn predict(&mut self, x: &Array2<f64>) -> Array2<f64> {
-        self.set_output(&Array2::zeros((x.nrows(), 1)));
+        self.set_output(&Array2::zeros((x.nrows(), self.n_targets())));
         self.set_input(x);
         self.graph.forward();
         self.predicted()
@@ -135,8 +293,11 @@ impl Model for SGD {
 
     fn loss(&mut self) -> f64 {
         let loss_node = self.graph.curr_node();
-        let loss = loss_node.output();
-        loss.as_slice().unwrap()[0]
+        let loss = loss_node.output().as_slice().unwrap()[0];
+        match &self.regularizer {
+            Some(regularizer) => loss + regularizer.penalty(&self.graph.node(1).output()),
+            None => loss
+        }
     }
 
 
@@ -150,18 +311,47 @@ impl Model for SGD {
         let w = self.graph.node(1);
         let w_grad = w.grad() * self.learning_rate;
         let w_delta = w.output() - w_grad;
-        self.graph.mut_node_output(1, w_delta); 
-
-        let b = self.graph.node(3);
-        let b_grad = b.grad() * self.learning_rate;
-        let b_delta = b.output() - b_grad;
-        self.graph.mut_node_output(3, b_delta); 
+        let step = Array2::from_elem(w_delta.dim(), self.learning_rate);
+        self.update_parameter(1, w_delta, &step); 
+
+        if self.fit_intercept {
+            let b = self.graph.node(3);
+            let b_grad = b.grad() * self.learning_rate;
+            let b_delta = b.output() - b_grad;
+            self.graph.mut_node_output(3, b_delta); 
+        }
     }
 
-    fn update_parameter(&mut self, idx: usize, val: Array2<f64>) {
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>) {
+        let val = match (idx, &self.regularizer) {
+            (1, Some(regularizer)) => regularizer.proximal(&val, step),
+            _ => val
+        };
         self.graph.mut_node_output(idx, val);
     }
 
+    fn regularizer(&self) -> Option<&dyn Regularizer> {
+        self.regularizer.as_deref()
+    }
+
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>) {
+        self.regularizer = Some(regularizer);
+    }
+
+    fn sample_weight(&self) -> Option<Array2<f64>> {
+        sample_weight_node(&self.graph).map(|idx| self.graph.node(idx).output())
+    }
+
+    fn set_sample_weight(&mut self, weights: &Array2<f64>) {
+        match sample_weight_node(&self.graph) {
+            Some(idx) => {
+                let column = weights.to_shape((weights.len(), 1)).unwrap().to_owned();
+                self.graph.mut_node_output(idx, column);
+            },
+            None => panic!("Model was created without sample weights")
+        }
+    }
+
 }
 
 
@@ -176,7 +366,9 @@ impl ModelSerialize for SGD {
             graph_path: format!("{filepath}/regression_exp"),
             weight_dim: self.weight_dim,
             bias_dim: self.bias_dim,
-            learning_rate: self.learning_rate
+            learning_rate: self.learning_rate,
+            regularizer: self.regularizer.clone(),
+            fit_intercept: self.fit_intercept
         };
 
         let _ = self.graph.save(&obj.graph_path); 
@@ -206,7 +398,9 @@ impl ModelSerialize for SGD {
             graph_path: format!("{namespace}/regression_exp"),
             weight_dim: self.weight_dim,
             bias_dim: self.bias_dim,
-            learning_rate: self.learning_rate
+            learning_rate: self.learning_rate,
+            regularizer: self.regularizer.clone(),
+            fit_intercept: self.fit_intercept
         };
 
         let _ = self.graph.save(&obj.graph_path); 
@@ -230,7 +424,9 @@ impl ModelSerialize for SGD {
             graph: ComputationGraph::load(&obj.graph_path).unwrap(),
             weight_dim: obj.weight_dim,
             bias_dim: obj.bias_dim,
-            learning_rate: obj.learning_rate
+            learning_rate: obj.learning_rate,
+            regularizer: obj.regularizer,
+            fit_intercept: obj.fit_intercept
         }) 
     }
  
@@ -255,7 +451,9 @@ impl ModelSerialize for SGD {
             graph: ComputationGraph::load(&obj.graph_path).unwrap(),
             weight_dim: obj.weight_dim,
             bias_dim: obj.bias_dim,
-            learning_rate: obj.learning_rate
+            learning_rate: obj.learning_rate,
+            regularizer: obj.regularizer,
+            fit_intercept: obj.fit_intercept
         })
     }
 
@@ -0,0 +1,562 @@
+//! Proximal gradient & coordinate descent solvers for sparse regression
+//!
+//! Solvers minimize the objective that the update rules of `Lasso` & `Elastic` descend,
+//! `1/2 * ||Xw + b - y||^2 + lambda * (alpha * ||w||_1 + 1/2 * (1 - alpha) * ||w||^2)`,
+//! where `alpha` is 1 for `Lasso` and the bias is not penalized.
+//! `lambda` & `alpha` are read from the penalty attached to the model, so penalties attached with
+//! `set_regularizer` are solved as long as they are part of the elastic net family (`L1`, `L2`, `ElasticNet`).
+//! Models created with sample weights or without an intercept are solved with the same options.
+//! Soft-thresholding sets coefficients exactly to zero,
+//! so fitted models can be used for feature selection.
+
+use ndarray::{concatenate, Array1, Array2, Axis};
+
+use crate::optimizer::model::*;
+use crate::optimizer::regularizer::{soft_threshold, Regularizer};
+use crate::optimizer::regression::elastic::*;
+use crate::optimizer::regression::lasso::*;
+
+
+/// Solver used to fit sparse regression models
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SparseSolver {
+
+    /// Iterative shrinkage-thresholding, proximal gradient with step `1 / L`
+    Ista,
+
+    /// Fast iterative shrinkage-thresholding with Nesterov momentum
+    Fista,
+
+    /// Cyclic coordinate descent over each weight
+    CoordinateDescent
+}
+
+
+/// Solution for a single lambda of a regularization path
+#[derive(Debug, Clone)]
+pub struct PathPoint {
+
+    /// Regularization strength
+    pub lambda: f64,
+
+    /// Weights fitted for lambda
+    pub weights: Array2<f64>,
+
+    /// Bias fitted for lambda
+    pub bias: Array2<f64>,
+
+    /// Iterations used by solver
+    pub iterations: usize,
+
+    /// Number of non zero weights
+    pub nonzero: usize
+}
+
+
+/// Sparse solvers for regularized regression models
+pub trait SparseFit {
+
+    /// Fit weights & bias of model with sparse solver, starting from current parameters.
+    /// Returns number of iterations used, or an error when the attached penalty
+    /// is not part of the elastic net family.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver` - Solver used to minimize the objective.
+    /// * `max_iter` - Maximum number of iterations.
+    /// * `tol` - Stop when the largest parameter change is below tolerance.
+    ///
+    fn fit_sparse(&mut self, solver: SparseSolver, max_iter: usize, tol: f64) -> Result<usize, String>;
+
+    /// Fit model for each lambda in order, warm starting from the previous solution.
+    /// Model keeps the solution and lambda of the last point.
+    ///
+    /// # Arguments
+    ///
+    /// * `lambdas` - Regularization strengths, usually decreasing (see `lambda_path`).
+    /// * `solver` - Solver used to minimize the objective.
+    /// * `max_iter` - Maximum number of iterations for each lambda.
+    /// * `tol` - Stop when the largest parameter change is below tolerance.
+    ///
+    fn path(
+        &mut self,
+        lambdas: &[f64],
+        solver: SparseSolver,
+        max_iter: usize,
+        tol: f64) -> Result<Vec<PathPoint>, String>;
+
+}
+
+
+/// Proximal operator of the elastic net penalty scaled by step size.
+///
+/// # Arguments
+///
+/// * `w` - Weights after gradient step.
+/// * `step` - Step size of gradient step.
+/// * `lambda` - Regularization strength.
+/// * `alpha` - Mixing of L1 (1.0) and L2 (0.0) penalties.
+///
+pub fn proximal_elastic(w: &Array2<f64>, step: f64, lambda: f64, alpha: f64) -> Array2<f64> {
+    soft_threshold(w, step * lambda * alpha) / (1.0 + step * lambda * (1.0 - alpha))
+}
+
+
+/// Center columns of features & targets, returning centered values with their means
+pub(crate) fn center(x: &Array2<f64>, y: &Array2<f64>) -> (Array2<f64>, Array2<f64>, Array1<f64>, Array1<f64>) {
+    let x_mean = x.mean_axis(Axis(0)).unwrap();
+    let y_mean = y.mean_axis(Axis(0)).unwrap();
+    (x - &x_mean, y - &y_mean, x_mean, y_mean)
+}
+
+
+/// Sample weights as a column rescaled to a mean of 1 (same scaling as weighted loss functions)
+fn normalize_weights(sample_weight: Option<&Array2<f64>>, rows: usize) -> Array2<f64> {
+    match sample_weight {
+        Some(weights) => {
+            let column = weights.to_shape((rows, 1)).unwrap().to_owned();
+            let total = column.sum();
+            column * (rows as f64 / total)
+        },
+        None => Array2::ones((rows, 1))
+    }
+}
+
+
+/// Reduce a weighted least squares problem to an equivalent problem without bias.
+/// Columns are centered with weighted means (when fitting an intercept) and rows are scaled
+/// by the square root of their sample weight, the bias of a solution is `y_mean - x_mean * w`.
+pub(crate) fn reduce(
+    x: &Array2<f64>,
+    y: &Array2<f64>,
+    sample_weight: Option<&Array2<f64>>,
+    fit_intercept: bool) -> (Array2<f64>, Array2<f64>, Array1<f64>, Array1<f64>) {
+
+    let rows = x.nrows();
+    let weights = normalize_weights(sample_weight, rows);
+
+    let (x_mean, y_mean) = match fit_intercept {
+        true => (
+            (x * &weights).sum_axis(Axis(0)) / rows as f64,
+            (y * &weights).sum_axis(Axis(0)) / rows as f64
+        ),
+        false => (Array1::zeros(x.ncols()), Array1::zeros(y.ncols()))
+    };
+
+    let scale = weights.mapv(f64::sqrt);
+    ((x - &x_mean) * &scale, (y - &y_mean) * &scale, x_mean, y_mean)
+}
+
+
+/// Smallest lambda where all weights are zero
+///
+/// # Arguments
+///
+/// * `x` - Input features.
+/// * `y` - Target values.
+/// * `alpha` - Mixing of L1 & L2 penalties (clamped to at least 0.001).
+///
+pub fn lambda_max(x: &Array2<f64>, y: &Array2<f64>, alpha: f64) -> f64 {
+    let (xc, yc, _, _) = center(x, y);
+    let corr = xc.t().dot(&yc);
+    corr.iter().fold(0.0, |max: f64, val| max.max(val.abs())) / alpha.max(1e-3)
+}
+
+
+/// Decreasing lambdas spaced on a log scale from `lambda_max` to `eps * lambda_max`
+///
+/// # Arguments
+///
+/// * `x` - Input features.
+/// * `y` - Target values.
+/// * `alpha` - Mixing of L1 & L2 penalties.
+/// * `n` - Number of lambdas.
+/// * `eps` - Ratio of smallest to largest lambda.
+///
+pub fn lambda_path(x: &Array2<f64>, y: &Array2<f64>, alpha: f64, n: usize, eps: f64) -> Vec<f64> {
+    let max = lambda_max(x, y, alpha);
+    if n == 1 {
+        return vec![max];
+    }
+    (0..n)
+        .map(|i| max * eps.powf(i as f64 / (n - 1) as f64))
+        .collect()
+}
+
+
+/// Lipschitz constant of the weighted least squares gradient
+/// (largest eigenvalue of `[X 1]^T W [X 1]`, without the bias column when no intercept is fit)
+fn lipschitz(x: &Array2<f64>, sample_weight: &Array2<f64>, fit_intercept: bool) -> f64 {
+
+    let xa = match fit_intercept {
+        true => {
+            let ones = Array2::ones((x.nrows(), 1));
+            concatenate![Axis(1), x.view(), ones.view()]
+        },
+        false => x.clone()
+    } * sample_weight.mapv(f64::sqrt);
+    let gram = xa.t().dot(&xa);
+
+    let mut v = Array1::<f64>::ones(gram.nrows());
+    let mut eigen = 0.0;
+    for _ in 0..100 {
+        let next = gram.dot(&v);
+        let norm = next.dot(&next).sqrt();
+        if norm == 0.0 {
+            break;
+        }
+        v = next / norm;
+        eigen = v.dot(&gram.dot(&v));
+    }
+    eigen.max(f64::EPSILON)
+}
+
+
+/// Largest absolute difference between two arrays
+fn max_change(a: &Array2<f64>, b: &Array2<f64>) -> f64 {
+    a.iter().zip(b.iter()).fold(0.0, |max: f64, (x, y)| max.max((x - y).abs()))
+}
+
+
+/// Minimize the sparse regression objective in place, returning iterations used.
+///
+/// # Arguments
+///
+/// * `x` - Input features.
+/// * `y` - Target values.
+/// * `weights` - Initial weights of shape `(features, targets)`, updated with solution.
+/// * `bias` - Initial bias of shape `(1, targets)`, updated with solution.
+/// * `lambda` - Regularization strength.
+/// * `alpha` - Mixing of L1 (1.0) and L2 (0.0) penalties.
+/// * `solver` - Solver used to minimize the objective.
+/// * `max_iter` - Maximum number of iterations.
+/// * `tol` - Stop when the largest parameter change is below tolerance.
+///
+#[allow(clippy::too_many_arguments)]
+pub fn solve(
+    x: &Array2<f64>,
+    y: &Array2<f64>,
+    weights: &mut Array2<f64>,
+    bias: &mut Array2<f64>,
+    lambda: f64,
+    alpha: f64,
+    solver: SparseSolver,
+    max_iter: usize,
+    tol: f64) -> usize {
+
+    solve_weighted(x, y, None, true, weights, bias, lambda, alpha, solver, max_iter, tol)
+}
+
+
+/// Minimize the objective with sample weights (rescaled to a mean of 1), without bias
+/// when no intercept is fit
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve_weighted(
+    x: &Array2<f64>,
+    y: &Array2<f64>,
+    sample_weight: Option<&Array2<f64>>,
+    fit_intercept: bool,
+    weights: &mut Array2<f64>,
+    bias: &mut Array2<f64>,
+    lambda: f64,
+    alpha: f64,
+    solver: SparseSolver,
+    max_iter: usize,
+    tol: f64) -> usize {
+
+    if !fit_intercept {
+        bias.fill(0.0);
+    }
+
+    match solver {
+        SparseSolver::CoordinateDescent => {
+            coordinate_descent(
+                x, y, sample_weight, fit_intercept,
+                weights, bias, lambda, alpha, max_iter, tol
+            )
+        },
+        _ => {
+            let sample_weight = normalize_weights(sample_weight, x.nrows());
+            proximal_gradient(
+                x, y, &sample_weight, fit_intercept,
+                weights, bias, lambda, alpha,
+                solver == SparseSolver::Fista, max_iter, tol
+            )
+        }
+    }
+}
+
+
+/// ISTA, or FISTA when accelerated
+#[allow(clippy::too_many_arguments)]
+fn proximal_gradient(
+    x: &Array2<f64>,
+    y: &Array2<f64>,
+    sample_weight: &Array2<f64>,
+    fit_intercept: bool,
+    weights: &mut Array2<f64>,
+    bias: &mut Array2<f64>,
+    lambda: f64,
+    alpha: f64,
+    accelerated: bool,
+    max_iter: usize,
+    tol: f64) -> usize {
+
+    let step = 1.0 / lipschitz(x, sample_weight, fit_intercept);
+    let (mut z_w, mut z_b) = (weights.clone(), bias.clone());
+    let mut t: f64 = 1.0;
+
+    for iteration in 1..=max_iter {
+
+        let residual = (x.dot(&z_w) + &z_b - y) * sample_weight;
+        let grad_w = x.t().dot(&residual);
+        let grad_b = match fit_intercept {
+            true => residual.sum_axis(Axis(0)).insert_axis(Axis(0)),
+            false => Array2::zeros(z_b.dim())
+        };
+
+        let w_new = proximal_elastic(&(&z_w - &(step * grad_w)), step, lambda, alpha);
+        let b_new = &z_b - &(step * grad_b);
+        let change = max_change(&w_new, weights).max(max_change(&b_new, bias));
+
+        if accelerated {
+            let t_new = (1.0 + (1.0 + 4.0 * t * t).sqrt()) / 2.0;
+            let momentum = (t - 1.0) / t_new;
+            z_w = &w_new + &(momentum * (&w_new - &*weights));
+            z_b = &b_new + &(momentum * (&b_new - &*bias));
+            t = t_new;
+        } else {
+            z_w = w_new.clone();
+            z_b = b_new.clone();
+        }
+
+        *weights = w_new;
+        *bias = b_new;
+
+        if change < tol {
+            return iteration;
+        }
+    }
+    max_iter
+}
+
+
+/// Cyclic coordinate descent on centered data, bias is recovered from the means
+#[allow(clippy::too_many_arguments)]
+fn coordinate_descent(
+    x: &Array2<f64>,
+    y: &Array2<f64>,
+    sample_weight: Option<&Array2<f64>>,
+    fit_intercept: bool,
+    weights: &mut Array2<f64>,
+    bias: &mut Array2<f64>,
+    lambda: f64,
+    alpha: f64,
+    max_iter: usize,
+    tol: f64) -> usize {
+
+    let (xc, yc, x_mean, y_mean) = reduce(x, y, sample_weight, fit_intercept);
+    let norms: Vec<f64> = xc.axis_iter(Axis(1)).map(|col| col.dot(&col)).collect();
+    let threshold = lambda * alpha;
+    let shrink = lambda * (1.0 - alpha);
+    let mut iterations = 0;
+
+    for target in 0..weights.ncols() {
+
+        let mut residual = yc.column(target).to_owned() - xc.dot(&weights.column(target));
+
+        for iteration in 1..=max_iter {
+
+            let mut change: f64 = 0.0;
+            for (feature, norm) in norms.iter().enumerate() {
+
+                let old = weights[[feature, target]];
+                let new = match *norm == 0.0 {
+                    true => 0.0,
+                    false => {
+                        let rho = xc.column(feature).dot(&residual) + norm * old;
+                        rho.signum() * (rho.abs() - threshold).max(0.0) / (norm + shrink)
+                    }
+                };
+
+                if new != old {
+                    residual.scaled_add(old - new, &xc.column(feature));
+                    weights[[feature, target]] = new;
+                    change = change.max((new - old).abs());
+                }
+            }
+
+            iterations = iterations.max(iteration);
+            if change < tol {
+                break;
+            }
+        }
+    }
+
+    let intercept = &y_mean - &x_mean.dot(&*weights);
+    *bias = intercept.insert_axis(Axis(0));
+    iterations
+}
+
+
+/// Strength & mixing of the penalty attached to a model (no penalty solves least squares)
+fn attached_penalty(regularizer: Option<&dyn Regularizer>) -> Result<(f64, f64), String> {
+    match regularizer {
+        None => Ok((0.0, 1.0)),
+        Some(regularizer) => regularizer.elastic_net().ok_or(format!(
+            "Sparse solvers solve elastic net penalties, model penalty is {:?}", regularizer
+        ))
+    }
+}
+
+
+/// Solve for parameters of linear model graph (weights node 1, bias node 3)
+macro_rules! sparse_fit {
+
+    ($t:ty) => {
+
+        impl SparseFit for $t {
+
+            fn fit_sparse(&mut self, solver: SparseSolver, max_iter: usize, tol: f64) -> Result<usize, String> {
+
+                let (lambda, alpha) = attached_penalty(self.regularizer())?;
+                let (x, y) = (self.input(), self.output());
+                let sample_weight = self.sample_weight();
+                let mut weights = self.sgd.graph.node(1).output();
+                let mut bias = self.sgd.graph.node(3).output();
+
+                let iterations = solve_weighted(
+                    &x, &y,
+                    sample_weight.as_ref(), self.sgd.fit_intercept,
+                    &mut weights, &mut bias,
+                    lambda, alpha,
+                    solver, max_iter, tol
+                );
+
+                self.sgd.graph.mut_node_output(1, weights);
+                self.sgd.graph.mut_node_output(3, bias);
+                self.forward();
+                Ok(iterations)
+            }
+
+            fn path(
+                &mut self,
+                lambdas: &[f64],
+                solver: SparseSolver,
+                max_iter: usize,
+                tol: f64) -> Result<Vec<PathPoint>, String> {
+
+                lambdas
+                    .iter()
+                    .map(|lambda| {
+                        self.set_lambda(*lambda);
+                        let iterations = self.fit_sparse(solver, max_iter, tol)?;
+                        let weights = self.sgd.graph.node(1).output();
+                        Ok(PathPoint {
+                            lambda: *lambda,
+                            nonzero: weights.iter().filter(|w| **w != 0.0).count(),
+                            weights,
+                            bias: self.sgd.graph.node(3).output(),
+                            iterations
+                        })
+                    })
+                    .collect()
+            }
+
+        }
+
+    }
+
+}
+
+
+sparse_fit!(Lasso);
+sparse_fit!(Elastic);
+
+
+#[cfg(test)]
+mod sparse_test {
+
+    use ndarray::arr2;
+    use crate::optimizer::regularizer::soft_threshold;
+    use crate::optimizer::regression::sparse::*;
+
+    fn load_data() -> (Array2<f64>, Array2<f64>) {
+
+        // target depends on first & third feature only
+        let x = arr2(&[
+            [1.0, 0.5, 2.0, 1.0],
+            [2.0, -1.0, 0.0, 3.0],
+            [3.0, 0.0, 1.0, -2.0],
+            [4.0, 1.5, -1.0, 0.0],
+            [5.0, -0.5, 3.0, 1.0],
+            [6.0, 1.0, 2.0, -1.0]
+        ]);
+
+        let y = x.column(0).mapv(|v| 3.0 * v).insert_axis(Axis(1))
+            + x.column(2).mapv(|v| -2.0 * v).insert_axis(Axis(1))
+            + 1.0;
+        (x, y)
+    }
+
+    #[test]
+    fn test_soft_threshold() {
+
+        let x = arr2(&[[3.0, -0.5], [0.2, -2.0]]);
+        assert_eq!(soft_threshold(&x, 1.0), arr2(&[[2.0, 0.0], [0.0, -1.0]]));
+        assert_eq!(
+            proximal_elastic(&x, 1.0, 2.0, 0.5),
+            arr2(&[[1.0, 0.0], [0.0, -0.5]])
+        );
+    }
+
+    #[test]
+    fn test_solvers_agree() {
+
+        let (x, y) = load_data();
+        let lambda = 2.0;
+
+        let mut solutions = Vec::new();
+        for solver in [SparseSolver::Ista, SparseSolver::Fista, SparseSolver::CoordinateDescent] {
+            let mut w = Array2::zeros((4, 1));
+            let mut b = Array2::zeros((1, 1));
+            let iterations = solve(&x, &y, &mut w, &mut b, lambda, 1.0, solver, 100000, 1e-10);
+            assert!(iterations < 100000);
+            solutions.push((w, b, iterations));
+        }
+
+        let (cd_w, cd_b, cd_iter) = &solutions[2];
+        for (w, b, _) in &solutions[..2] {
+            assert!(max_change(w, cd_w) < 1e-4);
+            assert!(max_change(b, cd_b) < 1e-4);
+        }
+
+        // acceleration converges faster than plain proximal gradient
+        assert!(solutions[1].2 < solutions[0].2);
+        assert!(*cd_iter < solutions[1].2);
+
+        // irrelevant features are exactly zero
+        assert_eq!(cd_w[[1, 0]], 0.0);
+        assert_eq!(cd_w[[3, 0]], 0.0);
+        assert!(cd_w[[0, 0]] > 2.5);
+        assert!(cd_w[[2, 0]] < -1.5);
+    }
+
+    #[test]
+    fn test_lambda_path() {
+
+        let (x, y) = load_data();
+        let lambdas = lambda_path(&x, &y, 1.0, 5, 0.001);
+        assert_eq!(lambdas.len(), 5);
+        assert_eq!(lambdas[0], lambda_max(&x, &y, 1.0));
+        assert!(lambdas.windows(2).all(|pair| pair[0] > pair[1]));
+
+        // all weights are zero at lambda max
+        let mut w = Array2::ones((4, 1));
+        let mut b = Array2::zeros((1, 1));
+        solve(&x, &y, &mut w, &mut b, lambdas[0], 1.0, SparseSolver::CoordinateDescent, 1000, 1e-10);
+        assert!(w.iter().all(|val| *val == 0.0));
+        assert_eq!(b[[0, 0]], y.mean().unwrap());
+    }
+
+}
@@ -0,0 +1,381 @@
+//! Linear support vector machine (SVM) model implementation
+
+use std::fs;
+use std::fs::File;
+use std::io::{Write, BufWriter, BufReader};
+
+use uuid::Uuid;
+use chrono::{Datelike, Utc};
+use ndarray::{Array2, Axis};
+use serde::{Serialize, Deserialize};
+
+use crate::autodiff::graph::{ComputationGraph, GraphSerialize};
+use crate::autodiff::operations::base::Operation;
+use crate::autodiff::operations::loss::*;
+
+use crate::optimizer::model::*;
+use crate::optimizer::regression::sgd::*;
+use crate::optimizer::regularizer::*;
+
+
+/// Linear support vector machine classifier trained with hinge loss.
+/// Labels are a single column of 0 or 1 for binary classification or
+/// one hot columns for multi-class classification (one vs rest).
+pub struct LinearSVM {
+
+    /// Instance of linear regression structure producing decision scores,
+    /// lambda is read from its attached L2 penalty (`lambda`, `set_lambda`)
+    pub sgd: SGD
+}
+
+/// Serialization structure for linear SVM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinearSVMSerialize {
+
+    /// Serializable instance of regression structure
+    sgd: SGDSerialize,
+
+    /// lambda parmeter to regualrize weights
+    lambda: f64
+}
+
+
+impl LinearSVM {
+
+
+    /// Create instance of linear SVM with hinge loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target labels of 0 or 1, one hot columns for multi-class.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `lambda` - The L2 regularization strength.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::optimizer::prelude::*;
+    ///
+    ///
+    /// fn main() {
+    ///
+    ///     let x = arr2(&[
+    ///         [1.0, 2.0],
+    ///         [2.0, 1.0],
+    ///         [1.5, 1.8],
+    ///         [5.0, 5.5],
+    ///         [6.0, 5.8],
+    ///         [5.5, 6.0]
+    ///     ]);
+    ///
+    ///     let y = arr2(&[[0.0], [0.0], [0.0], [1.0], [1.0], [1.0]]);
+    ///     let mut model = LinearSVM::new(&x, &y, 0.01, 0.001).unwrap();
+    ///
+    ///     // Save model train and save results
+    ///     model.train(1000);
+    ///     model.save("data/linear_svm").unwrap();
+    ///
+    ///     // Load model and make predictions
+    ///     let mut loaded_model = LinearSVM::load("data/linear_svm").unwrap();
+    ///     let output = loaded_model.predict(&x);
+    ///     println!("Predictions: {:?}", output);
+    ///
+    ///     // Remove saved parameters
+    ///     std::fs::remove_dir_all("data/linear_svm").unwrap();
+    ///
+    /// }
+    /// ```
+    pub fn new(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        lambda: f64) -> Result<Self, String> {
+
+        Self::with_options(x, y, learning_rate, lambda, ModelOptions::default())
+    }
+
+    /// Create instance of linear SVM with squared hinge loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target labels of 0 or 1, one hot columns for multi-class.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `lambda` - The L2 regularization strength.
+    ///
+    pub fn squared_hinge(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        lambda: f64) -> Result<Self, String> {
+
+        let mut svm = Self::new(x, y, learning_rate, lambda)?;
+        svm.set_loss(Box::new(SquaredHinge));
+        Ok(svm)
+    }
+
+    /// Create instance of linear SVM with intercept & sample weight options (see `SGD::with_options`).
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features for training.
+    /// * `y` - Target labels of 0 or 1, one hot columns for multi-class.
+    /// * `learning_rate` - The learning rate for the optimizer.
+    /// * `lambda` - The L2 regularization strength.
+    /// * `options` - Intercept & sample weight options.
+    ///
+    pub fn with_options(
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        learning_rate: f64,
+        lambda: f64,
+        options: ModelOptions) -> Result<Self, String> {
+
+        if y.iter().any(|label| *label != 0.0 && *label != 1.0) {
+            return Err("SVM labels must be 0 or 1".to_string());
+        }
+
+        if lambda < 0.0 {
+            return Err("Regularization lambda must be non negative".to_string());
+        }
+
+        let mut sgd = SGD::with_options(x, y, learning_rate, options)?;
+        sgd.set_loss(Box::new(Hinge));
+        sgd.set_regularizer(Box::new(L2::new(lambda)));
+
+        Ok(Self { sgd })
+    }
+
+    /// Strength of the L2 part of the attached penalty (`lambda * ||w||^2`), follows penalties
+    /// attached with `set_regularizer` (0 for penalties outside of the elastic net family)
+    pub fn lambda(&self) -> f64 {
+        self.sgd
+            .regularizer()
+            .and_then(|regularizer| regularizer.elastic_net())
+            .map_or(0.0, |(lambda, alpha)| 0.5 * lambda * (1.0 - alpha))
+    }
+
+    /// Set regularization strength of model by attaching an L2 penalty
+    pub fn set_lambda(&mut self, lambda: f64) {
+        self.sgd.set_regularizer(Box::new(L2::new(lambda)));
+    }
+
+    /// Flag for multi-class (one vs rest) classification
+    pub fn multi_class(&self) -> bool {
+        self.sgd.n_targets() > 1
+    }
+
+    /// Signed distance to the separating hyperplane of each class for inputs
+    pub fn decision_function(&mut self, x: &Array2<f64>) -> Array2<f64> {
+        self.sgd.predict(x)
+    }
+
+    /// Labels from decision scores, positive scores for binary classification
+    /// and the highest score as a one hot row for multi-class classification
+    fn labels(&self, scores: &Array2<f64>) -> Array2<f64> {
+
+        if !self.multi_class() {
+            return scores.mapv(|s| if s > 0.0 { 1.0 } else { 0.0 });
+        }
+
+        let mut labels = Array2::zeros(scores.raw_dim());
+        for (idx, row) in scores.axis_iter(Axis(0)).enumerate() {
+            let (class, _) = row
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap();
+            labels[[idx, class]] = 1.0;
+        }
+        labels
+    }
+
+}
+
+
+impl Model for LinearSVM {
+
+    fn input(&self) -> Array2<f64> {
+        self.sgd.input()
+    }
+
+    fn output(&self) -> Array2<f64> {
+        self.sgd.output()
+    }
+
+    fn set_input(&mut self, x: &Array2<f64>) {
+        self.sgd.set_input(x);
+    }
+
+    fn set_output(&mut self, y: &Array2<f64>) {
+        self.sgd.set_output(y);
+    }
+
+    fn graph(&self) -> &ComputationGraph<Array2<f64>> {
+        &self.sgd.graph
+    }
+
+    fn forward(&mut self) {
+        self.sgd.forward();
+    }
+
+    fn backward(&mut self) {
+        self.sgd.backward();
+    }
+
+    fn predicted(&self) -> Array2<f64> {
+        self.labels(&self.sgd.predicted())
+    }
+
+    fn predict(&mut self, x: &Array2<f64>) -> Array2<f64> {
+        let scores = self.decision_function(x);
+        self.labels(&scores)
+    }
+
+    fn loss(&mut self) -> f64 {
+        self.sgd.loss()
+    }
+
+    fn set_loss(&mut self, op: Box<dyn Operation<Array2<f64>>>) {
+        self.sgd.set_loss(op);
+    }
+
+    fn update_parameters(&mut self) {
+        self.sgd.update_parameters();
+    }
+
+    fn update_parameter(&mut self, idx: usize, val: Array2<f64>, step: &Array2<f64>) {
+        self.sgd.update_parameter(idx, val, step);
+    }
+
+    fn regularizer(&self) -> Option<&dyn Regularizer> {
+        self.sgd.regularizer()
+    }
+
+    fn set_regularizer(&mut self, regularizer: Box<dyn Regularizer>) {
+        self.sgd.set_regularizer(regularizer);
+    }
+
+    fn sample_weight(&self) -> Option<Array2<f64>> {
+        self.sgd.sample_weight()
+    }
+
+    fn set_sample_weight(&mut self, weights: &Array2<f64>) {
+        self.sgd.set_sample_weight(weights);
+    }
+
+}
+
+
+impl ModelSerialize for LinearSVM {
+
+    fn save(&self, filepath: &str) -> std::io::Result<()> {
+
+        fs::create_dir_all(filepath)?;
+        let file_path = format!("{filepath}/parameters.json");
+
+        let obj = LinearSVMSerialize {
+            sgd: SGDSerialize {
+                graph_path: format!("{filepath}/regression_exp"),
+                weight_dim: self.sgd.weight_dim,
+                bias_dim: self.sgd.bias_dim,
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
+            },
+            lambda: self.lambda()
+        };
+
+        let _ = self.sgd.graph.save(&obj.sgd.graph_path);
+        let file = File::create(&file_path)?;
+        let mut writer = BufWriter::new(file);
+        let json_string = serde_json::to_string_pretty(&obj)?;
+        writer.write_all(json_string.as_bytes())?;
+        Ok(())
+    }
+
+    fn save_snapshot(&self, namespace: &str) -> std::io::Result<()> {
+
+        let now = Utc::now();
+        let (_, year) = now.year_ce();
+        let month = now.month().to_string();
+        let day = now.day().to_string();
+        let curr_year = year.to_string();
+
+        let directory_path = format!("{namespace}/snapshot/{curr_year}/{month}/{day}");
+        fs::create_dir_all(directory_path.clone())?;
+
+        let id = Uuid::new_v4();
+        let file_path = format!("{directory_path}/{id}.json");
+
+        let obj = LinearSVMSerialize {
+            sgd: SGDSerialize {
+                graph_path: format!("{namespace}/regression_exp"),
+                weight_dim: self.sgd.weight_dim,
+                bias_dim: self.sgd.bias_dim,
+                learning_rate: self.sgd.learning_rate,
+                regularizer: self.sgd.regularizer.clone(),
+                fit_intercept: self.sgd.fit_intercept
+            },
+            lambda: self.lambda()
+        };
+
+        let _ = self.sgd.graph.save(&obj.sgd.graph_path);
+        let file = File::create(&file_path)?;
+        let mut writer = BufWriter::new(file);
+        let json_string = serde_json::to_string_pretty(&obj)?;
+        writer.write_all(json_string.as_bytes())?;
+        Ok(())
+    }
+
+    fn load(filepath: &str) -> Result<Self, Box<dyn std::error::Error>> {
+
+        let parameter_path = format!("{filepath}/parameters.json");
+        let obj: LinearSVMSerialize = {
+            let file = File::open(&parameter_path)?;
+            let reader = BufReader::new(file);
+            serde_json::from_reader(reader)?
+        };
+
+        let sgd = SGD {
+            graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
+            weight_dim: obj.sgd.weight_dim,
+            bias_dim: obj.sgd.bias_dim,
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(L2::new(obj.lambda)))),
+            fit_intercept: obj.sgd.fit_intercept
+        };
+
+        Ok(LinearSVM { sgd })
+    }
+
+    fn load_snapshot(
+        namespace: &str,
+        year: &str,
+        month: &str,
+        day: &str,
+        snapshot_id: &str) -> Result<Self, Box<dyn std::error::Error>> {
+
+        let parameter_path = format!(
+            "{namespace}/snapshot/{year}/{month}/{day}/{snapshot_id}.json"
+        );
+
+        let obj: LinearSVMSerialize = {
+            let file = File::open(&parameter_path)?;
+            let reader = BufReader::new(file);
+            serde_json::from_reader(reader)?
+        };
+
+        let sgd = SGD {
+            graph: ComputationGraph::load(&obj.sgd.graph_path).unwrap(),
+            weight_dim: obj.sgd.weight_dim,
+            bias_dim: obj.sgd.bias_dim,
+            learning_rate: obj.sgd.learning_rate,
+            regularizer: obj.sgd.regularizer.or(Some(Box::new(L2::new(obj.lambda)))),
+            fit_intercept: obj.sgd.fit_intercept
+        };
+
+        Ok(LinearSVM { sgd })
+    }
+
+}
@@ -0,0 +1,334 @@
+//! Regularization penalties applied to weights of models
+
+use std::fmt::Debug;
+
+use ndarray::{Array2, Axis, Zip};
+use serde::{Serialize, Deserialize};
+
+
+/// Penalty on the weights of a model.
+/// The gradient of the smooth part is added to the weight gradient after every backward pass,
+/// the proximal operator of the non smooth part is applied whenever weights are updated
+/// (by the model itself or any optimizer).
+#[typetag::serde(tag = "type")]
+pub trait Regularizer: RegularizerClone + Debug {
+
+    /// Value of penalty added to the loss of a model
+    fn penalty(&self, w: &Array2<f64>) -> f64;
+
+    /// Gradient of the smooth part of the penalty
+    fn gradient(&self, w: &Array2<f64>) -> Array2<f64> {
+        Array2::zeros(w.dim())
+    }
+
+    /// Proximal operator of the non smooth part of the penalty (or projection onto a constraint)
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - Weights after update.
+    /// * `step` - Step size of update taken for each weight (adaptive optimizers scale steps per weight).
+    ///
+    fn proximal(&self, w: &Array2<f64>, _step: &Array2<f64>) -> Array2<f64> {
+        w.clone()
+    }
+
+    /// Strength `lambda` & L1 mixing `alpha` of penalties written as an elastic net
+    /// `lambda * (alpha * ||w||_1 + 1/2 * (1 - alpha) * ||w||^2)`, read by the sparse & direct solvers.
+    /// `None` for penalties & constraints outside of the elastic net family.
+    fn elastic_net(&self) -> Option<(f64, f64)> {
+        None
+    }
+
+}
+
+pub trait RegularizerClone {
+    fn clone_box(&self) -> Box<dyn Regularizer>;
+}
+
+impl<U> RegularizerClone for U
+where
+    U: 'static + Regularizer + Clone,
+{
+    fn clone_box(&self) -> Box<dyn Regularizer> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Regularizer> {
+    fn clone(&self) -> Box<dyn Regularizer> {
+        self.clone_box()
+    }
+}
+
+
+/// Soft-thresholding operator, shrinks values towards zero by threshold
+pub fn soft_threshold(x: &Array2<f64>, threshold: f64) -> Array2<f64> {
+    x.mapv(|val| shrink(val, threshold))
+}
+
+
+/// Soft-thresholding with a separate threshold for each value
+fn soft_threshold_each(x: &Array2<f64>, threshold: &Array2<f64>) -> Array2<f64> {
+    Zip::from(x).and(threshold).map_collect(|&val, &t| shrink(val, t))
+}
+
+
+/// Shrink single value towards zero by threshold
+fn shrink(val: f64, threshold: f64) -> f64 {
+    val.signum() * (val.abs() - threshold).max(0.0)
+}
+
+
+/// L1 penalty `lambda * ||w||_1`, drives weights exactly to zero
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct L1 {
+    pub lambda: f64
+}
+
+
+/// L2 penalty `lambda * ||w||^2`, shrinks weights towards zero
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct L2 {
+    pub lambda: f64
+}
+
+
+/// Elastic net penalty `lambda * (alpha * ||w||_1 + 1/2 * (1 - alpha) * ||w||^2)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElasticNet {
+    pub lambda: f64,
+    pub alpha: f64
+}
+
+
+/// Group lasso penalty `lambda * sum(sqrt(|g|) * ||w_g||)` over groups of feature rows,
+/// drives whole groups of weights to zero together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupLasso {
+    pub lambda: f64,
+    pub groups: Vec<Vec<usize>>
+}
+
+
+/// Max norm constraint, weights of each output column are rescaled to a norm of at most `max_norm`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaxNorm {
+    pub max_norm: f64
+}
+
+
+impl L1 {
+
+    /// Create L1 penalty with regularization strength
+    pub fn new(lambda: f64) -> Self {
+        L1 { lambda }
+    }
+}
+
+
+impl L2 {
+
+    /// Create L2 penalty with regularization strength
+    pub fn new(lambda: f64) -> Self {
+        L2 { lambda }
+    }
+}
+
+
+impl ElasticNet {
+
+    /// Create elastic net penalty with strength and mixing of L1 (1.0) and L2 (0.0)
+    pub fn new(lambda: f64, alpha: f64) -> Self {
+        ElasticNet { lambda, alpha }
+    }
+}
+
+
+impl GroupLasso {
+
+    /// Create group lasso penalty with strength and groups of feature indices
+    pub fn new(lambda: f64, groups: Vec<Vec<usize>>) -> Self {
+        GroupLasso { lambda, groups }
+    }
+
+    /// Norm of the weights in a group
+    fn group_norm(&self, w: &Array2<f64>, group: &[usize]) -> f64 {
+        w.select(Axis(0), group).mapv(|x| x * x).sum().sqrt()
+    }
+}
+
+
+impl MaxNorm {
+
+    /// Create max norm constraint
+    pub fn new(max_norm: f64) -> Self {
+        MaxNorm { max_norm }
+    }
+}
+
+
+#[typetag::serde]
+impl Regularizer for L1 {
+
+    fn penalty(&self, w: &Array2<f64>) -> f64 {
+        self.lambda * w.mapv(f64::abs).sum()
+    }
+
+    fn proximal(&self, w: &Array2<f64>, step: &Array2<f64>) -> Array2<f64> {
+        soft_threshold_each(w, &(step * self.lambda))
+    }
+
+    fn elastic_net(&self) -> Option<(f64, f64)> {
+        Some((self.lambda, 1.0))
+    }
+}
+
+
+#[typetag::serde]
+impl Regularizer for L2 {
+
+    fn penalty(&self, w: &Array2<f64>) -> f64 {
+        self.lambda * w.mapv(|x| x * x).sum()
+    }
+
+    fn gradient(&self, w: &Array2<f64>) -> Array2<f64> {
+        2.0 * self.lambda * w
+    }
+
+    fn elastic_net(&self) -> Option<(f64, f64)> {
+        Some((2.0 * self.lambda, 0.0))
+    }
+}
+
+
+#[typetag::serde]
+impl Regularizer for ElasticNet {
+
+    fn penalty(&self, w: &Array2<f64>) -> f64 {
+        let l1 = w.mapv(f64::abs).sum();
+        let l2 = w.mapv(|x| x * x).sum();
+        self.lambda * (self.alpha * l1 + 0.5 * (1.0 - self.alpha) * l2)
+    }
+
+    fn gradient(&self, w: &Array2<f64>) -> Array2<f64> {
+        self.lambda * (1.0 - self.alpha) * w
+    }
+
+    fn proximal(&self, w: &Array2<f64>, step: &Array2<f64>) -> Array2<f64> {
+        soft_threshold_each(w, &(step * self.lambda * self.alpha))
+    }
+
+    fn elastic_net(&self) -> Option<(f64, f64)> {
+        Some((self.lambda, self.alpha))
+    }
+}
+
+
+#[typetag::serde]
+impl Regularizer for GroupLasso {
+
+    fn penalty(&self, w: &Array2<f64>) -> f64 {
+        self.groups
+            .iter()
+            .map(|group| (group.len() as f64).sqrt() * self.group_norm(w, group))
+            .sum::<f64>() * self.lambda
+    }
+
+    fn proximal(&self, w: &Array2<f64>, step: &Array2<f64>) -> Array2<f64> {
+        let mut result = w.clone();
+        for group in &self.groups {
+            // weights of a group shrink together, so the group takes the mean of their steps
+            let group_step = step.select(Axis(0), group).mean().unwrap_or(0.0);
+            let norm = self.group_norm(w, group);
+            let threshold = group_step * self.lambda * (group.len() as f64).sqrt();
+            let scale = if norm > threshold { 1.0 - threshold / norm } else { 0.0 };
+            for idx in group {
+                result.row_mut(*idx).mapv_inplace(|x| x * scale);
+            }
+        }
+        result
+    }
+}
+
+
+#[typetag::serde]
+impl Regularizer for MaxNorm {
+
+    fn penalty(&self, _w: &Array2<f64>) -> f64 {
+        0.0
+    }
+
+    fn proximal(&self, w: &Array2<f64>, _step: &Array2<f64>) -> Array2<f64> {
+        let mut result = w.clone();
+        for mut column in result.axis_iter_mut(Axis(1)) {
+            let norm = column.mapv(|x| x * x).sum().sqrt();
+            if norm > self.max_norm {
+                column.mapv_inplace(|x| x * self.max_norm / norm);
+            }
+        }
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod regularizer_test {
+
+    use ndarray::arr2;
+    use crate::optimizer::regularizer::*;
+
+    #[test]
+    fn test_regularizers() {
+
+        let w = arr2(&[[3.0], [-0.5], [0.0], [4.0]]);
+        let step = |size: f64| Array2::from_elem(w.dim(), size);
+
+        let l1 = L1::new(0.5);
+        assert_eq!(l1.penalty(&w), 3.75);
+        assert_eq!(l1.gradient(&w), Array2::<f64>::zeros((4, 1)));
+        assert_eq!(l1.proximal(&w, &step(2.0)), arr2(&[[2.0], [0.0], [0.0], [3.0]]));
+
+        // adaptive optimizers take a different step for each weight
+        let adaptive = arr2(&[[4.0], [0.5], [1.0], [0.0]]);
+        assert_eq!(l1.proximal(&w, &adaptive), arr2(&[[1.0], [-0.25], [0.0], [4.0]]));
+
+        let l2 = L2::new(0.5);
+        assert_eq!(l2.penalty(&w), 12.625);
+        assert_eq!(l2.gradient(&w), w);
+        assert_eq!(l2.proximal(&w, &step(2.0)), w);
+
+        let elastic = ElasticNet::new(1.0, 0.5);
+        assert_eq!(elastic.penalty(&w), 0.5 * 7.5 + 0.25 * 25.25);
+        assert_eq!(elastic.gradient(&w), 0.5 * &w);
+        assert_eq!(elastic.proximal(&w, &step(1.0)), arr2(&[[2.5], [0.0], [0.0], [3.5]]));
+
+        // second group has norm below threshold and is removed
+        let group = GroupLasso::new(1.0, vec![vec![0, 3], vec![1, 2]]);
+        let prox = group.proximal(&w, &step(1.0));
+        assert_eq!(group.penalty(&w), 2.0_f64.sqrt() * (5.0 + 0.5));
+        assert_eq!(prox[[1, 0]], 0.0);
+        assert_eq!(prox[[2, 0]], 0.0);
+        assert_eq!((prox[[3, 0]] / prox[[0, 0]] * 100.0).round(), 133.0);
+
+        let max_norm = MaxNorm::new(2.5);
+        assert_eq!(max_norm.penalty(&w), 0.0);
+        let projected = max_norm.proximal(&w, &step(1.0));
+        assert!((projected.mapv(|x| x * x).sum().sqrt() - 2.5).abs() < 1e-12);
+
+        // penalties of the elastic net family have the same penalty as an elastic net
+        for regularizer in [&l1 as &dyn Regularizer, &l2, &elastic] {
+            let (lambda, alpha) = regularizer.elastic_net().unwrap();
+            assert_eq!(ElasticNet::new(lambda, alpha).penalty(&w), regularizer.penalty(&w));
+        }
+        assert!(group.elastic_net().is_none());
+        assert!(max_norm.elastic_net().is_none());
+
+        // regularizers serialize with their type
+        let boxed: Box<dyn Regularizer> = Box::new(ElasticNet::new(1.0, 0.5));
+        let json = serde_json::to_string(&boxed).unwrap();
+        let loaded: Box<dyn Regularizer> = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, "{\"type\":\"ElasticNet\",\"lambda\":1.0,\"alpha\":0.5}");
+        assert_eq!(loaded.penalty(&w), elastic.penalty(&w));
+    }
+
+}
@@ -1,10 +1,12 @@
-use rand::thread_rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use rand::prelude::SliceRandom;
 use indicatif::{ProgressBar, ProgressStyle}; 
-use ndarray::{s, Axis};
+use ndarray::{Array2, Axis};
 use serde::Serialize;
 use serde_json; 
 
+use crate::data::dataset::*;
 use crate::optimizer::model::*;
 use crate::optimizer::optimizers::Optimizer;
 use crate::optimizer::regression::elastic::*; 
@@ -12,6 +14,12 @@ use crate::optimizer::regression::lasso::*;
 use crate::optimizer::regression::ridge::*; 
 use crate::optimizer::regression::sgd::*; 
 use crate::optimizer::regression::logistic::*;
+use crate::optimizer::regression::svm::*;
+use crate::optimizer::regression::glm::*;
+
+
+/// Metric computed from true labels and model predictions (in that order)
+pub type MetricFn = fn(&Array2<f64>, &Array2<f64>) -> f64;
 
 
 /// Trainable model trait
@@ -32,14 +40,77 @@ pub trait Trainable {
     /// * `iterations` - The number of iterations to train the model.
     /// * `batch_size` - The size of each training batch.
     /// * `batch_epochs` - The number of epochs to train within each batch.
+    /// * `drop_last` - Skip the final batch when it has fewer than `batch_size` rows.
+    /// * `seed` - Seed for shuffling rows into batches, `None` shuffles differently on every call.
     ///
     fn train_batch(
         &mut self, 
         iterations: usize,
         batch_size: usize,
-        batch_epochs: usize
+        batch_epochs: usize,
+        drop_last: bool,
+        seed: Option<u64>
     );
 
+    /// Train full dataset and evaluate held out data after every epoch.
+    /// Data held out as a fraction is loaded back into the model after training,
+    /// returns an error when the model data is too small to hold out rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `epochs` - Number of iterations to train the model.
+    /// * `validation` - Validation set or fraction with metrics, records results per epoch.
+    ///
+    fn train_validate(&mut self, epochs: usize, validation: &mut Validation) -> Result<(), String>;
+
+    /// Train batches of data and evaluate held out data after every epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `iterations` - The number of iterations to train the model.
+    /// * `batch_size` - The size of each training batch.
+    /// * `batch_epochs` - The number of epochs to train within each batch.
+    /// * `drop_last` - Skip the final batch when it has fewer than `batch_size` rows.
+    /// * `seed` - Seed for shuffling rows into batches, `None` shuffles differently on every call.
+    /// * `validation` - Validation set or fraction with metrics, records results per epoch.
+    ///
+    fn train_batch_validate(
+        &mut self,
+        iterations: usize,
+        batch_size: usize,
+        batch_epochs: usize,
+        drop_last: bool,
+        seed: Option<u64>,
+        validation: &mut Validation
+    ) -> Result<(), String>;
+
+    /// Train batches read from a data loader, rows are only loaded into the model one batch at a time.
+    /// Returns an error when rows can't be read from the dataset.
+    /// The model holds the last batch afterwards, the reported loss is the mean batch loss of the last epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `loader` - Data loader with batch size & shuffling of the training rows.
+    /// * `epochs` - Number of passes over the dataset.
+    ///
+    fn train_loader<D: Dataset + ?Sized>(&mut self, loader: &mut DataLoader<D>, epochs: usize) -> Result<(), String>;
+
+    /// Train batches read from a data loader and evaluate held out data after every epoch.
+    /// Returns an error for validation fractions, or when rows can't be read from the dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `loader` - Data loader with batch size & shuffling of the training rows.
+    /// * `epochs` - Number of passes over the dataset.
+    /// * `validation` - Explicit validation set with metrics, records results per epoch.
+    ///
+    fn train_loader_validate<D: Dataset + ?Sized>(
+        &mut self,
+        loader: &mut DataLoader<D>,
+        epochs: usize,
+        validation: &mut Validation
+    ) -> Result<(), String>;
+
 }
 
 
@@ -67,6 +138,8 @@ pub trait TrainOptimizer {
     /// * `iterations` - The number of iterations to train the model.
     /// * `batch_size` - The size of each training batch.
     /// * `batch_epochs` - The number of epochs to train within each batch.
+    /// * `drop_last` - Skip the final batch when it has fewer than `batch_size` rows.
+    /// * `seed` - Seed for shuffling rows into batches, `None` shuffles differently on every call.
     /// * `optimizer` - The optimizer to use for updating parameters on each iteration.
     ///
     fn train_batch_with_optimizer<O: Optimizer>(
@@ -74,9 +147,292 @@ pub trait TrainOptimizer {
         iterations: usize,
         batch_size: usize,
         batch_epochs: usize,
+        drop_last: bool,
+        seed: Option<u64>,
         optimizer: &mut O
     );
 
+    /// Train dataset with optimizer and evaluate held out data after every epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `epochs` - The number of epochs to train on dataset.
+    /// * `validation` - Validation set or fraction with metrics, records results per epoch.
+    /// * `optimizer` - The optimizer to use for updating parameters on each iteration.
+    ///
+    fn train_validate_with_optimizer<O: Optimizer>(
+        &mut self,
+        epochs: usize,
+        validation: &mut Validation,
+        optimizer: &mut O
+    ) -> Result<(), String>;
+
+    /// Train batches of data with optimizer and evaluate held out data after every epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `iterations` - The number of iterations to train the model.
+    /// * `batch_size` - The size of each training batch.
+    /// * `batch_epochs` - The number of epochs to train within each batch.
+    /// * `drop_last` - Skip the final batch when it has fewer than `batch_size` rows.
+    /// * `seed` - Seed for shuffling rows into batches, `None` shuffles differently on every call.
+    /// * `validation` - Validation set or fraction with metrics, records results per epoch.
+    /// * `optimizer` - The optimizer to use for updating parameters on each iteration.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    fn train_batch_validate_with_optimizer<O: Optimizer>(
+        &mut self,
+        iterations: usize,
+        batch_size: usize,
+        batch_epochs: usize,
+        drop_last: bool,
+        seed: Option<u64>,
+        validation: &mut Validation,
+        optimizer: &mut O
+    ) -> Result<(), String>;
+
+    /// Train batches read from a data loader with optimizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `loader` - Data loader with batch size & shuffling of the training rows.
+    /// * `epochs` - Number of passes over the dataset.
+    /// * `optimizer` - The optimizer to use for updating parameters on each iteration.
+    ///
+    fn train_loader_with_optimizer<D: Dataset + ?Sized, O: Optimizer>(
+        &mut self,
+        loader: &mut DataLoader<D>,
+        epochs: usize,
+        optimizer: &mut O
+    ) -> Result<(), String>;
+
+    /// Train batches read from a data loader with optimizer and evaluate held out data after every epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `loader` - Data loader with batch size & shuffling of the training rows.
+    /// * `epochs` - Number of passes over the dataset.
+    /// * `validation` - Explicit validation set with metrics, records results per epoch.
+    /// * `optimizer` - The optimizer to use for updating parameters on each iteration.
+    ///
+    fn train_loader_validate_with_optimizer<D: Dataset + ?Sized, O: Optimizer>(
+        &mut self,
+        loader: &mut DataLoader<D>,
+        epochs: usize,
+        validation: &mut Validation,
+        optimizer: &mut O
+    ) -> Result<(), String>;
+
+}
+
+
+/// Held out data that is evaluated while a model trains.
+/// Either an explicit validation set is provided or a fraction of the
+/// training rows is held out. Validation data is only loaded into the
+/// model for evaluation, the training data is restored afterwards.
+/// Held out rows are loaded back into the model once training finishes,
+/// so repeated runs hold out the same rows of the full dataset.
+#[derive(Debug, Clone)]
+pub struct Validation {
+
+    /// Explicit validation features and labels
+    data: Option<(Array2<f64>, Array2<f64>)>,
+
+    /// Fraction of training rows to hold out when no validation set is provided
+    fraction: f64,
+
+    /// Seed for shuffling rows before holding out a fraction
+    seed: u64,
+
+    /// Named metrics computed on validation predictions
+    metrics: Vec<(String, MetricFn)>,
+
+    /// Results recorded after each epoch
+    history: Vec<ValidationResult>
+}
+
+
+/// Training and validation results for a single epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationResult {
+
+    /// Epoch number the results were recorded for
+    pub epoch: usize,
+
+    /// Loss of model on training data
+    pub loss: f64,
+
+    /// Loss of model on validation data
+    pub validation_loss: f64,
+
+    /// Named metrics computed on validation predictions
+    pub metrics: Vec<(String, f64)>
+}
+
+
+/// Validation rows held out of training
+struct Holdout {
+    x_val: Array2<f64>,
+    y_val: Array2<f64>
+}
+
+
+/// Load rows (and their sample weights) into the model
+fn load_batch<M: Model>(model: &mut M, batch: &Batch) {
+    model.set_input(&batch.x);
+    model.set_output(&batch.y);
+    if let Some(ref weights) = batch.sample_weight {
+        model.set_sample_weight(weights);
+    }
+}
+
+
+impl Validation {
+
+    /// Create validation from an explicit held out dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Validation input features.
+    /// * `y` - Validation target labels.
+    ///
+    pub fn new(x: &Array2<f64>, y: &Array2<f64>) -> Result<Self, String> {
+
+        if x.nrows() != y.nrows() {
+            return Err(
+                "Validation features and labels must have same number of rows".to_string()
+            );
+        }
+
+        Ok(Validation {
+            data: Some((x.clone(), y.clone())),
+            fraction: 0.0,
+            seed: 0,
+            metrics: vec![],
+            history: vec![]
+        })
+    }
+
+    /// Create validation that holds out a fraction of the training rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - Fraction of rows between 0 and 1 to hold out for validation.
+    /// * `seed` - Seed for shuffling rows before splitting.
+    ///
+    pub fn split(fraction: f64, seed: u64) -> Result<Self, String> {
+
+        if fraction <= 0.0 || fraction >= 1.0 {
+            return Err(
+                "Validation fraction must be between 0 and 1".to_string()
+            );
+        }
+
+        Ok(Validation {
+            data: None,
+            fraction,
+            seed,
+            metrics: vec![],
+            history: vec![]
+        })
+    }
+
+    /// Add named metric computed on validation predictions each epoch
+    pub fn add_metric(&mut self, name: &str, metric: MetricFn) {
+        self.metrics.push((name.to_string(), metric));
+    }
+
+    /// Results recorded for each epoch of the most recent training run
+    pub fn history(&self) -> &[ValidationResult] {
+        &self.history
+    }
+
+    /// Most recent validation result
+    pub fn last(&self) -> Option<&ValidationResult> {
+        self.history.last()
+    }
+
+    /// Separate validation data from the training data loaded in the model
+    fn holdout<M: Model>(&self, model: &M) -> Result<(Batch, Holdout), String> {
+
+        let x = model.input();
+        let y = model.output();
+        let w = model.sample_weight();
+
+        match &self.data {
+            Some((x_val, y_val)) => {
+                let train = Batch { x, y, sample_weight: w };
+                Ok((train, Holdout { x_val: x_val.clone(), y_val: y_val.clone() }))
+            },
+            None => {
+                let rows = x.nrows();
+                if rows < 2 {
+                    return Err("At least 2 rows are required to hold out validation data".to_string());
+                }
+
+                let mut indices: Vec<usize> = (0..rows).collect();
+                indices.shuffle(&mut StdRng::seed_from_u64(self.seed));
+
+                let n_val = ((rows as f64 * self.fraction).round() as usize).clamp(1, rows - 1);
+                let (val_idxs, train_idxs) = indices.split_at(n_val);
+
+                let train = Batch {
+                    x: x.select(Axis(0), train_idxs),
+                    y: y.select(Axis(0), train_idxs),
+                    sample_weight: w.map(|weights| weights.select(Axis(0), train_idxs))
+                };
+
+                let holdout = Holdout {
+                    x_val: x.select(Axis(0), val_idxs),
+                    y_val: y.select(Axis(0), val_idxs)
+                };
+
+                Ok((train, holdout))
+            }
+        }
+    }
+
+    /// Explicit validation data, training from a data loader can't hold out a fraction of the model data
+    fn loader_holdout(&self) -> Result<Holdout, String> {
+        match &self.data {
+            Some((x_val, y_val)) => Ok(Holdout { x_val: x_val.clone(), y_val: y_val.clone() }),
+            None => Err(
+                "Validation fractions need the training data loaded in the model, split the dataset to validate a data loader".to_string()
+            )
+        }
+    }
+
+    /// Evaluate validation data (with uniform sample weights) and restore training rows in the model.
+    /// The training loss is computed on the restored rows unless provided.
+    fn evaluate<M: Model>(
+        &mut self,
+        model: &mut M,
+        holdout: &Holdout,
+        restore: &Batch,
+        loss: Option<f64>,
+        epoch: usize) {
+
+        model.set_input(&holdout.x_val);
+        model.set_output(&holdout.y_val);
+        model.forward();
+
+        let validation_loss = model.loss();
+        let predicted = model.predicted();
+        let metrics = self.metrics
+            .iter()
+            .map(|(name, metric)| (name.clone(), metric(&holdout.y_val, &predicted)))
+            .collect();
+
+        load_batch(model, restore);
+        model.forward();
+
+        self.history.push(ValidationResult {
+            epoch,
+            loss: loss.unwrap_or(model.loss()),
+            validation_loss,
+            metrics
+        });
+    }
 }
 
 
@@ -90,113 +446,316 @@ struct TrainingResult {
     loss_decrease: f64, 
 
     /// Current batch number of training
-    batch_number: String
+    batch_number: String,
+
+    /// Loss of model on validation data (if provided)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation_loss: Option<f64>
 }
 
 
-macro_rules! train_default {
+/// Data loaded in the model before training
+fn model_data<M: Model>(model: &M) -> Batch {
+    Batch {
+        x: model.input(),
+        y: model.output(),
+        sample_weight: model.sample_weight()
+    }
+}
 
-    ($t:ty) => {
 
-        impl Trainable for $t {
+/// Shared full dataset training loop for models.
+/// When validation is provided the held out rows are evaluated after every epoch,
+/// the data loaded in the model before training (including held out rows) is restored afterwards.
+fn fit_epochs<M: Model, F: FnMut(&mut M)>(
+    model: &mut M,
+    epochs: usize,
+    mut validation: Option<&mut Validation>,
+    mut step: F) -> Result<(), String> {
 
-            fn train(&mut self, epochs: usize) {
+    let original = model_data(model);
+    let holdout = match validation.as_ref() {
+        Some(val) => Some(val.holdout(model)?),
+        None => None
+    };
 
-                let bar = ProgressBar::new(epochs.try_into().unwrap());
-                    bar.set_style(ProgressStyle::default_bar()
-                        .template("{bar:50} {pos}/{len}")
-                        .unwrap());
+    if let Some((ref train, _)) = holdout {
+        load_batch(model, train);
+    }
 
-                for _ in 0..epochs {
-                    self.forward();
-                    self.backward();
-                    self.update_parameters();
-                    bar.inc(1); 
-                }
+    if let Some(ref mut val) = validation {
+        val.history.clear();
+    }
 
-                bar.finish();
+    let bar = ProgressBar::new(epochs.try_into().unwrap());
+    bar.set_style(ProgressStyle::default_bar()
+        .template("{bar:50} {pos}/{len}")
+        .unwrap());
 
-                let total_loss = self.loss();
-                println!(
-                    "Loss: {:?}, Epochs: {:?}", 
-                    total_loss,
-                    epochs
-                );
+    for epoch in 0..epochs {
+        model.forward();
+        model.backward();
+        step(model);
 
-            }
+        if let (Some(val), Some((train, data))) = (validation.as_mut(), holdout.as_ref()) {
+            val.evaluate(model, data, train, None, epoch + 1);
+        }
+        bar.inc(1);
+    }
 
-            
-            fn train_batch(
-                &mut self, 
-                iterations: usize,
-                batch_size: usize,
-                batch_epochs: usize) {
+    bar.finish();
 
-                let x_train = self.input();
-                let y_train = self.output(); 
-                let rows = x_train.nrows();
-                let num_batches = (rows + batch_size - 1) / batch_size;
-                let mut curr_loss = 0.00;
+    let total_loss = model.loss();
+    if holdout.is_some() {
+        load_batch(model, &original);
+        model.forward();
+    }
 
-                for iteration in 0..iterations {
+    match validation.and_then(|val| val.last().cloned()) {
+        Some(result) => {
+            println!(
+                "Loss: {:?}, Validation Loss: {:?}, Epochs: {:?}",
+                result.loss,
+                result.validation_loss,
+                epochs
+            );
+        },
+        None => {
+            println!(
+                "Loss: {:?}, Epochs: {:?}",
+                total_loss,
+                epochs
+            );
+        }
+    }
 
-                    let bar = ProgressBar::new(batch_epochs.try_into().unwrap());
-                    bar.set_style(ProgressStyle::default_bar()
-                        .template("{bar:50} {pos}/{len}")
-                        .unwrap());
-                
-                    for _epoch in 0..batch_epochs {
+    Ok(())
+}
 
-                        let mut row_indices: Vec<_> = (0..rows).collect();
-                        row_indices.shuffle(&mut thread_rng());
 
-                        let x_shuffled = x_train.select(Axis(0), &row_indices);
-                        let y_shuffled = y_train.select(Axis(0), &row_indices);
+/// Shared mini-batch training loop for models, over the training data loaded in the model.
+/// The rows are wrapped in a dataset and shuffled into batches by a data loader with `options`.
+/// The full training set is restored after each iteration,
+/// so the reported loss (and any later `loss()` or `predicted()` call) covers the whole dataset.
+/// Held out validation rows are loaded back into the model after training.
+fn fit_batches<M: Model, F: FnMut(&mut M)>(
+    model: &mut M,
+    iterations: usize,
+    batch_epochs: usize,
+    options: LoaderOptions,
+    validation: Option<&mut Validation>,
+    step: F) -> Result<(), String> {
+
+    let original = model_data(model);
+    let (train, holdout) = match validation.as_ref() {
+        Some(val) => {
+            let (train, holdout) = val.holdout(model)?;
+            (train, Some(holdout))
+        },
+        None => (original.clone(), None)
+    };
+
+    let dataset = ArrayDataset::from_batch(train)?;
+    let mut loader = DataLoader::new(&dataset, options)?;
+
+    fit_loader(
+        model,
+        &mut loader,
+        iterations,
+        batch_epochs,
+        Some(dataset.data()),
+        validation.zip(holdout.as_ref()),
+        step
+    )?;
+
+    if holdout.is_some() {
+        load_batch(model, &original);
+        model.forward();
+    }
 
+    Ok(())
+}
 
-                        for batch_idx in 0..num_batches { 
-                            let start_idx = batch_idx * batch_size;
-                            let end_idx = (start_idx + batch_size).min(rows);
-                            let x = x_shuffled.slice(s![start_idx..end_idx, ..]);
-                            let y = y_shuffled.slice(s![start_idx..end_idx, ..]);
 
-                            // fix this later
-                            if (end_idx - start_idx) < batch_size {
-                                continue; 
-                            }
+/// Shared data loader training loop for models.
+/// Every batch is loaded into the model before calling `step` to update parameters.
+/// After each iteration the loss is computed on `full` when provided (rows held in memory),
+/// otherwise it is the mean batch loss of the last epoch.
+fn fit_loader<M: Model, D: Dataset + ?Sized, F: FnMut(&mut M)>(
+    model: &mut M,
+    loader: &mut DataLoader<D>,
+    iterations: usize,
+    batch_epochs: usize,
+    full: Option<&Batch>,
+    mut validation: Option<(&mut Validation, &Holdout)>,
+    mut step: F) -> Result<(), String> {
+
+    if let Some((ref mut val, _)) = validation {
+        val.history.clear();
+    }
 
-                            self.set_input(&x.to_owned());
-                            self.set_output(&y.to_owned());
+    let mut curr_loss = 0.00;
 
-                            self.forward();
-                            self.backward();
+    for iteration in 0..iterations {
 
-                            self.update_parameters();
+        let bar = ProgressBar::new(batch_epochs.try_into().unwrap());
+        bar.set_style(ProgressStyle::default_bar()
+            .template("{bar:50} {pos}/{len}")
+            .unwrap());
 
-                        }
+        let mut epoch_loss = f64::NAN;
+        let mut last: Option<Batch> = None;
+    
+        for epoch in 0..batch_epochs {
+
+            let (mut loss_sum, mut rows) = (0.0, 0);
+            for batch in loader.batches() {
+                let batch = batch?;
+                load_batch(model, &batch);
+                model.forward();
+                model.backward();
+                step(model);
+
+                loss_sum += model.loss() * batch.x.nrows() as f64;
+                rows += batch.x.nrows();
+                last = Some(batch);
+            }
 
-                        bar.inc(1); 
-                    }
+            if rows > 0 {
+                epoch_loss = loss_sum / rows as f64;
+            }
 
-                    bar.finish();
+            if let Some((ref mut val, data)) = validation {
+                let epoch_number = iteration * batch_epochs + epoch + 1;
+                match (full, last.as_ref()) {
+                    (Some(rows), _) => val.evaluate(model, data, rows, None, epoch_number),
+                    (None, Some(batch)) => val.evaluate(model, data, batch, Some(epoch_loss), epoch_number),
+                    (None, None) => {}
+                }
+            }
 
+            bar.inc(1); 
+        }
 
+        bar.finish();
+
+        let total_loss = match full {
+            Some(rows) => {
+                load_batch(model, rows);
+                model.forward();
+                model.loss()
+            },
+            None => epoch_loss
+        };
+
+        let result = TrainingResult {
+            loss: total_loss,
+            loss_decrease: curr_loss - total_loss,
+            batch_number: format!("{:?}/{:?}", iteration+1, iterations),
+            validation_loss: validation
+                .as_ref()
+                .and_then(|(val, _)| val.last())
+                .map(|last| last.validation_loss)
+        };
+        let json = serde_json::to_string_pretty(&result).unwrap();
+        println!("{}", json); 
+        curr_loss = total_loss; 
+        println!();
+    }
 
-                    let total_loss = self.loss();
-                    let result = TrainingResult {
-                        loss: total_loss,
-                        loss_decrease: curr_loss - total_loss,
-                        batch_number: format!("{:?}/{:?}", iteration+1, iterations)
-                    };
-                    let json = serde_json::to_string_pretty(&result).unwrap();
-                    println!("{}", json); 
-                    curr_loss = total_loss; 
-                    println!(""); 
-                }
+    Ok(())
+}
+
+
+macro_rules! train_default {
+
+    ($t:ty) => {
+
+        impl Trainable for $t {
+
+            fn train(&mut self, epochs: usize) {
+                fit_epochs(
+                    self,
+                    epochs,
+                    None,
+                    |model| model.update_parameters()
+                ).unwrap_or_else(|err| panic!("{}", err));
+            }
+            
+            fn train_batch(
+                &mut self, 
+                iterations: usize,
+                batch_size: usize,
+                batch_epochs: usize,
+                drop_last: bool,
+                seed: Option<u64>) {
+
+                fit_batches(
+                    self,
+                    iterations,
+                    batch_epochs,
+                    LoaderOptions { batch_size, shuffle: true, seed, drop_last },
+                    None,
+                    |model| model.update_parameters()
+                ).unwrap_or_else(|err| panic!("{}", err));
+            }
 
+            fn train_validate(&mut self, epochs: usize, validation: &mut Validation) -> Result<(), String> {
+                fit_epochs(
+                    self,
+                    epochs,
+                    Some(validation),
+                    |model| model.update_parameters()
+                )
+            }
 
+            fn train_batch_validate(
+                &mut self,
+                iterations: usize,
+                batch_size: usize,
+                batch_epochs: usize,
+                drop_last: bool,
+                seed: Option<u64>,
+                validation: &mut Validation) -> Result<(), String> {
+
+                fit_batches(
+                    self,
+                    iterations,
+                    batch_epochs,
+                    LoaderOptions { batch_size, shuffle: true, seed, drop_last },
+                    Some(validation),
+                    |model| model.update_parameters()
+                )
+            }
 
+            fn train_loader<D: Dataset + ?Sized>(&mut self, loader: &mut DataLoader<D>, epochs: usize) -> Result<(), String> {
+                fit_loader(
+                    self,
+                    loader,
+                    1,
+                    epochs,
+                    None,
+                    None,
+                    |model| model.update_parameters()
+                )
+            }
 
+            fn train_loader_validate<D: Dataset + ?Sized>(
+                &mut self,
+                loader: &mut DataLoader<D>,
+                epochs: usize,
+                validation: &mut Validation) -> Result<(), String> {
+
+                let holdout = validation.loader_holdout()?;
+                fit_loader(
+                    self,
+                    loader,
+                    1,
+                    epochs,
+                    None,
+                    Some((validation, &holdout)),
+                    |model| model.update_parameters()
+                )
             }
 
         }
@@ -210,6 +769,8 @@ train_default!(Logistic);
 train_default!(Ridge);
 train_default!(Lasso); 
 train_default!(Elastic); 
+train_default!(LinearSVM);
+train_default!(GLM);
 
 
 macro_rules! train_optimizer {
@@ -219,98 +780,101 @@ macro_rules! train_optimizer {
         impl TrainOptimizer for $t {
 
             fn train_with_optimizer<O: Optimizer>(&mut self, epochs: usize, optimizer: &mut O) {
-
-                let bar = ProgressBar::new(epochs.try_into().unwrap());
-                    bar.set_style(ProgressStyle::default_bar()
-                        .template("{bar:50} {pos}/{len}")
-                        .unwrap());
-
-                for _ in 0..epochs {
-                    self.forward();
-                    self.backward();
-                    optimizer.step(self);
-                    bar.inc(1); 
-                }
-
-                bar.finish();
-
-                let total_loss = self.loss();
-                println!(
-                    "Loss: {:?}, Epochs: {:?}", 
-                    total_loss,
-                    epochs
-                );
-
+                fit_epochs(
+                    self,
+                    epochs,
+                    None,
+                    |model| optimizer.step(model)
+                ).unwrap_or_else(|err| panic!("{}", err));
             }
 
-
             fn train_batch_with_optimizer<O: Optimizer>(
                 &mut self, 
                 iterations: usize,
                 batch_size: usize,
                 batch_epochs: usize,
+                drop_last: bool,
+                seed: Option<u64>,
                 optimizer: &mut O) {
 
-                let x_train = self.input();
-                let y_train = self.output(); 
-                let rows = x_train.nrows();
-                let num_batches = (rows + batch_size - 1) / batch_size;
-                let mut curr_loss = 0.00;
-
-                for iteration in 0..iterations {
-
-                    let bar = ProgressBar::new(batch_epochs.try_into().unwrap());
-                    bar.set_style(ProgressStyle::default_bar()
-                        .template("{bar:50} {pos}/{len}")
-                        .unwrap());
-                
-                    for _epoch in 0..batch_epochs {
-
-                        let mut row_indices: Vec<_> = (0..rows).collect();
-                        row_indices.shuffle(&mut thread_rng());
-
-                        let x_shuffled = x_train.select(Axis(0), &row_indices);
-                        let y_shuffled = y_train.select(Axis(0), &row_indices);
-
-                        for batch_idx in 0..num_batches { 
-                            let start_idx = batch_idx * batch_size;
-                            let end_idx = (start_idx + batch_size).min(rows);
-                            let x = x_shuffled.slice(s![start_idx..end_idx, ..]);
-                            let y = y_shuffled.slice(s![start_idx..end_idx, ..]);
-
-                            // fix this later
-                            if (end_idx - start_idx) < batch_size {
-                                continue; 
-                            }
-
-                            self.set_input(&x.to_owned());
-                            self.set_output(&y.to_owned());
-
-                            self.forward();
-                            self.backward();
-
-                            optimizer.step(self);
-
-                        }
-
-                        bar.inc(1); 
-                    }
+                fit_batches(
+                    self,
+                    iterations,
+                    batch_epochs,
+                    LoaderOptions { batch_size, shuffle: true, seed, drop_last },
+                    None,
+                    |model| optimizer.step(model)
+                ).unwrap_or_else(|err| panic!("{}", err));
+            }
 
-                    bar.finish();
+            fn train_validate_with_optimizer<O: Optimizer>(
+                &mut self,
+                epochs: usize,
+                validation: &mut Validation,
+                optimizer: &mut O) -> Result<(), String> {
+
+                fit_epochs(
+                    self,
+                    epochs,
+                    Some(validation),
+                    |model| optimizer.step(model)
+                )
+            }
 
-                    let total_loss = self.loss();
-                    let result = TrainingResult {
-                        loss: total_loss,
-                        loss_decrease: curr_loss - total_loss,
-                        batch_number: format!("{:?}/{:?}", iteration+1, iterations)
-                    };
-                    let json = serde_json::to_string_pretty(&result).unwrap();
-                    println!("{}", json); 
-                    curr_loss = total_loss; 
-                    println!(""); 
-                }
+            fn train_batch_validate_with_optimizer<O: Optimizer>(
+                &mut self,
+                iterations: usize,
+                batch_size: usize,
+                batch_epochs: usize,
+                drop_last: bool,
+                seed: Option<u64>,
+                validation: &mut Validation,
+                optimizer: &mut O) -> Result<(), String> {
+
+                fit_batches(
+                    self,
+                    iterations,
+                    batch_epochs,
+                    LoaderOptions { batch_size, shuffle: true, seed, drop_last },
+                    Some(validation),
+                    |model| optimizer.step(model)
+                )
+            }
 
+            fn train_loader_with_optimizer<D: Dataset + ?Sized, O: Optimizer>(
+                &mut self,
+                loader: &mut DataLoader<D>,
+                epochs: usize,
+                optimizer: &mut O) -> Result<(), String> {
+
+                fit_loader(
+                    self,
+                    loader,
+                    1,
+                    epochs,
+                    None,
+                    None,
+                    |model| optimizer.step(model)
+                )
+            }
 
+            fn train_loader_validate_with_optimizer<D: Dataset + ?Sized, O: Optimizer>(
+                &mut self,
+                loader: &mut DataLoader<D>,
+                epochs: usize,
+                validation: &mut Validation,
+                optimizer: &mut O) -> Result<(), String> {
+
+                let holdout = validation.loader_holdout()?;
+                fit_loader(
+                    self,
+                    loader,
+                    1,
+                    epochs,
+                    None,
+                    Some((validation, &holdout)),
+                    |model| optimizer.step(model)
+                )
             }
 
         }
@@ -320,4 +884,10 @@ macro_rules! train_optimizer {
 }
 
 
-train_optimizer!(SGD); 
+train_optimizer!(SGD);
+train_optimizer!(Logistic);
+train_optimizer!(Ridge);
+train_optimizer!(Lasso);
+train_optimizer!(Elastic); 
+train_optimizer!(LinearSVM);
+train_optimizer!(GLM);
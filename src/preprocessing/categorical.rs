@@ -0,0 +1,659 @@
+//! Ordinal, target & hashing encoders for categorical features given as
+//! `f64` codes or strings
+
+use std::collections::HashMap;
+
+use ndarray::{Array2, ArrayView1, ArrayView2, Axis};
+use serde::{Serialize, Deserialize};
+
+use crate::preprocessing::processor::*;
+
+
+/// Shared behavior of encoders for categorical features given as strings.
+/// Encoders implementing it also implement `FeatureEncoder` for `f64` codes,
+/// codes are treated as categories named by their decimal representation.
+pub trait CategoricalEncoder {
+
+    /// Learn categories of each feature from data, replacing previous fits
+    fn fit_categories(&mut self, data: &ArrayView2<String>) -> Result<(), String>;
+
+    /// Encode categories with the fitted statistics of the encoder
+    fn transform_categories(&self, data: &ArrayView2<String>) -> Result<Array2<f64>, String>;
+
+    /// Fit encoder to categories and encode the same categories
+    fn fit_transform_categories(&mut self, data: &ArrayView2<String>) -> Result<Array2<f64>, String> {
+        self.fit_categories(data)?;
+        self.transform_categories(data)
+    }
+
+}
+
+
+/// Category names of `f64` codes, missing values (NaN) are an error
+pub fn to_categories(data: &ArrayView2<f64>) -> Result<Array2<String>, String> {
+    if data.iter().any(|x| x.is_nan()) {
+        return Err("Categories cannot be NaN, impute them first".to_string());
+    }
+    Ok(data.mapv(|x| x.to_string()))
+}
+
+
+/// Sort categories numerically when every category is a number, otherwise lexicographically
+fn sort_categories(categories: &mut [String]) {
+    let numeric: Option<Vec<f64>> = categories.iter().map(|c| c.parse().ok()).collect();
+    match numeric {
+        Some(_) => categories.sort_by(|a, b| {
+            a.parse::<f64>().unwrap().total_cmp(&b.parse::<f64>().unwrap())
+        }),
+        None => categories.sort()
+    }
+}
+
+
+/// Hash of category, FNV-1a so hashed features are stable across builds & platforms
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+
+/// Ordinal encoder, replaces each category with its index among the sorted categories of the feature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrdinalEncoder {
+
+    /// Sorted categories of each feature learned at fit time
+    categories: Vec<Vec<String>>,
+
+    /// Code of unknown categories, unknown categories are an error when not set
+    unknown_value: Option<f64>
+}
+
+
+impl OrdinalEncoder {
+
+    /// Create instance of ordinal encoder.
+    ///
+    /// # Arguments
+    ///
+    /// * `unknown_value` - Code of categories not seen at fit time, `None` to return an error.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let colors = arr2(&[["red"], ["green"], ["blue"], ["green"]]).mapv(String::from);
+    /// let mut encoder = OrdinalEncoder::new(Some(-1.0));
+    /// let encoded = encoder.fit_transform_categories(&colors.view()).unwrap();
+    /// assert_eq!(encoded, arr2(&[[2.0], [1.0], [0.0], [1.0]]));
+    /// ```
+    pub fn new(unknown_value: Option<f64>) -> Self {
+        Self {
+            categories: vec![],
+            unknown_value
+        }
+    }
+
+    /// Retrieve sorted categories of each feature
+    pub fn categories(&self) -> &Vec<Vec<String>> {
+        &self.categories
+    }
+
+    /// Decode codes to category names, unknown codes decode to an empty string
+    pub fn inverse_transform_categories(&self, data: &ArrayView2<f64>) -> Result<Array2<String>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.categories.len())?;
+
+        let mut decoded = Array2::from_elem(data.dim(), String::new());
+        for ((row, col), code) in data.indexed_iter() {
+            let categories = &self.categories[col];
+            if *code >= 0.0 && code.fract() == 0.0 && (*code as usize) < categories.len() {
+                decoded[[row, col]] = categories[*code as usize].clone();
+            }
+        }
+        Ok(decoded)
+    }
+
+}
+
+
+impl CategoricalEncoder for OrdinalEncoder {
+
+    fn fit_categories(&mut self, data: &ArrayView2<String>) -> Result<(), String> {
+
+        if data.nrows() == 0 || data.ncols() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        self.categories = data
+            .axis_iter(Axis(1))
+            .map(|col| {
+                let mut categories = col.to_vec();
+                sort_categories(&mut categories);
+                categories.dedup();
+                categories
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    fn transform_categories(&self, data: &ArrayView2<String>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.categories.len())?;
+
+        let mut encoded = Array2::zeros(data.dim());
+        for ((row, col), category) in data.indexed_iter() {
+            let code = self.categories[col].iter().position(|c| c == category);
+            encoded[[row, col]] = match (code, self.unknown_value) {
+                (Some(code), _) => code as f64,
+                (None, Some(value)) => value,
+                (None, None) => {
+                    return Err(format!(
+                        "Unknown category {} of feature {} at row {}", category, col, row
+                    ));
+                }
+            };
+        }
+        Ok(encoded)
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for OrdinalEncoder {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+        self.fit_categories(&to_categories(data)?.view())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.categories.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        self.transform_categories(&to_categories(data)?.view())
+    }
+
+    /// Decode codes to the `f64` categories, unknown codes & string categories decode to NaN
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        let decoded = self.inverse_transform_categories(data)?;
+        Ok(decoded.mapv(|c| c.parse().unwrap_or(f64::NAN)))
+    }
+
+}
+
+
+/// Target encoder, replaces each category with the mean target of its rows shrunk
+/// towards the overall mean: `(n * mean + smoothing * prior) / (n + smoothing)`.
+/// `fit_transform` encodes the training rows out of fold (with statistics of the other folds)
+/// so the encoding of a row never includes its own target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetEncoder {
+
+    /// Weight of the overall mean in the encoding of each category
+    smoothing: f64,
+
+    /// Number of folds (rows `i % n_folds`) used by `fit_transform`
+    n_folds: usize,
+
+    /// Target column encodings are learned from, not kept after saving
+    #[serde(skip)]
+    targets: Vec<f64>,
+
+    /// Mean of the target, encoding of unknown categories
+    prior: f64,
+
+    /// Encoding of each category of each feature
+    encodings: Vec<HashMap<String, f64>>
+}
+
+
+impl TargetEncoder {
+
+    /// Create instance of target encoder.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - Target column (regression or binary labels) with a row per sample.
+    /// * `smoothing` - Weight of the overall mean in the encoding of each category.
+    /// * `n_folds` - Number of folds for out of fold encoding of the training data.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let cities = arr2(&[["a"], ["a"], ["b"], ["b"]]).mapv(String::from);
+    /// let y = arr2(&[[1.0], [3.0], [10.0], [10.0]]);
+    ///
+    /// let mut encoder = TargetEncoder::new(&y, 0.0, 2).unwrap();
+    /// encoder.fit_categories(&cities.view()).unwrap();
+    /// let encoded = encoder.transform_categories(&cities.view()).unwrap();
+    /// assert_eq!(encoded, arr2(&[[2.0], [2.0], [10.0], [10.0]]));
+    /// ```
+    pub fn new(y: &Array2<f64>, smoothing: f64, n_folds: usize) -> Result<Self, String> {
+
+        if y.ncols() != 1 {
+            return Err("Target encoder requires a single target column".to_string());
+        }
+
+        if y.iter().any(|v| v.is_nan()) {
+            return Err("Targets cannot be NaN".to_string());
+        }
+
+        if smoothing.is_nan() || smoothing < 0.0 {
+            return Err("Smoothing must be non negative".to_string());
+        }
+
+        if n_folds < 2 {
+            return Err("Number of folds must be at least 2".to_string());
+        }
+
+        Ok(Self {
+            smoothing,
+            n_folds,
+            targets: y.column(0).to_vec(),
+            prior: 0.0,
+            encodings: vec![]
+        })
+    }
+
+    /// Retrieve mean of the target, encoding of unknown categories
+    pub fn prior(&self) -> f64 {
+        self.prior
+    }
+
+    /// Retrieve encoding of each category of each feature
+    pub fn encodings(&self) -> &Vec<HashMap<String, f64>> {
+        &self.encodings
+    }
+
+    /// Prior & category encodings learned from selected rows
+    fn learn(&self, data: &ArrayView2<String>, rows: &[usize]) -> (f64, Vec<HashMap<String, f64>>) {
+
+        let prior = rows.iter().map(|row| self.targets[*row]).sum::<f64>() / rows.len() as f64;
+        let encodings = data
+            .axis_iter(Axis(1))
+            .map(|col| {
+                let mut stats: HashMap<String, (f64, f64)> = HashMap::new();
+                for row in rows {
+                    let entry = stats.entry(col[*row].clone()).or_insert((0.0, 0.0));
+                    entry.0 += self.targets[*row];
+                    entry.1 += 1.0;
+                }
+
+                stats
+                    .into_iter()
+                    .map(|(category, (sum, n))| {
+                        (category, (sum + self.smoothing * prior) / (n + self.smoothing))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        (prior, encodings)
+    }
+
+    /// Encode categories with selected prior & encodings
+    fn encode(
+        data: &ArrayView2<String>,
+        prior: f64,
+        encodings: &[HashMap<String, f64>]) -> Array2<f64> {
+
+        let mut encoded = Array2::zeros(data.dim());
+        for ((row, col), category) in data.indexed_iter() {
+            encoded[[row, col]] = *encodings[col].get(category).unwrap_or(&prior);
+        }
+        encoded
+    }
+
+    /// Error for data with a different number of rows than the targets
+    fn check_targets(&self, data: &ArrayView2<String>) -> Result<(), String> {
+        if data.nrows() != self.targets.len() || data.nrows() == 0 {
+            return Err(format!(
+                "Expected {} rows matching the targets, got {}", self.targets.len(), data.nrows()
+            ));
+        }
+        Ok(())
+    }
+
+}
+
+
+impl CategoricalEncoder for TargetEncoder {
+
+    fn fit_categories(&mut self, data: &ArrayView2<String>) -> Result<(), String> {
+
+        self.check_targets(data)?;
+        let rows: Vec<usize> = (0..data.nrows()).collect();
+        let (prior, encodings) = self.learn(data, &rows);
+        self.prior = prior;
+        self.encodings = encodings;
+        Ok(())
+    }
+
+    fn transform_categories(&self, data: &ArrayView2<String>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.encodings.len())?;
+
+        Ok(TargetEncoder::encode(data, self.prior, &self.encodings))
+    }
+
+    /// Fit on all rows and encode each fold with statistics of the other folds
+    fn fit_transform_categories(&mut self, data: &ArrayView2<String>) -> Result<Array2<f64>, String> {
+
+        self.fit_categories(data)?;
+        if data.nrows() < self.n_folds {
+            return Err(format!(
+                "Need at least {} rows for {} folds", self.n_folds, self.n_folds
+            ));
+        }
+
+        let mut encoded = Array2::zeros(data.dim());
+        for fold in 0..self.n_folds {
+            let (held_out, rest): (Vec<usize>, Vec<usize>) = (0..data.nrows())
+                .partition(|row| row % self.n_folds == fold);
+
+            let (prior, encodings) = self.learn(data, &rest);
+            let fold_data = data.select(Axis(0), &held_out);
+            let fold_encoded = TargetEncoder::encode(&fold_data.view(), prior, &encodings);
+            for (idx, row) in held_out.iter().enumerate() {
+                encoded.row_mut(*row).assign(&fold_encoded.row(idx));
+            }
+        }
+
+        Ok(encoded)
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for TargetEncoder {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+        self.fit_categories(&to_categories(data)?.view())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.encodings.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        self.transform_categories(&to_categories(data)?.view())
+    }
+
+    /// Encodings are not unique per category, so they can't be decoded
+    fn inverse_transform(&self, _data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        Err("Target encodings cannot be decoded".to_string())
+    }
+
+    fn fit_transform(&mut self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        self.fit_transform_categories(&to_categories(data)?.view())
+    }
+
+}
+
+
+/// Feature hasher, hashes each (feature, category) pair into one of a fixed number of
+/// output columns so high cardinality features need no vocabulary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureHasher {
+
+    /// Number of output columns
+    n_features: usize,
+
+    /// Alternate the sign of hashed values so collisions tend to cancel out
+    alternate_sign: bool,
+
+    /// Number of input columns the hasher was fit on
+    n_inputs: usize
+}
+
+
+impl FeatureHasher {
+
+    /// Create instance of feature hasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_features` - Number of output columns.
+    /// * `alternate_sign` - Alternate the sign of hashed values.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let users = arr2(&[["u-1", "fr"], ["u-2", "de"]]).mapv(String::from);
+    /// let mut hasher = FeatureHasher::new(16, false).unwrap();
+    /// let encoded = hasher.fit_transform_categories(&users.view()).unwrap();
+    /// assert_eq!(encoded.dim(), (2, 16));
+    /// assert_eq!(encoded.row(0).sum(), 2.0);
+    /// ```
+    pub fn new(n_features: usize, alternate_sign: bool) -> Result<Self, String> {
+
+        if n_features == 0 {
+            return Err("Number of output features must be positive".to_string());
+        }
+
+        Ok(Self {
+            n_features,
+            alternate_sign,
+            n_inputs: 0
+        })
+    }
+
+    /// Retrieve number of output columns
+    pub fn n_features(&self) -> usize {
+        self.n_features
+    }
+
+    /// Output column & sign of a category of a feature
+    fn bucket(&self, feature: usize, category: &str) -> (usize, f64) {
+        let hash = fnv1a(&format!("{feature}={category}"));
+        let sign = match self.alternate_sign && hash >> 63 == 1 {
+            true => -1.0,
+            false => 1.0
+        };
+        ((hash % self.n_features as u64) as usize, sign)
+    }
+
+    /// Add hashed categories of a row to the output row
+    fn hash_row(&self, row: ArrayView1<String>, output: &mut [f64]) {
+        for (feature, category) in row.iter().enumerate() {
+            let (idx, sign) = self.bucket(feature, category);
+            output[idx] += sign;
+        }
+    }
+
+}
+
+
+impl CategoricalEncoder for FeatureHasher {
+
+    fn fit_categories(&mut self, data: &ArrayView2<String>) -> Result<(), String> {
+
+        if data.ncols() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        self.n_inputs = data.ncols();
+        Ok(())
+    }
+
+    fn transform_categories(&self, data: &ArrayView2<String>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.n_inputs)?;
+
+        let mut encoded = Array2::zeros((data.nrows(), self.n_features));
+        for (row, mut output) in data.axis_iter(Axis(0)).zip(encoded.axis_iter_mut(Axis(0))) {
+            self.hash_row(row, output.as_slice_mut().unwrap());
+        }
+        Ok(encoded)
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for FeatureHasher {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+        self.fit_categories(&to_categories(data)?.view())
+    }
+
+    fn is_fitted(&self) -> bool {
+        self.n_inputs > 0
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        self.transform_categories(&to_categories(data)?.view())
+    }
+
+    /// Hashing is not invertible
+    fn inverse_transform(&self, _data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        Err("Hashed features cannot be decoded".to_string())
+    }
+
+}
+
+
+#[cfg(test)]
+mod categorical_tests {
+
+    use std::fs;
+    use ndarray::arr2;
+    use crate::preprocessing::categorical::*;
+
+
+    #[test]
+    fn test_ordinal_encoder() -> std::io::Result<()> {
+
+        let x = arr2(&[
+            ["red", "small"],
+            ["green", "large"],
+            ["blue", "small"],
+            ["green", "medium"]
+        ]).mapv(String::from);
+
+        let mut encoder = OrdinalEncoder::new(None);
+        assert!(encoder.transform_categories(&x.view()).is_err());
+
+        let encoded = encoder.fit_transform_categories(&x.view()).unwrap();
+        assert_eq!(encoder.categories()[0], vec!["blue", "green", "red"]);
+        assert_eq!(
+            encoded,
+            arr2(&[[2.0, 2.0], [1.0, 0.0], [0.0, 2.0], [1.0, 1.0]])
+        );
+        assert_eq!(encoder.inverse_transform_categories(&encoded.view()).unwrap(), x);
+
+        let unseen = arr2(&[["purple", "small"]]).mapv(String::from);
+        assert!(encoder.transform_categories(&unseen.view()).is_err());
+
+        let mut lenient = OrdinalEncoder::new(Some(-1.0));
+        lenient.fit_categories(&x.view()).unwrap();
+        assert_eq!(lenient.transform_categories(&unseen.view()).unwrap(), arr2(&[[-1.0, 2.0]]));
+
+        // numeric codes are sorted numerically
+        let codes = arr2(&[[10.0], [-2.5], [3.0], [10.0]]);
+        let mut numeric = OrdinalEncoder::new(None);
+        let encoded = numeric.fit_transform(&codes.view()).unwrap();
+        assert_eq!(encoded, arr2(&[[2.0], [0.0], [1.0], [2.0]]));
+        assert_eq!(numeric.inverse_transform(&encoded.view()).unwrap(), codes);
+        assert!(numeric.fit(&arr2(&[[f64::NAN]]).view()).is_err());
+
+        encoder.save("data/ordinal_encoder", "encoder")?;
+        let loaded = OrdinalEncoder::load("data/ordinal_encoder", "encoder").unwrap();
+        assert_eq!(
+            loaded.transform_categories(&x.view()).unwrap(),
+            encoder.transform_categories(&x.view()).unwrap()
+        );
+        fs::remove_dir_all("data/ordinal_encoder")?;
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_target_encoder() {
+
+        let x = arr2(&[["a"], ["a"], ["b"], ["b"], ["a"], ["b"]]).mapv(String::from);
+        let y = arr2(&[[1.0], [3.0], [10.0], [12.0], [2.0], [14.0]]);
+
+        assert!(TargetEncoder::new(&arr2(&[[1.0, 2.0]]), 1.0, 2).is_err());
+        assert!(TargetEncoder::new(&y, -1.0, 2).is_err());
+        assert!(TargetEncoder::new(&y, 1.0, 1).is_err());
+
+        let mut encoder = TargetEncoder::new(&y, 3.0, 2).unwrap();
+        encoder.fit_categories(&x.view()).unwrap();
+        assert_eq!(encoder.prior(), 7.0);
+
+        // (n * mean + smoothing * prior) / (n + smoothing)
+        assert_eq!(encoder.encodings()[0]["a"], (6.0 + 21.0) / 6.0);
+        assert_eq!(encoder.encodings()[0]["b"], (36.0 + 21.0) / 6.0);
+
+        let unseen = arr2(&[["c"]]).mapv(String::from);
+        assert_eq!(encoder.transform_categories(&unseen.view()).unwrap(), arr2(&[[7.0]]));
+
+        // out of fold encodings never use the target of the encoded row
+        let mut unsmoothed = TargetEncoder::new(&y, 0.0, 2).unwrap();
+        let encoded = unsmoothed.fit_transform_categories(&x.view()).unwrap();
+        assert_eq!(
+            encoded.column(0).to_vec(),
+            vec![3.0, 1.5, 13.0, 10.0, 3.0, 10.0]
+        );
+
+        let full = unsmoothed.transform_categories(&x.view()).unwrap();
+        assert_eq!(full.column(0).to_vec(), vec![2.0, 2.0, 12.0, 12.0, 2.0, 12.0]);
+
+        let codes = arr2(&[[0.0], [0.0], [1.0], [1.0], [0.0], [1.0]]);
+        let mut numeric = TargetEncoder::new(&y, 0.0, 2).unwrap();
+        assert_eq!(numeric.fit_transform(&codes.view()).unwrap(), encoded);
+        assert!(numeric.inverse_transform(&encoded.view()).is_err());
+        assert!(numeric.fit(&codes.slice(ndarray::s![0..3, ..])).is_err());
+    }
+
+
+    #[test]
+    fn test_feature_hasher() {
+
+        let x = arr2(&[
+            ["user-1", "fr"],
+            ["user-2", "de"],
+            ["user-1", "de"]
+        ]).mapv(String::from);
+
+        assert!(FeatureHasher::new(0, false).is_err());
+
+        let mut hasher = FeatureHasher::new(8, false).unwrap();
+        let encoded = hasher.fit_transform_categories(&x.view()).unwrap();
+        assert_eq!(encoded.dim(), (3, 8));
+        for row in encoded.rows() {
+            assert_eq!(row.sum(), 2.0);
+        }
+
+        // same category of the same feature lands in the same column
+        let (first, _) = hasher.bucket(0, "user-1");
+        assert!(encoded[[0, first]] >= 1.0 && encoded[[2, first]] >= 1.0);
+        assert_eq!(hasher.transform_categories(&x.view()).unwrap(), encoded);
+
+        // hashes are stable (FNV-1a reference values)
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+
+        let mut signed = FeatureHasher::new(1024, true).unwrap();
+        let encoded = signed.fit_transform_categories(&x.view()).unwrap();
+        assert!(encoded.iter().all(|v| *v == 0.0 || v.abs() == 1.0));
+
+        let codes = arr2(&[[1.0, 2.0]]);
+        let mut numeric = FeatureHasher::new(4, false).unwrap();
+        assert_eq!(numeric.fit_transform(&codes.view()).unwrap().sum(), 2.0);
+        assert!(numeric.inverse_transform(&codes.view()).is_err());
+        assert!(numeric.transform(&arr2(&[[1.0]]).view()).is_err());
+    }
+
+}
@@ -0,0 +1,645 @@
+//! Feature generation with polynomial terms, splines & binning, so linear models
+//! can fit nonlinear relationships
+
+use ndarray::{s, Array2, ArrayView1, ArrayView2, Axis};
+use serde::{Serialize, Deserialize};
+
+use crate::preprocessing::processor::*;
+use crate::preprocessing::scaler::quantile;
+
+
+/// Polynomial features, products of input features up to a degree
+/// (ordered by degree, then by feature index) with an optional bias column of ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolynomialFeatures {
+
+    /// Highest degree of generated terms
+    degree: usize,
+
+    /// Only products of distinct features (no powers of a single feature)
+    interaction_only: bool,
+
+    /// Prepend a column of ones
+    include_bias: bool,
+
+    /// Number of input features the transformer was fit on
+    n_inputs: usize,
+
+    /// Feature indices multiplied together for each generated term
+    terms: Vec<Vec<usize>>
+}
+
+
+impl PolynomialFeatures {
+
+    /// Create instance of polynomial features.
+    ///
+    /// # Arguments
+    ///
+    /// * `degree` - Highest degree of generated terms, at least 1.
+    /// * `interaction_only` - Only products of distinct features.
+    /// * `include_bias` - Prepend a column of ones.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[2.0, 3.0]]);
+    /// let mut poly = PolynomialFeatures::new(2, false, true).unwrap();
+    /// let encoded = poly.fit_transform(&x.view()).unwrap();
+    ///
+    /// // 1, a, b, a^2, ab, b^2
+    /// assert_eq!(encoded, arr2(&[[1.0, 2.0, 3.0, 4.0, 6.0, 9.0]]));
+    /// ```
+    pub fn new(degree: usize, interaction_only: bool, include_bias: bool) -> Result<Self, String> {
+
+        if degree == 0 {
+            return Err("Degree must be at least 1".to_string());
+        }
+
+        Ok(Self {
+            degree,
+            interaction_only,
+            include_bias,
+            n_inputs: 0,
+            terms: vec![]
+        })
+    }
+
+    /// Retrieve feature indices multiplied together for each generated term
+    pub fn terms(&self) -> &Vec<Vec<usize>> {
+        &self.terms
+    }
+
+    /// Feature index combinations of a degree, extending the combinations of the previous degree
+    fn combinations(&self, previous: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let mut terms = vec![];
+        for term in previous {
+            let last = *term.last().unwrap();
+            let start = if self.interaction_only { last + 1 } else { last };
+            for feature in start..self.n_inputs {
+                let mut next = term.clone();
+                next.push(feature);
+                terms.push(next);
+            }
+        }
+        terms
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for PolynomialFeatures {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.ncols() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        self.n_inputs = data.ncols();
+        let mut degree_terms: Vec<Vec<usize>> = (0..self.n_inputs).map(|f| vec![f]).collect();
+        self.terms = vec![];
+        for _ in 0..self.degree {
+            self.terms.extend(degree_terms.iter().cloned());
+            degree_terms = self.combinations(&degree_terms);
+        }
+
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        self.n_inputs > 0
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.n_inputs)?;
+
+        let bias = self.include_bias as usize;
+        let mut encoded = Array2::ones((data.nrows(), self.terms.len() + bias));
+        for (idx, term) in self.terms.iter().enumerate() {
+            let mut col = encoded.column_mut(idx + bias);
+            for feature in term {
+                col *= &data.column(*feature);
+            }
+        }
+        Ok(encoded)
+    }
+
+    /// Recover input features from their degree one terms
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        let bias = self.include_bias as usize;
+        check_columns(data, self.terms.len() + bias)?;
+        Ok(data.slice(s![.., bias..bias + self.n_inputs]).to_owned())
+    }
+
+}
+
+
+/// Placement of bin edges of the discretizer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BinStrategy {
+
+    /// Bins of equal width between the smallest & largest value
+    Uniform,
+
+    /// Bins with an equal number of samples
+    Quantile,
+
+    /// Bins around centers found with 1D k-means
+    KMeans
+}
+
+
+/// Output of the discretizer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BinEncoding {
+
+    /// Bin index of each feature
+    Ordinal,
+
+    /// One hot column per bin of each feature
+    OneHot
+}
+
+
+/// Discretizer, replaces continuous features with the bin they fall in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KBinsDiscretizer {
+
+    /// Number of bins of each feature
+    n_bins: usize,
+
+    /// Placement of bin edges
+    strategy: BinStrategy,
+
+    /// Output of bin indices or one hot columns
+    encode: BinEncoding,
+
+    /// Bin edges of each feature, bins may be merged when edges coincide
+    edges: Vec<Vec<f64>>
+}
+
+
+impl KBinsDiscretizer {
+
+    /// Create instance of bins discretizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_bins` - Number of bins of each feature, at least 2.
+    /// * `strategy` - Placement of bin edges.
+    /// * `encode` - Output of bin indices or one hot columns.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[0.0], [1.0], [2.5], [4.0]]);
+    /// let mut bins = KBinsDiscretizer::new(2, BinStrategy::Uniform, BinEncoding::Ordinal).unwrap();
+    /// let encoded = bins.fit_transform(&x.view()).unwrap();
+    /// assert_eq!(encoded, arr2(&[[0.0], [0.0], [1.0], [1.0]]));
+    /// ```
+    pub fn new(n_bins: usize, strategy: BinStrategy, encode: BinEncoding) -> Result<Self, String> {
+
+        if n_bins < 2 {
+            return Err("Number of bins must be at least 2".to_string());
+        }
+
+        Ok(Self {
+            n_bins,
+            strategy,
+            encode,
+            edges: vec![]
+        })
+    }
+
+    /// Retrieve bin edges of each feature
+    pub fn edges(&self) -> &Vec<Vec<f64>> {
+        &self.edges
+    }
+
+    /// Bin edges of a feature
+    fn fit_edges(&self, col: &ArrayView1<f64>) -> Vec<f64> {
+
+        let min = col.fold(f64::INFINITY, |a, &b| a.min(b));
+        let max = col.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let n = self.n_bins;
+
+        let mut edges: Vec<f64> = match self.strategy {
+            BinStrategy::Uniform => {
+                (0..=n).map(|i| min + (max - min) * i as f64 / n as f64).collect()
+            },
+            BinStrategy::Quantile => {
+                (0..=n).map(|i| quantile(col, i as f64 / n as f64)).collect()
+            },
+            BinStrategy::KMeans => {
+                let centers = KBinsDiscretizer::kmeans(col, min, max, n);
+                let mut edges = vec![min];
+                edges.extend(centers.windows(2).map(|pair| (pair[0] + pair[1]) / 2.0));
+                edges.push(max);
+                edges
+            }
+        };
+
+        edges.dedup();
+        edges
+    }
+
+    /// Centers of 1D k-means, initialized at the centers of uniform bins
+    fn kmeans(col: &ArrayView1<f64>, min: f64, max: f64, k: usize) -> Vec<f64> {
+
+        let width = (max - min) / k as f64;
+        let mut centers: Vec<f64> = (0..k).map(|i| min + width * (i as f64 + 0.5)).collect();
+
+        for _ in 0..100 {
+            let mut sums = vec![0.0; k];
+            let mut counts = vec![0usize; k];
+            for x in col.iter() {
+                let nearest = (0..k)
+                    .min_by(|a, b| (x - centers[*a]).abs().total_cmp(&(x - centers[*b]).abs()))
+                    .unwrap();
+                sums[nearest] += x;
+                counts[nearest] += 1;
+            }
+
+            let updated: Vec<f64> = (0..k)
+                .map(|i| if counts[i] > 0 { sums[i] / counts[i] as f64 } else { centers[i] })
+                .collect();
+
+            let converged = updated.iter().zip(centers.iter()).all(|(a, b)| (a - b).abs() < 1e-12);
+            centers = updated;
+            if converged {
+                break;
+            }
+        }
+
+        centers.sort_by(|a, b| a.total_cmp(b));
+        centers
+    }
+
+    /// Bin index of value, values outside the fitted range go to the first & last bin
+    fn bin(edges: &[f64], x: f64) -> usize {
+        if edges.len() < 2 {
+            return 0;
+        }
+        let inner = &edges[1..edges.len() - 1];
+        inner.iter().filter(|edge| x >= **edge).count()
+    }
+
+    /// Number of bins of each feature
+    fn bins(&self) -> Vec<usize> {
+        self.edges.iter().map(|e| (e.len() - 1).max(1)).collect()
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for KBinsDiscretizer {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        if data.iter().any(|x| x.is_nan()) {
+            return Err("Data contains missing values (NaN), impute them first".to_string());
+        }
+
+        self.edges = data
+            .axis_iter(Axis(1))
+            .map(|col| self.fit_edges(&col))
+            .collect();
+
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.edges.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.edges.len())?;
+
+        let bins = self.bins();
+        let width = match self.encode {
+            BinEncoding::Ordinal => self.edges.len(),
+            BinEncoding::OneHot => bins.iter().sum()
+        };
+
+        let mut encoded = Array2::zeros((data.nrows(), width));
+        let mut offset = 0;
+        for (feature, col) in data.axis_iter(Axis(1)).enumerate() {
+            for (row, x) in col.iter().enumerate() {
+                let bin = KBinsDiscretizer::bin(&self.edges[feature], *x);
+                match self.encode {
+                    BinEncoding::Ordinal => encoded[[row, feature]] = bin as f64,
+                    BinEncoding::OneHot => encoded[[row, offset + bin]] = 1.0
+                }
+            }
+            offset += bins[feature];
+        }
+
+        Ok(encoded)
+    }
+
+    /// Decode bins to the center of each bin
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        let bins = self.bins();
+        match self.encode {
+            BinEncoding::Ordinal => check_columns(data, self.edges.len())?,
+            BinEncoding::OneHot => check_columns(data, bins.iter().sum())?
+        };
+
+        let mut decoded = Array2::zeros((data.nrows(), self.edges.len()));
+        let mut offset = 0;
+        for (feature, edges) in self.edges.iter().enumerate() {
+            for row in 0..data.nrows() {
+                let bin = match self.encode {
+                    BinEncoding::Ordinal => data[[row, feature]].max(0.0) as usize,
+                    BinEncoding::OneHot => {
+                        let block = data.slice(s![row, offset..offset + bins[feature]]);
+                        block.iter().position(|x| *x == 1.0).unwrap_or(0)
+                    }
+                };
+                let bin = bin.min(bins[feature] - 1);
+                let upper = edges.get(bin + 1).unwrap_or(&edges[bin]);
+                decoded[[row, feature]] = (edges[bin] + upper) / 2.0;
+            }
+            offset += bins[feature];
+        }
+
+        Ok(decoded)
+    }
+
+}
+
+
+/// Spline transformer, expands each feature into a B-spline basis with uniformly spaced knots
+/// between the smallest & largest value. Values outside the fitted range are clamped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplineTransformer {
+
+    /// Number of knots spanning the range of each feature
+    n_knots: usize,
+
+    /// Degree of the spline pieces (3 for cubic splines)
+    degree: usize,
+
+    /// Knots of each feature, extended by `degree` knots past each end of the range
+    knots: Vec<Vec<f64>>
+}
+
+
+impl SplineTransformer {
+
+    /// Create instance of spline transformer, each feature produces `n_knots + degree - 1` columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_knots` - Number of knots spanning the range of each feature, at least 2.
+    /// * `degree` - Degree of the spline pieces.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[0.0], [0.5], [1.0]]);
+    /// let mut splines = SplineTransformer::new(4, 3).unwrap();
+    /// let encoded = splines.fit_transform(&x.view()).unwrap();
+    /// assert_eq!(encoded.dim(), (3, 6));
+    /// ```
+    pub fn new(n_knots: usize, degree: usize) -> Result<Self, String> {
+
+        if n_knots < 2 {
+            return Err("Number of knots must be at least 2".to_string());
+        }
+
+        Ok(Self {
+            n_knots,
+            degree,
+            knots: vec![]
+        })
+    }
+
+    /// Number of basis functions of each feature
+    pub fn n_splines(&self) -> usize {
+        self.n_knots + self.degree - 1
+    }
+
+    /// Values of all B-spline basis functions at a point (Cox-de Boor recursion)
+    fn basis(&self, knots: &[f64], x: f64) -> Vec<f64> {
+
+        let min = knots[self.degree];
+        let max = knots[self.degree + self.n_knots - 1];
+        let x = x.clamp(min, max);
+
+        let mut values: Vec<f64> = knots
+            .windows(2)
+            .map(|pair| if pair[0] <= x && x < pair[1] { 1.0 } else { 0.0 })
+            .collect();
+
+        for k in 1..=self.degree {
+            values = (0..values.len() - 1)
+                .map(|i| {
+                    let left = (x - knots[i]) / (knots[i + k] - knots[i]);
+                    let right = (knots[i + k + 1] - x) / (knots[i + k + 1] - knots[i + 1]);
+                    left * values[i] + right * values[i + 1]
+                })
+                .collect();
+        }
+
+        values
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for SplineTransformer {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        let mut knots = vec![];
+        for (idx, col) in data.axis_iter(Axis(1)).enumerate() {
+            let min = col.fold(f64::INFINITY, |a, &b| a.min(b));
+            let max = col.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+            if min >= max {
+                return Err(format!("Feature {} needs at least two distinct values", idx));
+            }
+
+            let spacing = (max - min) / (self.n_knots - 1) as f64;
+            let first = -(self.degree as isize);
+            let last = (self.n_knots + self.degree) as isize;
+            knots.push((first..last).map(|i| min + spacing * i as f64).collect());
+        }
+
+        self.knots = knots;
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.knots.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.knots.len())?;
+
+        let n_splines = self.n_splines();
+        let mut encoded = Array2::zeros((data.nrows(), self.knots.len() * n_splines));
+        for (feature, col) in data.axis_iter(Axis(1)).enumerate() {
+            for (row, x) in col.iter().enumerate() {
+                let values = self.basis(&self.knots[feature], *x);
+                let offset = feature * n_splines;
+                for (idx, value) in values.iter().enumerate() {
+                    encoded[[row, offset + idx]] = *value;
+                }
+            }
+        }
+
+        Ok(encoded)
+    }
+
+    /// Spline bases overlap, so features can't be decoded
+    fn inverse_transform(&self, _data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        Err("Spline features cannot be decoded".to_string())
+    }
+
+}
+
+
+#[cfg(test)]
+mod features_tests {
+
+    use ndarray::{arr2, Array2};
+    use crate::preprocessing::features::*;
+
+
+    #[test]
+    fn test_polynomial_features() {
+
+        let x = arr2(&[[2.0, 3.0, 5.0], [1.0, -1.0, 0.5]]);
+
+        assert!(PolynomialFeatures::new(0, false, true).is_err());
+
+        let mut poly = PolynomialFeatures::new(2, false, false).unwrap();
+        let encoded = poly.fit_transform(&x.view()).unwrap();
+        assert_eq!(poly.terms().len(), 9);
+        assert_eq!(
+            encoded.row(0).to_vec(),
+            vec![2.0, 3.0, 5.0, 4.0, 6.0, 10.0, 9.0, 15.0, 25.0]
+        );
+        assert_eq!(poly.inverse_transform(&encoded.view()).unwrap(), x);
+
+        let mut interactions = PolynomialFeatures::new(3, true, true).unwrap();
+        let encoded = interactions.fit_transform(&x.view()).unwrap();
+        assert_eq!(
+            interactions.terms(),
+            &vec![
+                vec![0], vec![1], vec![2],
+                vec![0, 1], vec![0, 2], vec![1, 2],
+                vec![0, 1, 2]
+            ]
+        );
+        assert_eq!(
+            encoded.row(0).to_vec(),
+            vec![1.0, 2.0, 3.0, 5.0, 6.0, 10.0, 15.0, 30.0]
+        );
+        assert_eq!(interactions.inverse_transform(&encoded.view()).unwrap(), x);
+
+        let mut cubic = PolynomialFeatures::new(3, false, false).unwrap();
+        cubic.fit(&x.slice(ndarray::s![.., 0..1])).unwrap();
+        assert_eq!(cubic.transform(&arr2(&[[2.0]]).view()).unwrap(), arr2(&[[2.0, 4.0, 8.0]]));
+        assert!(cubic.transform(&x.view()).is_err());
+    }
+
+
+    #[test]
+    fn test_bins_discretizer() {
+
+        let x = arr2(&[
+            [0.0, 1.0],
+            [1.0, 1.0],
+            [2.0, 1.1],
+            [3.0, 9.0],
+            [10.0, 9.5],
+            [11.0, 10.0]
+        ]);
+
+        assert!(KBinsDiscretizer::new(1, BinStrategy::Uniform, BinEncoding::Ordinal).is_err());
+
+        let mut uniform = KBinsDiscretizer::new(2, BinStrategy::Uniform, BinEncoding::Ordinal).unwrap();
+        let encoded = uniform.fit_transform(&x.view()).unwrap();
+        assert_eq!(uniform.edges()[0], vec![0.0, 5.5, 11.0]);
+        assert_eq!(encoded.column(0).to_vec(), vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+
+        // values outside the fitted range go to the edge bins
+        let outside = uniform.transform(&arr2(&[[-5.0, 50.0]]).view()).unwrap();
+        assert_eq!(outside, arr2(&[[0.0, 1.0]]));
+
+        let decoded = uniform.inverse_transform(&encoded.view()).unwrap();
+        assert_eq!(decoded.column(0).to_vec(), vec![2.75, 2.75, 2.75, 2.75, 8.25, 8.25]);
+
+        let mut quantile = KBinsDiscretizer::new(3, BinStrategy::Quantile, BinEncoding::Ordinal).unwrap();
+        let encoded = quantile.fit_transform(&x.view()).unwrap();
+        assert_eq!(encoded.column(0).to_vec(), vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+
+        let mut kmeans = KBinsDiscretizer::new(2, BinStrategy::KMeans, BinEncoding::OneHot).unwrap();
+        let encoded = kmeans.fit_transform(&x.view()).unwrap();
+        assert_eq!(kmeans.edges()[0], vec![0.0, 6.0, 11.0]);
+        assert_eq!(encoded.dim(), (6, 4));
+        assert_eq!(encoded.row(2).to_vec(), vec![1.0, 0.0, 1.0, 0.0]);
+        assert_eq!(encoded.row(4).to_vec(), vec![0.0, 1.0, 0.0, 1.0]);
+
+        let decoded = kmeans.inverse_transform(&encoded.view()).unwrap();
+        assert_eq!(decoded[[0, 0]], 3.0);
+    }
+
+
+    #[test]
+    fn test_spline_transformer() {
+
+        let x = Array2::from_shape_fn((11, 1), |(i, _)| i as f64 / 10.0);
+
+        assert!(SplineTransformer::new(1, 3).is_err());
+
+        let mut splines = SplineTransformer::new(5, 3).unwrap();
+        let encoded = splines.fit_transform(&x.view()).unwrap();
+        assert_eq!(encoded.dim(), (11, 7));
+
+        // bases are non negative & sum to one over the fitted range
+        for row in encoded.rows() {
+            assert!(row.iter().all(|v| *v >= 0.0));
+            assert!((row.sum() - 1.0).abs() < 1e-12);
+        }
+
+        // linear splines interpolate between knots
+        let mut linear = SplineTransformer::new(3, 1).unwrap();
+        linear.fit(&x.view()).unwrap();
+        let encoded = linear.transform(&arr2(&[[0.25], [1.0], [7.0]]).view()).unwrap();
+        assert!((encoded.row(0).to_owned() - arr2(&[[0.5, 0.5, 0.0]]).row(0)).mapv(f64::abs).sum() < 1e-12);
+        assert_eq!(encoded.row(1).to_vec(), vec![0.0, 0.0, 1.0]);
+        assert_eq!(encoded.row(2), encoded.row(1));
+
+        assert!(SplineTransformer::new(3, 1).unwrap().fit(&arr2(&[[1.0], [1.0]]).view()).is_err());
+        assert!(linear.inverse_transform(&encoded.view()).is_err());
+    }
+
+}
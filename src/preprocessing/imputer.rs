@@ -0,0 +1,514 @@
+//! Imputation of missing values (NaN) with fitted fill values
+
+use ndarray::{concatenate, s, Array2, ArrayView1, ArrayView2, Axis};
+use serde::{Serialize, Deserialize};
+
+use crate::preprocessing::processor::*;
+use crate::preprocessing::scaler::quantile;
+
+
+/// Columns of data with at least one missing value
+fn missing_columns(data: &ArrayView2<f64>) -> Vec<usize> {
+    data.axis_iter(Axis(1))
+        .enumerate()
+        .filter(|(_, col)| col.iter().any(|x| x.is_nan()))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+
+/// Append indicator columns (1 where missing) of selected features to imputed data
+fn with_indicator(
+    data: &ArrayView2<f64>,
+    imputed: Array2<f64>,
+    columns: &[usize]) -> Array2<f64> {
+
+    let indicator = data
+        .select(Axis(1), columns)
+        .mapv(|x| if x.is_nan() { 1.0 } else { 0.0 });
+    concatenate(Axis(1), &[imputed.view(), indicator.view()]).unwrap()
+}
+
+
+/// Remove indicator columns from imputed data and restore missing values they mark
+fn restore_missing(
+    data: &ArrayView2<f64>,
+    n_features: usize,
+    columns: &[usize]) -> Array2<f64> {
+
+    let mut decoded = data.slice(s![.., 0..n_features]).to_owned();
+    for (idx, col) in columns.iter().enumerate() {
+        let indicator = data.column(n_features + idx);
+        for (row, flag) in indicator.iter().enumerate() {
+            if *flag == 1.0 {
+                decoded[[row, *col]] = f64::NAN;
+            }
+        }
+    }
+    decoded
+}
+
+
+/// Serialization of arrays with missing values, JSON has no NaN so they are stored as null
+mod missing_values {
+
+    use ndarray::Array2;
+    use serde::{Serialize, Deserialize, Serializer, Deserializer};
+
+    pub fn serialize<S: Serializer>(data: &Array2<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        let values: Vec<Option<f64>> = data
+            .iter()
+            .map(|x| if x.is_nan() { None } else { Some(*x) })
+            .collect();
+        (data.dim(), values).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Array2<f64>, D::Error> {
+        let (dim, values): ((usize, usize), Vec<Option<f64>>) = Deserialize::deserialize(deserializer)?;
+        let values: Vec<f64> = values.iter().map(|x| x.unwrap_or(f64::NAN)).collect();
+        Array2::from_shape_vec(dim, values).map_err(serde::de::Error::custom)
+    }
+
+}
+
+
+/// Value used to fill missing values of a feature
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ImputeStrategy {
+
+    /// Mean of observed values
+    Mean,
+
+    /// Median of observed values
+    Median,
+
+    /// Most frequent observed value (smallest on ties)
+    MostFrequent,
+
+    /// Constant fill value
+    Constant(f64)
+}
+
+
+impl ImputeStrategy {
+
+    /// Fill value of a feature from its observed values
+    fn fill_value(&self, col: &ArrayView1<f64>) -> Option<f64> {
+
+        if let ImputeStrategy::Constant(value) = self {
+            return Some(*value);
+        }
+
+        let mut observed: Vec<f64> = col.iter().copied().filter(|x| !x.is_nan()).collect();
+        if observed.is_empty() {
+            return None;
+        }
+
+        match self {
+            ImputeStrategy::Mean => {
+                Some(observed.iter().sum::<f64>() / observed.len() as f64)
+            },
+            ImputeStrategy::Median => {
+                Some(quantile(&ArrayView1::from(&observed), 0.5))
+            },
+            ImputeStrategy::MostFrequent => {
+                observed.sort_by(|a, b| a.total_cmp(b));
+                let mut best = (observed[0], 0);
+                let mut idx = 0;
+                while idx < observed.len() {
+                    let count = observed[idx..].iter().take_while(|x| **x == observed[idx]).count();
+                    if count > best.1 {
+                        best = (observed[idx], count);
+                    }
+                    idx += count;
+                }
+                Some(best.0)
+            },
+            ImputeStrategy::Constant(value) => Some(*value)
+        }
+    }
+
+}
+
+
+/// Simple imputer, fills missing values (NaN) of each feature with a value learned at fit time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleImputer {
+
+    /// Value used to fill missing values
+    strategy: ImputeStrategy,
+
+    /// Append a column per feature with missing values at fit time, 1 where a value was missing
+    add_indicator: bool,
+
+    /// Fill value of each feature
+    fill_values: Vec<f64>,
+
+    /// Features with missing values at fit time
+    indicator_columns: Vec<usize>
+}
+
+
+impl SimpleImputer {
+
+    /// Create instance of simple imputer.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - Value used to fill missing values.
+    /// * `add_indicator` - Append missing indicator columns to the output.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[1.0, 2.0], [f64::NAN, 4.0], [3.0, f64::NAN]]);
+    /// let mut imputer = SimpleImputer::new(ImputeStrategy::Mean, false);
+    /// let imputed = imputer.fit_transform(&x.view()).unwrap();
+    /// assert_eq!(imputed, arr2(&[[1.0, 2.0], [2.0, 4.0], [3.0, 3.0]]));
+    /// ```
+    pub fn new(strategy: ImputeStrategy, add_indicator: bool) -> Self {
+        Self {
+            strategy,
+            add_indicator,
+            fill_values: vec![],
+            indicator_columns: vec![]
+        }
+    }
+
+    /// Retrieve fill value of each feature
+    pub fn fill_values(&self) -> &Vec<f64> {
+        &self.fill_values
+    }
+
+    /// Retrieve features with missing indicator columns
+    pub fn indicator_columns(&self) -> &Vec<usize> {
+        &self.indicator_columns
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for SimpleImputer {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        let mut fill_values = vec![];
+        for (idx, col) in data.axis_iter(Axis(1)).enumerate() {
+            match self.strategy.fill_value(&col) {
+                Some(value) => fill_values.push(value),
+                None => return Err(format!("Feature {} has no observed values", idx))
+            }
+        }
+
+        self.fill_values = fill_values;
+        self.indicator_columns = match self.add_indicator {
+            true => missing_columns(data),
+            false => vec![]
+        };
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.fill_values.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.fill_values.len())?;
+
+        let mut imputed = data.to_owned();
+        for (mut col, fill) in imputed.axis_iter_mut(Axis(1)).zip(self.fill_values.iter()) {
+            col.mapv_inplace(|x| if x.is_nan() { *fill } else { x });
+        }
+
+        Ok(with_indicator(data, imputed, &self.indicator_columns))
+    }
+
+    /// Drop indicator columns, values marked missing by an indicator are restored to NaN
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.fill_values.len() + self.indicator_columns.len())?;
+        Ok(restore_missing(data, self.fill_values.len(), &self.indicator_columns))
+    }
+
+}
+
+
+/// K nearest neighbour imputer, fills missing values with the mean of the feature over
+/// the closest rows of the training data (nan euclidean distance over observed features)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KNNImputer {
+
+    /// Number of neighbours averaged
+    n_neighbors: usize,
+
+    /// Append a column per feature with missing values at fit time, 1 where a value was missing
+    add_indicator: bool,
+
+    /// Training rows neighbours are taken from
+    #[serde(with = "missing_values")]
+    reference: Array2<f64>,
+
+    /// Mean of each feature, used when no neighbour has the feature
+    means: Vec<f64>,
+
+    /// Features with missing values at fit time
+    indicator_columns: Vec<usize>
+}
+
+
+impl KNNImputer {
+
+    /// Create instance of k nearest neighbour imputer.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_neighbors` - Number of neighbours averaged.
+    /// * `add_indicator` - Append missing indicator columns to the output.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[1.0, 1.0], [1.1, 1.2], [9.0, 9.0], [1.05, f64::NAN]]);
+    /// let mut imputer = KNNImputer::new(2, false).unwrap();
+    /// let imputed = imputer.fit_transform(&x.view()).unwrap();
+    /// assert!((imputed[[3, 1]] - 1.1).abs() < 1e-12);
+    /// ```
+    pub fn new(n_neighbors: usize, add_indicator: bool) -> Result<Self, String> {
+
+        if n_neighbors == 0 {
+            return Err("Number of neighbors must be positive".to_string());
+        }
+
+        Ok(Self {
+            n_neighbors,
+            add_indicator,
+            reference: Array2::zeros((0, 0)),
+            means: vec![],
+            indicator_columns: vec![]
+        })
+    }
+
+    /// Retrieve number of neighbours averaged
+    pub fn n_neighbors(&self) -> usize {
+        self.n_neighbors
+    }
+
+    /// Euclidean distance over features observed in both rows, scaled up by the
+    /// fraction of observed features. `None` when no feature is observed in both.
+    fn distance(a: &ArrayView1<f64>, b: &ArrayView1<f64>) -> Option<f64> {
+
+        let mut present = 0;
+        let mut sum = 0.0;
+        for (x, y) in a.iter().zip(b.iter()) {
+            if !x.is_nan() && !y.is_nan() {
+                present += 1;
+                sum += (x - y) * (x - y);
+            }
+        }
+
+        match present {
+            0 => None,
+            _ => Some((a.len() as f64 / present as f64 * sum).sqrt())
+        }
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for KNNImputer {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        let mut means = vec![];
+        for (idx, col) in data.axis_iter(Axis(1)).enumerate() {
+            match ImputeStrategy::Mean.fill_value(&col) {
+                Some(value) => means.push(value),
+                None => return Err(format!("Feature {} has no observed values", idx))
+            }
+        }
+
+        self.means = means;
+        self.reference = data.to_owned();
+        self.indicator_columns = match self.add_indicator {
+            true => missing_columns(data),
+            false => vec![]
+        };
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.means.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.means.len())?;
+
+        let mut imputed = data.to_owned();
+        for (row_idx, row) in data.axis_iter(Axis(0)).enumerate() {
+
+            if !row.iter().any(|x| x.is_nan()) {
+                continue;
+            }
+
+            let mut neighbours: Vec<(f64, usize)> = self.reference
+                .axis_iter(Axis(0))
+                .enumerate()
+                .filter_map(|(idx, other)| {
+                    KNNImputer::distance(&row, &other).map(|dist| (dist, idx))
+                })
+                .collect();
+            neighbours.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            for (col, value) in row.iter().enumerate() {
+
+                if !value.is_nan() {
+                    continue;
+                }
+
+                let donors: Vec<f64> = neighbours
+                    .iter()
+                    .map(|(_, idx)| self.reference[[*idx, col]])
+                    .filter(|x| !x.is_nan())
+                    .take(self.n_neighbors)
+                    .collect();
+
+                imputed[[row_idx, col]] = match donors.is_empty() {
+                    true => self.means[col],
+                    false => donors.iter().sum::<f64>() / donors.len() as f64
+                };
+            }
+        }
+
+        Ok(with_indicator(data, imputed, &self.indicator_columns))
+    }
+
+    /// Drop indicator columns, values marked missing by an indicator are restored to NaN
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.means.len() + self.indicator_columns.len())?;
+        Ok(restore_missing(data, self.means.len(), &self.indicator_columns))
+    }
+
+}
+
+
+#[cfg(test)]
+mod imputer_tests {
+
+    use ndarray::arr2;
+    use crate::preprocessing::imputer::*;
+
+
+    #[test]
+    fn test_simple_imputer() {
+
+        let nan = f64::NAN;
+        let x = arr2(&[
+            [1.0, 2.0, nan],
+            [nan, 2.0, 5.0],
+            [3.0, 7.0, 5.0],
+            [10.0, nan, 6.0]
+        ]);
+
+        let mut mean = SimpleImputer::new(ImputeStrategy::Mean, false);
+        assert!(mean.transform(&x.view()).is_err());
+        let imputed = mean.fit_transform(&x.view()).unwrap();
+        assert_eq!(mean.fill_values(), &vec![14.0 / 3.0, 11.0 / 3.0, 16.0 / 3.0]);
+        assert_eq!(imputed.row(1).to_vec(), vec![14.0 / 3.0, 2.0, 5.0]);
+
+        let mut median = SimpleImputer::new(ImputeStrategy::Median, false);
+        median.fit(&x.view()).unwrap();
+        assert_eq!(median.fill_values(), &vec![3.0, 2.0, 5.0]);
+
+        let mut frequent = SimpleImputer::new(ImputeStrategy::MostFrequent, false);
+        frequent.fit(&x.view()).unwrap();
+        assert_eq!(frequent.fill_values(), &vec![1.0, 2.0, 5.0]);
+
+        let mut constant = SimpleImputer::new(ImputeStrategy::Constant(-1.0), true);
+        let imputed = constant.fit_transform(&x.view()).unwrap();
+        assert_eq!(constant.indicator_columns(), &vec![0, 1, 2]);
+        assert_eq!(
+            imputed,
+            arr2(&[
+                [1.0, 2.0, -1.0, 0.0, 0.0, 1.0],
+                [-1.0, 2.0, 5.0, 1.0, 0.0, 0.0],
+                [3.0, 7.0, 5.0, 0.0, 0.0, 0.0],
+                [10.0, -1.0, 6.0, 0.0, 1.0, 0.0]
+            ])
+        );
+
+        let decoded = constant.inverse_transform(&imputed.view()).unwrap();
+        assert_eq!(decoded.dim(), x.dim());
+        assert!(decoded[[0, 2]].is_nan() && decoded[[1, 0]].is_nan() && decoded[[3, 1]].is_nan());
+        assert_eq!(decoded[[2, 1]], 7.0);
+
+        // fill values of the training data are used on new data
+        let test = arr2(&[[nan, nan, nan]]);
+        assert_eq!(median.transform(&test.view()).unwrap(), arr2(&[[3.0, 2.0, 5.0]]));
+
+        let empty = arr2(&[[nan], [nan]]);
+        assert!(SimpleImputer::new(ImputeStrategy::Mean, false).fit(&empty.view()).is_err());
+        assert!(SimpleImputer::new(ImputeStrategy::Constant(0.0), false).fit(&empty.view()).is_ok());
+    }
+
+
+    #[test]
+    fn test_knn_imputer() {
+
+        let nan = f64::NAN;
+        let x = arr2(&[
+            [1.0, 1.0, 10.0],
+            [1.2, 1.1, 12.0],
+            [5.0, 5.0, 50.0],
+            [5.1, 5.2, nan],
+            [1.1, nan, 11.0]
+        ]);
+
+        assert!(KNNImputer::new(0, false).is_err());
+
+        let mut knn = KNNImputer::new(2, true).unwrap();
+        let imputed = knn.fit_transform(&x.view()).unwrap();
+        assert_eq!(imputed.dim(), (5, 5));
+
+        // closest rows with the feature observed are averaged, distances to rows
+        // with missing features are scaled up by the fraction of observed features
+        assert!((imputed[[3, 2]] - 30.5).abs() < 1e-12);
+        assert!((imputed[[4, 1]] - 1.05).abs() < 1e-12);
+        assert_eq!(imputed.column(3).to_vec(), vec![0.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(imputed.column(4).to_vec(), vec![0.0, 0.0, 0.0, 1.0, 0.0]);
+
+        let decoded = knn.inverse_transform(&imputed.view()).unwrap();
+        assert!(decoded[[3, 2]].is_nan());
+        assert_eq!(decoded[[0, 0]], 1.0);
+
+        // rows without observed features fall back to the feature means
+        let test = arr2(&[[nan, nan, nan]]);
+        let imputed = knn.transform(&test.view()).unwrap();
+        assert!((imputed[[0, 0]] - 13.4 / 5.0).abs() < 1e-12);
+
+        // missing values of the reference rows survive serialization
+        knn.save("data/knn_imputer", "imputer").unwrap();
+        let loaded = KNNImputer::load("data/knn_imputer", "imputer").unwrap();
+        assert_eq!(loaded.transform(&x.view()).unwrap(), knn.transform(&x.view()).unwrap());
+        std::fs::remove_dir_all("data/knn_imputer").unwrap();
+    }
+
+}
@@ -9,6 +9,16 @@
 //! - **Standard Scalar**: Functions for normalizing input features to a standard scale.
 //! - **Min Max Scalar**: Utilities for scaling features to a specific range (e.g., [0, 1]).
 //! - **One Hot Encoding**: Strategies for converting categorical variables into a binary matrix.
+//! - **Robust, Max Abs & Power Scalers**: Median/IQR and max absolute scaling, row normalization & Yeo-Johnson/Box-Cox transforms.
+//! - **Categorical Encoders**: Ordinal, smoothed target & hashing encoders for `f64` codes or string categories.
+//! - **Feature Generation**: Polynomial & interaction terms, B-spline bases and binning (uniform, quantile or k-means bins).
+//! - **Imputation**: Filling missing values (NaN) with mean, median, most frequent, constant or nearest neighbour values.
+//! - **Pipeline**: Chaining encoders, and applying encoders to subsets of columns with a column transformer.
 //!
 pub mod processor;
+pub mod scaler;
+pub mod imputer;
+pub mod categorical;
+pub mod features;
+pub mod pipeline;
 pub mod prelude; 
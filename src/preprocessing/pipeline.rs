@@ -0,0 +1,434 @@
+//! Composition of feature encoders, chained or applied to column subsets
+
+use ndarray::{concatenate, s, Array2, ArrayView2, Axis};
+use serde::{Serialize, Deserialize};
+
+use crate::preprocessing::processor::*;
+
+
+/// Chain of feature encoders, each step is fit on & applied to the output of the previous step
+#[derive(Serialize, Deserialize)]
+pub struct Pipeline {
+
+    /// Encoders applied in order
+    steps: Vec<Box<dyn FeatureEncoder>>
+}
+
+
+impl Pipeline {
+
+    /// Create instance of pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - Encoders applied in order.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[
+    ///     [1.0, 2.0],
+    ///     [2.0, 4.0],
+    ///     [3.0, 6.0]
+    /// ]);
+    ///
+    /// let mut pipeline = Pipeline::new(vec![
+    ///     Box::new(StandardScalar::new()),
+    ///     Box::new(MinMax::new())
+    /// ]);
+    /// let encoded = pipeline.fit_transform(&x.view()).unwrap();
+    /// let decoded = pipeline.inverse_transform(&encoded.view()).unwrap();
+    /// println!("Encoded: {:?} Decoded: {:?}", encoded, decoded);
+    /// ```
+    pub fn new(steps: Vec<Box<dyn FeatureEncoder>>) -> Self {
+        Self { steps }
+    }
+
+    /// Append encoder to the end of the pipeline
+    pub fn add_step(&mut self, encoder: Box<dyn FeatureEncoder>) {
+        self.steps.push(encoder);
+    }
+
+    /// Retrieve encoders of the pipeline
+    pub fn steps(&self) -> &Vec<Box<dyn FeatureEncoder>> {
+        &self.steps
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for Pipeline {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+        self.fit_transform(data)?;
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        self.steps.iter().all(|step| step.is_fitted())
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        let mut encoded = data.to_owned();
+        for step in self.steps.iter() {
+            encoded = step.transform(&encoded.view())?;
+        }
+        Ok(encoded)
+    }
+
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        let mut decoded = data.to_owned();
+        for step in self.steps.iter().rev() {
+            decoded = step.inverse_transform(&decoded.view())?;
+        }
+        Ok(decoded)
+    }
+
+    /// Fit each step on the output of the previous step, returning the output of the last step.
+    /// Steps encoding their own training data differently (`TargetEncoder`) keep doing so.
+    fn fit_transform(&mut self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        let mut encoded = data.to_owned();
+        for step in self.steps.iter_mut() {
+            encoded = step.fit_transform(&encoded.view())?;
+        }
+        Ok(encoded)
+    }
+
+}
+
+
+/// Handling of columns not selected by any encoder of a column transformer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Remainder {
+
+    /// Leave unselected columns out of the output
+    Drop,
+
+    /// Append unselected columns to the output unchanged
+    Passthrough
+}
+
+
+/// Encoder applied to a subset of columns
+#[derive(Serialize, Deserialize)]
+struct ColumnEncoder {
+
+    /// Column indices of input selected for encoder
+    columns: Vec<usize>,
+
+    /// Encoder applied to the selected columns
+    encoder: Box<dyn FeatureEncoder>,
+
+    /// Number of output columns produced by encoder when fit
+    output_dim: usize
+}
+
+
+/// Applies encoders to column subsets of the input and concatenates their outputs
+/// (in the order encoders were added, followed by remainder columns)
+#[derive(Serialize, Deserialize)]
+pub struct ColumnTransformer {
+
+    /// Encoders with selected columns
+    transformers: Vec<ColumnEncoder>,
+
+    /// Handling of columns not selected by any encoder
+    remainder: Remainder,
+
+    /// Number of input columns the transformer was fit on
+    n_features: usize
+}
+
+
+impl ColumnTransformer {
+
+    /// Create instance of column transformer.
+    ///
+    /// # Arguments
+    ///
+    /// * `remainder` - Handling of columns not selected by any encoder.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// // numeric feature, class label, numeric feature
+    /// let x = arr2(&[
+    ///     [1.0, 0.0, 10.0],
+    ///     [2.0, 1.0, 20.0],
+    ///     [3.0, 2.0, 30.0]
+    /// ]);
+    ///
+    /// let mut transformer = ColumnTransformer::new(Remainder::Passthrough);
+    /// transformer.add_transformer(&[0], Box::new(StandardScalar::new()));
+    /// transformer.add_transformer(&[1], Box::new(OneHot::new()));
+    ///
+    /// let encoded = transformer.fit_transform(&x.view()).unwrap();
+    /// assert_eq!(encoded.dim(), (3, 5));
+    /// ```
+    pub fn new(remainder: Remainder) -> Self {
+        Self {
+            transformers: vec![],
+            remainder,
+            n_features: 0
+        }
+    }
+
+    /// Add encoder applied to selected columns of the input
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - Column indices of input passed to encoder.
+    /// * `encoder` - Encoder applied to the selected columns.
+    ///
+    pub fn add_transformer(&mut self, columns: &[usize], encoder: Box<dyn FeatureEncoder>) {
+        self.transformers.push(ColumnEncoder {
+            columns: columns.to_vec(),
+            encoder,
+            output_dim: 0
+        });
+    }
+
+    /// Retrieve handling of columns not selected by any encoder
+    pub fn remainder(&self) -> Remainder {
+        self.remainder
+    }
+
+    /// Column indices not selected by any encoder
+    pub fn remainder_columns(&self) -> Vec<usize> {
+        (0..self.n_features)
+            .filter(|col| !self.transformers.iter().any(|t| t.columns.contains(col)))
+            .collect()
+    }
+
+    /// Concatenate encoder outputs with the remainder columns of the input
+    fn combine(&self, data: &ArrayView2<f64>, mut outputs: Vec<Array2<f64>>) -> Result<Array2<f64>, String> {
+
+        if self.remainder == Remainder::Passthrough {
+            outputs.push(data.select(Axis(1), &self.remainder_columns()));
+        }
+
+        let views: Vec<_> = outputs.iter().map(|output| output.view()).collect();
+        match views.is_empty() {
+            true => Ok(Array2::zeros((data.nrows(), 0))),
+            false => concatenate(Axis(1), &views).map_err(|err| err.to_string())
+        }
+    }
+
+    /// Number of output columns of the fitted transformer
+    pub fn output_dim(&self) -> usize {
+        let encoded: usize = self.transformers.iter().map(|t| t.output_dim).sum();
+        match self.remainder {
+            Remainder::Drop => encoded,
+            Remainder::Passthrough => encoded + self.remainder_columns().len()
+        }
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for ColumnTransformer {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+        self.fit_transform(data)?;
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        self.n_features > 0 && self.transformers.iter().all(|t| t.encoder.is_fitted())
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.n_features)?;
+
+        let mut outputs: Vec<Array2<f64>> = vec![];
+        for transformer in self.transformers.iter() {
+            let selected = data.select(Axis(1), &transformer.columns);
+            outputs.push(transformer.encoder.transform(&selected.view())?);
+        }
+
+        self.combine(data, outputs)
+    }
+
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.output_dim())?;
+
+        let remainder = self.remainder_columns();
+        if self.remainder == Remainder::Drop && !remainder.is_empty() {
+            return Err("Dropped remainder columns cannot be decoded".to_string());
+        }
+
+        let mut decoded: Array2<f64> = Array2::zeros((data.nrows(), self.n_features));
+        let mut offset = 0;
+        for transformer in self.transformers.iter() {
+            let encoded = data.slice(s![.., offset..offset + transformer.output_dim]);
+            let features = transformer.encoder.inverse_transform(&encoded)?;
+            for (idx, col) in transformer.columns.iter().enumerate() {
+                decoded.column_mut(*col).assign(&features.column(idx));
+            }
+            offset += transformer.output_dim;
+        }
+
+        for (idx, col) in remainder.iter().enumerate() {
+            decoded.column_mut(*col).assign(&data.column(offset + idx));
+        }
+
+        Ok(decoded)
+    }
+
+    /// Fit each encoder on its selected columns and concatenate the outputs of fitting,
+    /// so encoders encoding their own training data differently (`TargetEncoder`) keep doing so.
+    fn fit_transform(&mut self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        for transformer in self.transformers.iter() {
+            if let Some(col) = transformer.columns.iter().find(|col| **col >= data.ncols()) {
+                return Err(format!(
+                    "Column {} out of bounds for data with {} columns", col, data.ncols()
+                ));
+            }
+        }
+
+        let mut outputs: Vec<Array2<f64>> = vec![];
+        for transformer in self.transformers.iter_mut() {
+            let selected = data.select(Axis(1), &transformer.columns);
+            let encoded = transformer.encoder.fit_transform(&selected.view())?;
+            transformer.output_dim = encoded.ncols();
+            outputs.push(encoded);
+        }
+
+        self.n_features = data.ncols();
+        self.combine(data, outputs)
+    }
+
+}
+
+
+#[cfg(test)]
+mod pipeline_tests {
+
+    use std::fs;
+    use ndarray::arr2;
+    use crate::preprocessing::processor::*;
+    use crate::preprocessing::categorical::*;
+    use crate::preprocessing::pipeline::*;
+
+
+    #[test]
+    fn test_pipeline() {
+
+        let x = arr2(&[
+            [1.0, 2.0],
+            [2.0, 4.0],
+            [3.0, 6.0],
+            [4.0, 8.0],
+            [5.0, 10.0],
+        ]);
+
+        let mut pipeline = Pipeline::new(vec![Box::new(StandardScalar::new())]);
+        pipeline.add_step(Box::new(MinMax::new()));
+        assert_eq!(pipeline.steps().len(), 2);
+        assert!(!pipeline.is_fitted());
+        assert!(pipeline.transform(&x.view()).is_err());
+
+        let encoded = pipeline.fit_transform(&x.view()).unwrap();
+        assert!(pipeline.is_fitted());
+        assert_eq!(
+            encoded.mapv(|v| (v * 10000.0).round() / 10000.0),
+            arr2(&[
+                [0.0, 0.0],
+                [0.25, 0.25],
+                [0.5, 0.5],
+                [0.75, 0.75],
+                [1.0, 1.0]
+            ])
+        );
+
+        let decoded = pipeline.inverse_transform(&encoded.view()).unwrap();
+        assert!((decoded - &x).mapv(f64::abs).sum() < 1e-9);
+    }
+
+
+    #[test]
+    fn test_column_transformer() -> std::io::Result<()> {
+
+        let x = arr2(&[
+            [1.0, 0.0, 10.0, 7.0],
+            [2.0, 1.0, 20.0, 8.0],
+            [3.0, 2.0, 30.0, 9.0],
+            [4.0, 1.0, 40.0, 6.0],
+            [5.0, 0.0, 50.0, 5.0]
+        ]);
+
+        let mut transformer = ColumnTransformer::new(Remainder::Passthrough);
+        transformer.add_transformer(&[0, 2], Box::new(MinMax::new()));
+        transformer.add_transformer(&[1], Box::new(OneHot::new()));
+        assert!(transformer.fit_transform(&arr2(&[[1.0]]).view()).is_err());
+
+        let encoded = transformer.fit_transform(&x.view()).unwrap();
+        assert_eq!(transformer.remainder_columns(), vec![3]);
+        assert_eq!(transformer.output_dim(), 6);
+        assert_eq!(encoded.row(0).to_vec(), vec![0.0, 0.0, 1.0, 0.0, 0.0, 7.0]);
+        assert_eq!(encoded.row(2).to_vec(), vec![0.5, 0.5, 0.0, 0.0, 1.0, 9.0]);
+
+        let decoded = transformer.inverse_transform(&encoded.view()).unwrap();
+        assert_eq!(decoded, x);
+
+        // fitted statistics are reused on new data
+        let test = arr2(&[[3.0, 1.0, 60.0, 1.0]]);
+        let encoded_test = transformer.transform(&test.view()).unwrap();
+        assert_eq!(encoded_test.row(0).to_vec(), vec![0.5, 1.25, 0.0, 1.0, 0.0, 1.0]);
+        assert!(transformer.transform(&x.slice(ndarray::s![.., 0..3]).view()).is_err());
+
+        // composed encoders serialize with their fitted statistics
+        transformer.save("data/column_transformer", "encoder")?;
+        let loaded = ColumnTransformer::load("data/column_transformer", "encoder").unwrap();
+        assert_eq!(loaded.transform(&test.view()).unwrap(), encoded_test);
+        fs::remove_dir_all("data/column_transformer")?;
+
+        // dropped columns cannot be decoded
+        let mut dropped = ColumnTransformer::new(Remainder::Drop);
+        dropped.add_transformer(&[1], Box::new(OneHot::new()));
+        let encoded = dropped.fit_transform(&x.view()).unwrap();
+        assert_eq!(encoded.dim(), (5, 3));
+        assert!(dropped.inverse_transform(&encoded.view()).is_err());
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_out_of_fold_encoding() {
+
+        let codes = arr2(&[[0.0, 5.0], [0.0, 4.0], [1.0, 3.0], [1.0, 2.0], [0.0, 1.0], [1.0, 0.0]]);
+        let y = arr2(&[[1.0], [3.0], [10.0], [12.0], [2.0], [14.0]]);
+        let out_of_fold = arr2(&[[3.0], [1.5], [13.0], [10.0], [3.0], [10.0]]);
+        let codes_view = codes.slice(ndarray::s![.., 0..1]);
+
+        // later steps are fit on & return the out of fold encodings
+        let mut pipeline = Pipeline::new(vec![
+            Box::new(TargetEncoder::new(&y, 0.0, 2).unwrap()),
+            Box::new(MinMax::new())
+        ]);
+        let encoded = pipeline.fit_transform(&codes_view).unwrap();
+        let mut scaler = MinMax::new();
+        assert_eq!(encoded, scaler.fit_transform(&out_of_fold.view()).unwrap());
+        assert_ne!(encoded, pipeline.transform(&codes_view).unwrap());
+
+        let mut transformer = ColumnTransformer::new(Remainder::Passthrough);
+        transformer.add_transformer(&[0], Box::new(TargetEncoder::new(&y, 0.0, 2).unwrap()));
+        let encoded = transformer.fit_transform(&codes.view()).unwrap();
+        assert_eq!(encoded.column(0), out_of_fold.column(0));
+        assert_eq!(encoded.column(1), codes.column(1));
+        assert_eq!(
+            transformer.transform(&codes.view()).unwrap().column(0).to_vec(),
+            vec![2.0, 2.0, 12.0, 12.0, 2.0, 12.0]
+        );
+    }
+
+}
@@ -1 +1,6 @@
 pub use super::processor::*;
+pub use super::scaler::*;
+pub use super::imputer::*;
+pub use super::categorical::*;
+pub use super::features::*;
+pub use super::pipeline::*;
@@ -1,29 +1,181 @@
-use std::collections::HashSet; 
-use ndarray::{Array, Array2, ArrayView2, Axis};
-use ndarray_stats::QuantileExt;
+use std::fs;
+use std::fs::File;
+use std::io::{Write, BufWriter, BufReader};
 
+use ndarray::{s, Array, Array2, ArrayView2, Axis};
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
 
-/// Trait for housing shared behavior of feature encoders
+
+/// Trait for housing shared behavior of feature encoders.
+/// Statistics are learned with `fit` (on training data) and applied unchanged
+/// by `transform`, so validation & inference batches are encoded the same way.
+#[typetag::serde(tag = "type")]
 pub trait FeatureEncoder {
- 
-    /// Transform data for specific feature encoder
-    fn transform(&mut self, data: &ArrayView2<f64>) -> Array2<f64>; 
+
+    /// Learn statistics of the encoder from data, replacing previous fits
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String>;
+
+    /// Flag for whether the encoder has been fit
+    fn is_fitted(&self) -> bool;
+
+    /// Transform data with the fitted statistics of the encoder
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String>;
 
     /// Decode transformed data from feature encoder
-    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Array2<f64>; 
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String>;
+
+    /// Fit encoder to data and transform the same data
+    fn fit_transform(&mut self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        self.fit(data)?;
+        self.transform(data)
+    }
+
+}
+
+
+/// Encoder serialization trait for saving and loading fitted encoders
+/// next to the parameters of a model (`{filepath}/{name}.json`)
+pub trait EncoderSerialize {
+
+    /// Save fitted encoder to `{filepath}/{name}.json`
+    fn save(&self, filepath: &str, name: &str) -> std::io::Result<()>;
+
+    /// Load fitted encoder from `{filepath}/{name}.json`
+    fn load(filepath: &str, name: &str) -> Result<Self, Box<dyn std::error::Error>> where Self: Sized;
+
+}
+
+
+impl<T: FeatureEncoder + Serialize + DeserializeOwned> EncoderSerialize for T {
+
+    fn save(&self, filepath: &str, name: &str) -> std::io::Result<()> {
+        save_encoder(self, filepath, name)
+    }
+
+    fn load(filepath: &str, name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        load_encoder(filepath, name)
+    }
+
+}
+
+
+/// Boxed encoders are saved with their type tag, so they load back as the same encoder
+impl EncoderSerialize for Box<dyn FeatureEncoder> {
+
+    fn save(&self, filepath: &str, name: &str) -> std::io::Result<()> {
+        save_encoder(self, filepath, name)
+    }
+
+    fn load(filepath: &str, name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        load_encoder(filepath, name)
+    }
+
+}
+
+
+/// Write encoder as json to `{filepath}/{name}.json`
+fn save_encoder<T: Serialize + ?Sized>(encoder: &T, filepath: &str, name: &str) -> std::io::Result<()> {
+
+    fs::create_dir_all(filepath)?;
+    let file_path = format!("{filepath}/{name}.json");
+
+    let file = File::create(&file_path)?;
+    let mut writer = BufWriter::new(file);
+    let json_string = serde_json::to_string_pretty(encoder)?;
+    writer.write_all(json_string.as_bytes())?;
+    Ok(())
+}
+
+
+/// Read encoder from json at `{filepath}/{name}.json`
+fn load_encoder<T: DeserializeOwned>(filepath: &str, name: &str) -> Result<T, Box<dyn std::error::Error>> {
+
+    let file_path = format!("{filepath}/{name}.json");
+    let file = File::open(&file_path)?;
+    let reader = BufReader::new(file);
+    Ok(serde_json::from_reader(reader)?)
+}
+
+
+/// Error for encoders used before being fit
+pub(crate) fn check_fitted(encoder: &dyn FeatureEncoder) -> Result<(), String> {
+    if !encoder.is_fitted() {
+        return Err("Encoder must be fit before transforming data".to_string());
+    }
+    Ok(())
+}
+
+
+/// Error for data with a different number of columns than the fitted data
+pub(crate) fn check_columns<T>(data: &ArrayView2<T>, expected: usize) -> Result<(), String> {
+    if data.ncols() != expected {
+        return Err(format!(
+            "Expected {} columns, got {}", expected, data.ncols()
+        ));
+    }
+    Ok(())
+}
+
+
+/// Scale of zero (constant feature) replaced with 1 to leave the feature unscaled
+pub(crate) fn non_zero(scale: f64) -> f64 {
+    if scale == 0.0 { 1.0 } else { scale }
+}
+
+
+/// Handling of categories not seen when a one hot encoder was fit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum UnknownCategory {
+
+    /// Return an error when transforming unknown categories
+    Error,
 
+    /// Encode unknown categories as a row of zeros (cannot be combined with `drop_first`,
+    /// where a row of zeros is the dropped category)
+    Ignore,
+
+    /// Encode unknown categories in an extra "other" column of each feature
+    Other
 }
 
 
-/// One hot encoder
-#[derive(Debug, Clone)]
+/// Options for one hot encoding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OneHotOptions {
+
+    /// Handling of categories not seen at fit time
+    pub handle_unknown: UnknownCategory,
+
+    /// Leave out the column of the first (smallest) category of each feature
+    pub drop_first: bool
+}
+
+
+impl Default for OneHotOptions {
+
+    fn default() -> Self {
+        OneHotOptions {
+            handle_unknown: UnknownCategory::Error,
+            drop_first: false
+        }
+    }
+}
+
+
+/// One hot encoder, every column of the input is a categorical feature encoded
+/// with a column per category, outputs of features are concatenated in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OneHot {
 
-    /// Num classes detected
-    num_classes: usize, 
+    /// Sorted categories of each feature learned at fit time
+    categories: Vec<Vec<f64>>,
 
-    /// Number of samples that were encoded
-    num_samples: usize
+    /// Number of samples the encoder was fit on
+    num_samples: usize,
+
+    /// Handling of unknown categories & dropped columns
+    options: OneHotOptions
 
 }
 
@@ -44,71 +196,193 @@ impl OneHot {
     ///     [0.0], [0.0], [0.0], [1.0], [1.0], [1.0], [2.0], [2.0], [2.0]
     /// ]);
     /// let mut one_hot = OneHot::new();
-    /// let encoded  = one_hot.transform(&data.view());
+    /// let encoded  = one_hot.fit_transform(&data.view()).unwrap();
     /// println!("Encoded: {:?}", encoded);
     /// ```
     pub fn new() -> Self {
+        OneHot::with_options(OneHotOptions::default())
+    }
+
+    /// Create instance of OneHot Encoder with unknown category & drop first options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Handling of unknown categories & dropped columns.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::processor::*;
+    ///
+    /// let data = arr2(&[[1.0, -2.0], [5.0, 0.5], [9.0, -2.0]]);
+    /// let mut one_hot = OneHot::with_options(OneHotOptions {
+    ///     handle_unknown: UnknownCategory::Other,
+    ///     drop_first: false
+    /// });
+    ///
+    /// one_hot.fit(&data.view()).unwrap();
+    /// let encoded = one_hot.transform(&arr2(&[[5.0, 7.0]]).view()).unwrap();
+    /// assert_eq!(encoded, arr2(&[[0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]]));
+    /// ```
+    pub fn with_options(options: OneHotOptions) -> Self {
         Self {
-            num_classes: 0,
-            num_samples: 0
+            categories: vec![],
+            num_samples: 0,
+            options
         }
-
     }
 
-    /// Retrieve number of classes associated with encoded data
+    /// Retrieve number of categories across all encoded features
     pub fn num_classes(&self) -> usize {
-        self.num_classes
+        self.categories.iter().map(|c| c.len()).sum()
     }
 
-    /// Retrieve number of samples associated with encoded data
+    /// Retrieve number of samples the encoder was fit on
     pub fn num_samples(&self) -> usize {
         self.num_samples
     }
 
+    /// Retrieve sorted categories of each feature
+    pub fn categories(&self) -> &Vec<Vec<f64>> {
+        &self.categories
+    }
+
+    /// Retrieve options of encoder
+    pub fn options(&self) -> &OneHotOptions {
+        &self.options
+    }
+
+    /// Number of output columns of each feature
+    fn feature_dims(&self) -> Vec<usize> {
+        let dropped = self.options.drop_first as usize;
+        let other = (self.options.handle_unknown == UnknownCategory::Other) as usize;
+        self.categories
+            .iter()
+            .map(|c| c.len() - dropped + other)
+            .collect()
+    }
+
 }
 
 
+impl Default for OneHot {
+
+    fn default() -> Self {
+        OneHot::new()
+    }
+}
+
+
+#[typetag::serde]
 impl FeatureEncoder for OneHot {
 
-    fn transform(&mut self, data: &ArrayView2<f64>) -> Array2<f64> {
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
 
-        if data.dim().1 != 1 {
-            let msg = "Input must be of size (N, 1)";
-            panic!("{}", msg.to_string());
+        if data.nrows() == 0 || data.ncols() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
         }
- 
-        let mut vals = HashSet::new();
-        data.mapv(|x| vals.insert(x as usize)); 
-        let num_classes = vals.len();
-        self.num_classes = num_classes;
-
-        let encoded_shape = (data.nrows(), num_classes);
-        let mut encoded: Array2<f64> = Array2::zeros(encoded_shape);
-        for (idx, row) in data.iter().enumerate() {
-            encoded[[idx, *row as usize]] = 1.0;
-            self.num_samples += 1; 
+
+        if data.iter().any(|x| x.is_nan()) {
+            return Err("Categories cannot be NaN".to_string());
         }
 
-        encoded
+        if self.options.drop_first && self.options.handle_unknown == UnknownCategory::Ignore {
+            return Err(
+                "Ignored unknown categories cannot be told apart from the dropped first category".to_string()
+            );
+        }
+
+        self.categories = data
+            .axis_iter(Axis(1))
+            .map(|col| {
+                let mut vals = col.to_vec();
+                vals.sort_by(|a, b| a.total_cmp(b));
+                vals.dedup();
+                vals
+            })
+            .collect();
+
+        self.num_samples = data.nrows();
+        Ok(())
     }
 
+    fn is_fitted(&self) -> bool {
+        !self.categories.is_empty()
+    }
 
-    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Array2<f64> {
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.categories.len())?;
+
+        let dims = self.feature_dims();
+        let dropped = self.options.drop_first as usize;
+        let mut encoded: Array2<f64> = Array2::zeros((data.nrows(), dims.iter().sum()));
+
+        let mut offset = 0;
+        for (feature, col) in data.axis_iter(Axis(1)).enumerate() {
+            let categories = &self.categories[feature];
+            for (idx, val) in col.iter().enumerate() {
+                let position = categories.binary_search_by(|c| c.total_cmp(val));
+                match (position, self.options.handle_unknown) {
+                    (Ok(category), _) => {
+                        if category >= dropped {
+                            encoded[[idx, offset + category - dropped]] = 1.0;
+                        }
+                    },
+                    (Err(_), UnknownCategory::Error) => {
+                        return Err(format!(
+                            "Unknown category {} of feature {} at row {}", val, feature, idx
+                        ));
+                    },
+                    (Err(_), UnknownCategory::Ignore) => {},
+                    (Err(_), UnknownCategory::Other) => {
+                        encoded[[idx, offset + dims[feature] - 1]] = 1.0;
+                    }
+                }
+            }
+            offset += dims[feature];
+        }
 
-        let mut decoded: Array2<f64> = Array2::zeros((data.nrows(), 1));
-        for (idx, row) in data.axis_iter(Axis(0)).enumerate() {
-            if let Some(col) = row.iter().position(|&x| x == 1.0) {
-                decoded[[idx, 0]] = col as f64;
+        Ok(encoded)
+    }
+
+    /// Decode one hot columns to categories, rows without a category
+    /// (ignored or "other" unknowns) decode to NaN. With `drop_first`
+    /// rows of zeros decode to the first category.
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+
+        let dims = self.feature_dims();
+        check_columns(data, dims.iter().sum())?;
+
+        let dropped = self.options.drop_first as usize;
+        let has_other = self.options.handle_unknown == UnknownCategory::Other;
+        let mut decoded: Array2<f64> = Array2::zeros((data.nrows(), self.categories.len()));
+
+        let mut offset = 0;
+        for (feature, categories) in self.categories.iter().enumerate() {
+            let known = categories.len() - dropped;
+            let block = data.slice(s![.., offset..offset + known]);
+            for (idx, row) in block.axis_iter(Axis(0)).enumerate() {
+                let other = has_other && data[[idx, offset + known]] == 1.0;
+                decoded[[idx, feature]] = match row.iter().position(|&x| x == 1.0) {
+                    Some(col) => categories[col + dropped],
+                    None if dropped == 1 && !other => categories[0],
+                    None => f64::NAN
+                };
             }
+            offset += dims[feature];
         }
 
-        decoded
+        Ok(decoded)
     }
 
 }
 
 
-/// One hot encoder 
+/// Min max scalar for scaling feature columns between 0 and 1
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinMax {
 
     /// Vector of minimum values associated with each feature
@@ -139,7 +413,7 @@ impl MinMax {
     ///     [5.0, 10.0],
     /// ]);
     /// let mut scalar = MinMax::new();
-    /// let encoded = scalar.transform(&x.view()); 
+    /// let encoded = scalar.fit_transform(&x.view()).unwrap();
     /// println!("Encoded data: {:?}", encoded);
     /// ```
     pub fn new() -> Self {
@@ -163,41 +437,62 @@ impl MinMax {
 }
 
 
+#[typetag::serde]
 impl FeatureEncoder for MinMax {
 
-    fn transform(&mut self, data: &ArrayView2<f64>) -> Array2<f64> {
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        if data.iter().any(|x| x.is_nan()) {
+            return Err("Data contains missing values (NaN), impute them first".to_string());
+        }
+
+        self.min_range = data
+            .axis_iter(Axis(1))
+            .map(|col| col.fold(f64::INFINITY, |min, x| min.min(*x)))
+            .collect();
+
+        self.max_range = data
+            .axis_iter(Axis(1))
+            .map(|col| col.fold(f64::NEG_INFINITY, |max, x| max.max(*x)))
+            .collect();
+
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.min_range.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.min_range.len())?;
 
         let mut encoded: Array2<f64> = Array2::zeros(data.dim());
         for (idx, col) in data.axis_iter(Axis(1)).enumerate() {
-            
-            let owned_col = col.to_owned();
-            
-            let min = owned_col.min().unwrap();
-            let max = owned_col.max().unwrap();
-
-            self.min_range.push(*min);
-            self.max_range.push(*max);
 
-            let min_vec = Array::from_elem(col.len(), *min);
-            let max_vec = Array::from_elem(col.len(), *max);
+            let min_vec = Array::from_elem(col.len(), self.min_range[idx]);
+            let max_vec = Array::from_elem(col.len(), self.max_range[idx]);
 
-            let subtract_min = owned_col - min_vec.clone();
-            let min_max = max_vec - min_vec;
+            let subtract_min = col.to_owned() - min_vec.clone();
+            let min_max = (max_vec - min_vec).mapv(non_zero);
             let div = subtract_min / min_max;
 
             encoded.index_axis_mut(Axis(1), idx).assign(&div);
         }
 
-        encoded
+        Ok(encoded)
     }
 
 
-    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Array2<f64> {
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
 
-        
-        if data.dim() != data.dim() {
-            panic!("Encoded data does not much decoded data dimensions"); 
-        }
+        check_fitted(self)?;
+        check_columns(data, self.min_range.len())?;
 
         let mut decoded: Array2<f64> = Array2::zeros(data.dim());
         for (idx, col) in data.axis_iter(Axis(1)).enumerate() {
@@ -212,19 +507,19 @@ impl FeatureEncoder for MinMax {
                 self.max_range[idx]
             );
 
-            let min_max = max_vec - min_vec.clone();
+            let min_max = (max_vec - min_vec.clone()).mapv(non_zero);
             let feature = min_max * col + min_vec;
             decoded.index_axis_mut(Axis(1), idx).assign(&feature);              
         }
 
-        decoded
+        Ok(decoded)
     }
 
 }
 
 
 /// Standard scalar for standarization of feature columns
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StandardScalar {
     
     /// Max value associated with encoder
@@ -256,7 +551,7 @@ impl StandardScalar {
     ///     [5.0, 10.0],
     /// ]);
     /// let mut scalar = StandardScalar::new();
-    /// let encoded = scalar.transform(&x.view()); 
+    /// let encoded = scalar.fit_transform(&x.view()).unwrap();
     /// println!("Encoded data: {:?}", encoded);
     /// ```
     pub fn new() -> Self {
@@ -281,43 +576,51 @@ impl StandardScalar {
 }
 
 
+#[typetag::serde]
 impl FeatureEncoder for StandardScalar {
 
-    /// Encode function for One Hot Encoding
-    fn transform(&mut self, data: &ArrayView2<f64>) -> Array2<f64> {
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
 
-        let mut encoded: Array2<f64> = Array2::zeros(data.dim());
-     
-        for (idx, col) in data.axis_iter(Axis(1)).enumerate() {
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
 
-            self.mean.push(col.mean().unwrap());
-            let mean_vec = Array::from_elem(
-                data.nrows(), 
-                col.mean().unwrap()
-            );
+        self.mean = vec![];
+        self.standard_deviation = vec![];
+        for col in data.axis_iter(Axis(1)) {
+            let mean = col.mean().unwrap();
+            let variance = col.mapv(|x| (x - mean) * (x - mean)).sum() / data.nrows() as f64;
+            self.mean.push(mean);
+            self.standard_deviation.push(variance.sqrt());
+        }
 
-            let mut diffs = col.to_owned() - mean_vec.clone();
-            diffs.mapv_inplace(|x| x * x); 
+        Ok(())
+    }
 
-            let variance = diffs.sum() / data.nrows() as f64;
-            self.standard_deviation.push(variance.sqrt());
-            let std_dev_vec = Array::from_elem(
-                data.nrows(), 
-                variance.sqrt()
-            );
+    fn is_fitted(&self) -> bool {
+        !self.mean.is_empty()
+    }
 
-            let feature_col = (col.to_owned() - mean_vec.clone()) / std_dev_vec.clone();
-            encoded.index_axis_mut(Axis(1), idx).assign(&feature_col); 
-        }
+    /// Standardize columns with the fitted mean & standard deviation
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.mean.len())?;
 
-        encoded
+        let mean = Array::from_vec(self.mean());
+        let std_dev = Array::from_vec(self.stdev()).mapv(non_zero);
+        Ok((data - &mean) / &std_dev)
     }
     
-    /// Decode function for decoding One Hot Encoded Values
-    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Array2<f64> {
+    /// Undo standardization of columns
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.mean.len())?;
+
         let mean = Array::from_vec(self.mean());
-        let std_dev = Array::from_vec(self.stdev());
-        data * std_dev + mean
+        let std_dev = Array::from_vec(self.stdev()).mapv(non_zero);
+        Ok(data * &std_dev + &mean)
     }
 
 }
@@ -326,6 +629,7 @@ impl FeatureEncoder for StandardScalar {
 #[cfg(test)]
 mod preprocessing_tests {
 
+    use std::fs;
     use ndarray::{s, arr2}; 
     use crate::preprocessing::processor::*;
 
@@ -350,7 +654,7 @@ mod preprocessing_tests {
         ]);
 
         let mut one_hot = OneHot::new();
-        let transformed = one_hot.transform(&x.view());
+        let transformed = one_hot.fit_transform(&x.view()).unwrap();
 
         assert_eq!(one_hot.num_classes(), 3); 
         assert_eq!(one_hot.num_samples(), x.nrows());
@@ -358,7 +662,7 @@ mod preprocessing_tests {
         assert_eq!(transformed.dim(), encoded.dim());
         assert_eq!(transformed, encoded);
 
-        let decoded = one_hot.inverse_transform(&transformed.view()); 
+        let decoded = one_hot.inverse_transform(&transformed.view()).unwrap();
         
         assert_eq!(decoded.dim(), x.dim());
         assert_eq!(decoded, x);
@@ -373,7 +677,7 @@ mod preprocessing_tests {
             [0.0,0.0,1.0]
         ]);
 
-        let x1_decoded = one_hot.inverse_transform(&x1.view());
+        let x1_decoded = one_hot.inverse_transform(&x1.view()).unwrap();
 
         assert_eq!(
             x1_decoded, 
@@ -399,7 +703,7 @@ mod preprocessing_tests {
 
         let mut scalar = StandardScalar::new();
 
-        let encoded = scalar.transform(&x.view());
+        let encoded = scalar.fit_transform(&x.view()).unwrap();
 
         assert_eq!(scalar.mean().len(), 2); 
         assert_eq!(scalar.stdev().len(), 2);
@@ -415,12 +719,18 @@ mod preprocessing_tests {
             ])
         );
 
-        assert_eq!(scalar.inverse_transform(&encoded.view()), x);
+        assert_eq!(scalar.inverse_transform(&encoded.view()).unwrap(), x);
 
         let uneven = encoded.slice(s![0..3, ..]);
-        let uneven_encoded = scalar.inverse_transform(&uneven);
+        let uneven_encoded = scalar.inverse_transform(&uneven).unwrap();
 
         assert_eq!(uneven_encoded, x.slice(s![0..3, ..])); 
+
+        // constant features are centered instead of divided by zero
+        let constant = arr2(&[[3.0, 1.0], [3.0, 2.0], [3.0, 3.0]]);
+        let encoded = scalar.fit_transform(&constant.view()).unwrap();
+        assert_eq!(encoded.column(0).to_vec(), vec![0.0, 0.0, 0.0]);
+        assert_eq!(scalar.inverse_transform(&encoded.view()).unwrap(), constant);
     }
 
 
@@ -444,7 +754,7 @@ mod preprocessing_tests {
         ]); 
 
         let mut min_max = MinMax::new();
-        let encoded = min_max.transform(&x.view());
+        let encoded = min_max.fit_transform(&x.view()).unwrap();
 
         assert_eq!(min_max.min_range().len(), x.ncols());
         assert_eq!(min_max.max_range().len(), x.ncols());
@@ -454,9 +764,198 @@ mod preprocessing_tests {
 
         assert_eq!(&encoded, expected);
 
-        let decoded = min_max.inverse_transform(&encoded.view());
+        let decoded = min_max.inverse_transform(&encoded.view()).unwrap();
         assert_eq!(decoded, x);
 
+        // constant features map to 0 instead of 0 / 0
+        let constant = arr2(&[[3.0, 1.0], [3.0, 2.0], [3.0, 3.0]]);
+        let encoded = min_max.fit_transform(&constant.view()).unwrap();
+        assert_eq!(encoded.column(0).to_vec(), vec![0.0, 0.0, 0.0]);
+        assert_eq!(min_max.inverse_transform(&encoded.view()).unwrap(), constant);
+
+    }
+
+
+    #[test]
+    fn test_fit_then_transform() {
+
+        let train = arr2(&[
+            [1.0, 2.0],
+            [2.0, 4.0],
+            [3.0, 6.0],
+            [4.0, 8.0],
+            [5.0, 10.0],
+        ]);
+
+        let test = arr2(&[
+            [3.0, 6.0],
+            [7.0, 2.0]
+        ]);
+
+        // transforming before fitting is an error
+        assert!(MinMax::new().transform(&test.view()).is_err());
+        assert!(StandardScalar::new().transform(&test.view()).is_err());
+        assert!(OneHot::new().transform(&arr2(&[[0.0]]).view()).is_err());
+        assert!(MinMax::new().inverse_transform(&test.view()).is_err());
+        assert!(MinMax::new().fit(&arr2(&[[1.0], [f64::NAN]]).view()).is_err());
+
+        let mut min_max = MinMax::new();
+        min_max.fit(&train.view()).unwrap();
+        assert!(min_max.is_fitted());
+
+        // statistics of the training data are applied to the test split
+        let encoded = min_max.transform(&test.view()).unwrap();
+        assert_eq!(encoded, arr2(&[[0.5, 0.5], [1.5, 0.0]]));
+        assert_eq!(min_max.min_range(), &vec![1.0, 2.0]);
+        assert_eq!(min_max.max_range(), &vec![5.0, 10.0]);
+        assert_eq!(min_max.transform(&test.view()).unwrap(), encoded);
+
+        let mut scalar = StandardScalar::new();
+        scalar.fit(&train.view()).unwrap();
+        let encoded = scalar.transform(&test.view()).unwrap();
+        assert_eq!(encoded.row(0).to_vec(), vec![0.0, 0.0]);
+        assert_eq!(scalar.mean(), vec![3.0, 6.0]);
+        assert_eq!(scalar.inverse_transform(&encoded.view()).unwrap(), test);
+
+        // refitting replaces statistics instead of appending them
+        scalar.fit(&test.view()).unwrap();
+        assert_eq!(scalar.mean(), vec![5.0, 4.0]);
+
+        // column count must match the fitted data
+        assert!(scalar.transform(&arr2(&[[1.0]]).view()).is_err());
+
+        let mut one_hot = OneHot::new();
+        one_hot.fit(&arr2(&[[0.0], [1.0], [2.0], [1.0]]).view()).unwrap();
+        assert_eq!(one_hot.num_samples(), 4);
+
+        let encoded = one_hot.transform(&arr2(&[[2.0], [0.0]]).view()).unwrap();
+        assert_eq!(encoded, arr2(&[[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]]));
+        assert_eq!(one_hot.num_classes(), 3);
+        assert!(one_hot.transform(&arr2(&[[3.0]]).view()).is_err());
+    }
+
+
+    #[test]
+    fn test_encoder_serialization() -> std::io::Result<()> {
+
+        let train = arr2(&[
+            [1.0, 2.3],
+            [2.7, 4.1],
+            [3.1, 6.9],
+            [4.4, 8.0],
+            [5.0, 10.7],
+        ]);
+
+        let test = arr2(&[[0.3, 7.7], [9.1, 2.2]]);
+        let labels = arr2(&[[0.0], [2.0], [1.0], [2.0]]);
+
+        let mut scalar = StandardScalar::new();
+        let mut min_max = MinMax::new();
+        let mut one_hot = OneHot::new();
+        scalar.fit(&train.view()).unwrap();
+        min_max.fit(&train.view()).unwrap();
+        one_hot.fit(&labels.view()).unwrap();
+
+        scalar.save("data/encoders", "standard_scalar")?;
+        min_max.save("data/encoders", "min_max")?;
+        one_hot.save("data/encoders", "one_hot")?;
+
+        let loaded_scalar = StandardScalar::load("data/encoders", "standard_scalar").unwrap();
+        let loaded_min_max = MinMax::load("data/encoders", "min_max").unwrap();
+        let loaded_one_hot = OneHot::load("data/encoders", "one_hot").unwrap();
+
+        assert_eq!(
+            loaded_scalar.transform(&test.view()).unwrap(),
+            scalar.transform(&test.view()).unwrap()
+        );
+        assert_eq!(
+            loaded_min_max.transform(&test.view()).unwrap(),
+            min_max.transform(&test.view()).unwrap()
+        );
+        assert_eq!(
+            loaded_one_hot.transform(&labels.view()).unwrap(),
+            one_hot.transform(&labels.view()).unwrap()
+        );
+
+        // encoders behind trait objects keep their type
+        let boxed: Box<dyn FeatureEncoder> = Box::new(scalar.clone());
+        boxed.save("data/encoders", "boxed")?;
+        let loaded_boxed = <Box<dyn FeatureEncoder>>::load("data/encoders", "boxed").unwrap();
+        assert_eq!(
+            loaded_boxed.transform(&test.view()).unwrap(),
+            scalar.transform(&test.view()).unwrap()
+        );
+
+        assert!(MinMax::load("data/encoders", "missing").is_err());
+        fs::remove_dir_all("data/encoders")?;
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_one_hot_categories() {
+
+        // non contiguous, negative & fractional categories
+        let x = arr2(&[[9.0, -1.5], [1.0, 0.25], [5.0, -1.5], [1.0, 0.25]]);
+
+        let mut one_hot = OneHot::new();
+        let encoded = one_hot.fit_transform(&x.view()).unwrap();
+        assert_eq!(one_hot.categories(), &vec![vec![1.0, 5.0, 9.0], vec![-1.5, 0.25]]);
+        assert_eq!(one_hot.num_classes(), 5);
+        assert_eq!(
+            encoded,
+            arr2(&[
+                [0.0, 0.0, 1.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0, 0.0, 1.0]
+            ])
+        );
+        assert_eq!(one_hot.inverse_transform(&encoded.view()).unwrap(), x);
+
+        let unseen = arr2(&[[5.0, 0.3]]);
+        assert!(one_hot.transform(&unseen.view()).is_err());
+        assert!(one_hot.fit(&arr2(&[[f64::NAN]]).view()).is_err());
+
+        let mut ignore = OneHot::with_options(OneHotOptions {
+            handle_unknown: UnknownCategory::Ignore,
+            drop_first: false
+        });
+        ignore.fit(&x.view()).unwrap();
+        let encoded = ignore.transform(&unseen.view()).unwrap();
+        assert_eq!(encoded, arr2(&[[0.0, 1.0, 0.0, 0.0, 0.0]]));
+        let decoded = ignore.inverse_transform(&encoded.view()).unwrap();
+        assert_eq!(decoded[[0, 0]], 5.0);
+        assert!(decoded[[0, 1]].is_nan());
+
+        // ignored unknowns would decode to the dropped first category
+        let mut ambiguous = OneHot::with_options(OneHotOptions {
+            handle_unknown: UnknownCategory::Ignore,
+            drop_first: true
+        });
+        assert!(ambiguous.fit(&x.view()).is_err());
+        assert!(!ambiguous.is_fitted());
+
+        let mut other = OneHot::with_options(OneHotOptions {
+            handle_unknown: UnknownCategory::Other,
+            drop_first: true
+        });
+        other.fit(&x.view()).unwrap();
+        let encoded = other.transform(&arr2(&[[1.0, 0.3], [9.0, 0.25]]).view()).unwrap();
+        assert_eq!(
+            encoded,
+            arr2(&[
+                [0.0, 0.0, 0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0, 1.0, 0.0]
+            ])
+        );
+
+        // dropped first category decodes from a row of zeros
+        let decoded = other.inverse_transform(&encoded.view()).unwrap();
+        assert_eq!(decoded[[0, 0]], 1.0);
+        assert!(decoded[[0, 1]].is_nan());
+        assert_eq!(decoded.row(1).to_vec(), vec![9.0, 0.25]);
+        assert!(other.inverse_transform(&x.view()).is_err());
     }
 
 
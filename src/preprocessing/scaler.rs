@@ -0,0 +1,705 @@
+//! Robust, max absolute, row normalization & power transform scalers
+
+use ndarray::{Array, Array1, Array2, ArrayView1, ArrayView2, Axis};
+use serde::{Serialize, Deserialize};
+
+use crate::preprocessing::processor::*;
+
+
+/// Quantile of values with linear interpolation between closest ranks
+///
+/// # Arguments
+///
+/// * `values` - Values to take quantile of.
+/// * `q` - Quantile between 0 and 1.
+///
+pub fn quantile(values: &ArrayView1<f64>, q: f64) -> f64 {
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let rank = q * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+
+/// Robust scalar, centers features on the median & scales by the inter quantile range.
+/// Outliers have less influence on the scale than with the standard scalar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobustScaler {
+
+    /// Lower & upper quantile of the range used for scaling
+    quantile_range: (f64, f64),
+
+    /// Median of each feature
+    center: Vec<f64>,
+
+    /// Inter quantile range of each feature
+    scale: Vec<f64>
+}
+
+
+impl RobustScaler {
+
+    /// Create instance of robust scaler with the inter quartile range (25% to 75%).
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[1.0], [2.0], [3.0], [4.0], [100.0]]);
+    /// let mut scaler = RobustScaler::new();
+    /// let encoded = scaler.fit_transform(&x.view()).unwrap();
+    /// assert_eq!(encoded.column(0).to_vec(), vec![-1.0, -0.5, 0.0, 0.5, 48.5]);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            quantile_range: (0.25, 0.75),
+            center: vec![],
+            scale: vec![]
+        }
+    }
+
+    /// Create instance of robust scaler with a custom quantile range.
+    ///
+    /// # Arguments
+    ///
+    /// * `lower` - Lower quantile of range, between 0 and 1.
+    /// * `upper` - Upper quantile of range, greater than lower.
+    ///
+    pub fn with_quantile_range(lower: f64, upper: f64) -> Result<Self, String> {
+
+        if !(0.0..=1.0).contains(&lower) || !(0.0..=1.0).contains(&upper) || lower >= upper {
+            return Err(
+                "Quantile range must satisfy 0 <= lower < upper <= 1".to_string()
+            );
+        }
+
+        Ok(Self {
+            quantile_range: (lower, upper),
+            center: vec![],
+            scale: vec![]
+        })
+    }
+
+    /// Retrieve median of each feature
+    pub fn center(&self) -> &Vec<f64> {
+        &self.center
+    }
+
+    /// Retrieve inter quantile range of each feature
+    pub fn scale(&self) -> &Vec<f64> {
+        &self.scale
+    }
+
+}
+
+
+impl Default for RobustScaler {
+
+    fn default() -> Self {
+        RobustScaler::new()
+    }
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for RobustScaler {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        let (lower, upper) = self.quantile_range;
+        self.center = data
+            .axis_iter(Axis(1))
+            .map(|col| quantile(&col, 0.5))
+            .collect();
+
+        self.scale = data
+            .axis_iter(Axis(1))
+            .map(|col| non_zero(quantile(&col, upper) - quantile(&col, lower)))
+            .collect();
+
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.center.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.center.len())?;
+
+        let center = Array::from_vec(self.center.clone());
+        let scale = Array::from_vec(self.scale.clone());
+        Ok((data - &center) / &scale)
+    }
+
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.center.len())?;
+
+        let center = Array::from_vec(self.center.clone());
+        let scale = Array::from_vec(self.scale.clone());
+        Ok(data * &scale + &center)
+    }
+
+}
+
+
+/// Max absolute scaler, divides features by their largest absolute value to a range of [-1, 1].
+/// Features are not centered so zero entries stay zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaxAbsScaler {
+
+    /// Largest absolute value of each feature
+    max_abs: Vec<f64>
+}
+
+
+impl MaxAbsScaler {
+
+    /// Create instance of max absolute scaler.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[1.0, 0.0], [-4.0, 0.0], [2.0, 5.0]]);
+    /// let mut scaler = MaxAbsScaler::new();
+    /// let encoded = scaler.fit_transform(&x.view()).unwrap();
+    /// assert_eq!(encoded, arr2(&[[0.25, 0.0], [-1.0, 0.0], [0.5, 1.0]]));
+    /// ```
+    pub fn new() -> Self {
+        Self { max_abs: vec![] }
+    }
+
+    /// Retrieve largest absolute value of each feature
+    pub fn max_abs(&self) -> &Vec<f64> {
+        &self.max_abs
+    }
+
+}
+
+
+impl Default for MaxAbsScaler {
+
+    fn default() -> Self {
+        MaxAbsScaler::new()
+    }
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for MaxAbsScaler {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        self.max_abs = data
+            .axis_iter(Axis(1))
+            .map(|col| non_zero(col.fold(0.0, |max: f64, x| max.max(x.abs()))))
+            .collect();
+
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.max_abs.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.max_abs.len())?;
+
+        let max_abs = Array::from_vec(self.max_abs.clone());
+        Ok(data / &max_abs)
+    }
+
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.max_abs.len())?;
+
+        let max_abs = Array::from_vec(self.max_abs.clone());
+        Ok(data * &max_abs)
+    }
+
+}
+
+
+/// Norm used to scale rows of the normalizer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Norm {
+
+    /// Sum of absolute values
+    L1,
+
+    /// Euclidean length
+    L2,
+
+    /// Largest absolute value
+    Max
+}
+
+
+/// Normalizer, scales each row (sample) to unit norm.
+/// Rows are scaled independently, fitting only records the number of features.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Normalizer {
+
+    /// Norm each row is scaled to
+    norm: Norm,
+
+    /// Number of features the normalizer was fit on
+    n_features: usize
+}
+
+
+impl Normalizer {
+
+    /// Create instance of normalizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `norm` - Norm each row is scaled to.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[3.0, 4.0], [1.0, 0.0]]);
+    /// let mut normalizer = Normalizer::new(Norm::L2);
+    /// let encoded = normalizer.fit_transform(&x.view()).unwrap();
+    /// assert_eq!(encoded, arr2(&[[0.6, 0.8], [1.0, 0.0]]));
+    /// ```
+    pub fn new(norm: Norm) -> Self {
+        Self {
+            norm,
+            n_features: 0
+        }
+    }
+
+    /// Retrieve norm rows are scaled to
+    pub fn norm(&self) -> Norm {
+        self.norm
+    }
+
+    /// Norm of a single row
+    fn row_norm(&self, row: &ArrayView1<f64>) -> f64 {
+        match self.norm {
+            Norm::L1 => row.mapv(f64::abs).sum(),
+            Norm::L2 => row.mapv(|x| x * x).sum().sqrt(),
+            Norm::Max => row.fold(0.0, |max: f64, x| max.max(x.abs()))
+        }
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for Normalizer {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.ncols() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        self.n_features = data.ncols();
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        self.n_features > 0
+    }
+
+    /// Scale rows to unit norm, rows of zeros are left unchanged
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.n_features)?;
+
+        let mut encoded = data.to_owned();
+        for mut row in encoded.axis_iter_mut(Axis(0)) {
+            let norm = non_zero(self.row_norm(&row.view()));
+            row.mapv_inplace(|x| x / norm);
+        }
+        Ok(encoded)
+    }
+
+    /// Norms of rows are not kept, so normalized rows can't be decoded
+    fn inverse_transform(&self, _data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+        Err("Normalizer does not keep row norms, rows cannot be decoded".to_string())
+    }
+
+}
+
+
+/// Power transform applied by the power transformer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PowerMethod {
+
+    /// Yeo-Johnson transform, supports positive & negative values
+    YeoJohnson,
+
+    /// Box-Cox transform, strictly positive values only
+    BoxCox
+}
+
+
+impl PowerMethod {
+
+    /// Transform single value with lambda
+    pub fn transform(&self, x: f64, lambda: f64) -> f64 {
+        let eps = 1e-8;
+        match self {
+            PowerMethod::BoxCox if lambda.abs() < eps => x.ln(),
+            PowerMethod::BoxCox => (lambda * x.ln()).exp_m1() / lambda,
+            PowerMethod::YeoJohnson if x >= 0.0 && lambda.abs() < eps => x.ln_1p(),
+            PowerMethod::YeoJohnson if x >= 0.0 => (lambda * x.ln_1p()).exp_m1() / lambda,
+            PowerMethod::YeoJohnson if (lambda - 2.0).abs() < eps => -(-x).ln_1p(),
+            PowerMethod::YeoJohnson => {
+                -((2.0 - lambda) * (-x).ln_1p()).exp_m1() / (2.0 - lambda)
+            }
+        }
+    }
+
+    /// Invert transform of single value with lambda
+    pub fn inverse(&self, y: f64, lambda: f64) -> f64 {
+        let eps = 1e-8;
+        match self {
+            PowerMethod::BoxCox if lambda.abs() < eps => y.exp(),
+            PowerMethod::BoxCox => ((y * lambda).ln_1p() / lambda).exp(),
+            PowerMethod::YeoJohnson if y >= 0.0 && lambda.abs() < eps => y.exp_m1(),
+            PowerMethod::YeoJohnson if y >= 0.0 => ((y * lambda).ln_1p() / lambda).exp_m1(),
+            PowerMethod::YeoJohnson if (lambda - 2.0).abs() < eps => -(-y).exp_m1(),
+            PowerMethod::YeoJohnson => {
+                -((-(2.0 - lambda) * y).ln_1p() / (2.0 - lambda)).exp_m1()
+            }
+        }
+    }
+
+    /// Gaussian log likelihood of feature transformed with lambda
+    fn log_likelihood(&self, col: &ArrayView1<f64>, lambda: f64) -> f64 {
+
+        let n = col.len() as f64;
+        let transformed: Array1<f64> = col.mapv(|x| self.transform(x, lambda));
+        let variance = transformed.var(0.0);
+
+        let jacobian: f64 = match self {
+            PowerMethod::BoxCox => col.mapv(f64::ln).sum(),
+            PowerMethod::YeoJohnson => col.mapv(|x| x.signum() * x.abs().ln_1p()).sum()
+        };
+
+        -n / 2.0 * variance.ln() + (lambda - 1.0) * jacobian
+    }
+
+    /// Lambda maximizing the log likelihood, golden section search over [-5, 5].
+    /// Constant features have no variance to fit (the likelihood takes `ln(0)`),
+    /// they keep a lambda of 1 so the transform stays linear.
+    fn fit_lambda(&self, col: &ArrayView1<f64>) -> f64 {
+
+        if col.iter().all(|x| *x == col[0]) {
+            return 1.0;
+        }
+
+        let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+        let (mut a, mut b) = (-5.0, 5.0);
+        let mut c = b - ratio * (b - a);
+        let mut d = a + ratio * (b - a);
+
+        while (b - a).abs() > 1e-8 {
+            if self.log_likelihood(col, c) > self.log_likelihood(col, d) {
+                b = d;
+            } else {
+                a = c;
+            }
+            c = b - ratio * (b - a);
+            d = a + ratio * (b - a);
+        }
+
+        (a + b) / 2.0
+    }
+
+}
+
+
+/// Power transformer, applies a Yeo-Johnson or Box-Cox transform per feature with lambdas
+/// fitted by maximum likelihood to make features more normally distributed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerTransformer {
+
+    /// Power transform applied to features
+    method: PowerMethod,
+
+    /// Fitted lambda of each feature
+    lambdas: Vec<f64>,
+
+    /// Standard scalar applied after the power transform (zero mean & unit variance)
+    scalar: Option<StandardScalar>
+}
+
+
+impl PowerTransformer {
+
+    /// Create instance of power transformer.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Power transform applied to features.
+    /// * `standardize` - Scale transformed features to zero mean & unit variance.
+    ///
+    /// ```
+    /// use ndarray::arr2;
+    /// use dendritic::preprocessing::prelude::*;
+    ///
+    /// let x = arr2(&[[1.0], [2.0], [4.0], [8.0], [16.0]]);
+    /// let mut transformer = PowerTransformer::new(PowerMethod::BoxCox, true);
+    /// let encoded = transformer.fit_transform(&x.view()).unwrap();
+    /// let decoded = transformer.inverse_transform(&encoded.view()).unwrap();
+    /// println!("Lambdas: {:?} Decoded: {:?}", transformer.lambdas(), decoded);
+    /// ```
+    pub fn new(method: PowerMethod, standardize: bool) -> Self {
+        Self {
+            method,
+            lambdas: vec![],
+            scalar: match standardize {
+                true => Some(StandardScalar::new()),
+                false => None
+            }
+        }
+    }
+
+    /// Retrieve fitted lambda of each feature
+    pub fn lambdas(&self) -> &Vec<f64> {
+        &self.lambdas
+    }
+
+    /// Retrieve power transform applied to features
+    pub fn method(&self) -> PowerMethod {
+        self.method
+    }
+
+    /// Error for values outside of the domain of Box-Cox
+    fn check_domain(&self, data: &ArrayView2<f64>) -> Result<(), String> {
+        if self.method == PowerMethod::BoxCox && data.iter().any(|x| *x <= 0.0) {
+            return Err("Box-Cox transform requires strictly positive data".to_string());
+        }
+        Ok(())
+    }
+
+    /// Power transform of each feature with its fitted lambda
+    fn power(&self, data: &ArrayView2<f64>) -> Array2<f64> {
+        let mut transformed = data.to_owned();
+        for (mut col, lambda) in transformed.axis_iter_mut(Axis(1)).zip(self.lambdas.iter()) {
+            col.mapv_inplace(|x| self.method.transform(x, *lambda));
+        }
+        transformed
+    }
+
+}
+
+
+#[typetag::serde]
+impl FeatureEncoder for PowerTransformer {
+
+    fn fit(&mut self, data: &ArrayView2<f64>) -> Result<(), String> {
+
+        if data.nrows() == 0 {
+            return Err("Cannot fit encoder on empty data".to_string());
+        }
+
+        self.check_domain(data)?;
+        self.lambdas = data
+            .axis_iter(Axis(1))
+            .map(|col| self.method.fit_lambda(&col))
+            .collect();
+
+        let transformed = self.power(data);
+        if let Some(scalar) = self.scalar.as_mut() {
+            scalar.fit(&transformed.view())?;
+        }
+
+        Ok(())
+    }
+
+    fn is_fitted(&self) -> bool {
+        !self.lambdas.is_empty()
+    }
+
+    fn transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.lambdas.len())?;
+        self.check_domain(data)?;
+
+        let transformed = self.power(data);
+        match &self.scalar {
+            Some(scalar) => scalar.transform(&transformed.view()),
+            None => Ok(transformed)
+        }
+    }
+
+    fn inverse_transform(&self, data: &ArrayView2<f64>) -> Result<Array2<f64>, String> {
+
+        check_fitted(self)?;
+        check_columns(data, self.lambdas.len())?;
+
+        let mut decoded = match &self.scalar {
+            Some(scalar) => scalar.inverse_transform(data)?,
+            None => data.to_owned()
+        };
+
+        for (mut col, lambda) in decoded.axis_iter_mut(Axis(1)).zip(self.lambdas.iter()) {
+            col.mapv_inplace(|y| self.method.inverse(y, *lambda));
+        }
+        Ok(decoded)
+    }
+
+}
+
+
+#[cfg(test)]
+mod scaler_tests {
+
+    use ndarray::{arr1, arr2, Array2};
+    use crate::preprocessing::scaler::*;
+
+
+    #[test]
+    fn test_quantile() {
+        let values = arr1(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(quantile(&values.view(), 0.0), 1.0);
+        assert_eq!(quantile(&values.view(), 0.5), 2.5);
+        assert_eq!(quantile(&values.view(), 0.25), 1.75);
+        assert_eq!(quantile(&values.view(), 1.0), 4.0);
+    }
+
+
+    #[test]
+    fn test_robust_and_max_abs_scaler() {
+
+        let x = arr2(&[
+            [1.0, -2.0, 3.0],
+            [2.0, 0.0, 3.0],
+            [3.0, 4.0, 3.0],
+            [4.0, 1.0, 3.0],
+            [100.0, -8.0, 3.0]
+        ]);
+
+        let mut robust = RobustScaler::new();
+        assert!(robust.transform(&x.view()).is_err());
+
+        let encoded = robust.fit_transform(&x.view()).unwrap();
+        assert_eq!(robust.center(), &vec![3.0, 0.0, 3.0]);
+        assert_eq!(robust.scale(), &vec![2.0, 3.0, 1.0]);
+        assert_eq!(encoded.column(0).to_vec(), vec![-1.0, -0.5, 0.0, 0.5, 48.5]);
+        assert_eq!(encoded.column(2).to_vec(), vec![0.0; 5]);
+        assert_eq!(robust.inverse_transform(&encoded.view()).unwrap(), x);
+
+        let wide = RobustScaler::with_quantile_range(0.0, 1.0);
+        assert!(wide.is_ok());
+        assert!(RobustScaler::with_quantile_range(0.75, 0.25).is_err());
+
+        let mut max_abs = MaxAbsScaler::new();
+        let encoded = max_abs.fit_transform(&x.view()).unwrap();
+        assert_eq!(max_abs.max_abs(), &vec![100.0, 8.0, 3.0]);
+        assert_eq!(encoded.column(1).to_vec(), vec![-0.25, 0.0, 0.5, 0.125, -1.0]);
+        assert_eq!(max_abs.inverse_transform(&encoded.view()).unwrap(), x);
+        assert!(max_abs.transform(&arr2(&[[1.0]]).view()).is_err());
+    }
+
+
+    #[test]
+    fn test_normalizer() {
+
+        let x = arr2(&[[3.0, -4.0], [0.0, 0.0], [1.0, 1.0]]);
+
+        let mut l1 = Normalizer::new(Norm::L1);
+        let encoded = l1.fit_transform(&x.view()).unwrap();
+        assert_eq!(encoded, arr2(&[[3.0 / 7.0, -4.0 / 7.0], [0.0, 0.0], [0.5, 0.5]]));
+
+        let mut l2 = Normalizer::new(Norm::L2);
+        let encoded = l2.fit_transform(&x.view()).unwrap();
+        assert_eq!(encoded.row(0).to_vec(), vec![0.6, -0.8]);
+
+        let mut max = Normalizer::new(Norm::Max);
+        let encoded = max.fit_transform(&x.view()).unwrap();
+        assert_eq!(encoded.row(0).to_vec(), vec![0.75, -1.0]);
+        assert!(max.inverse_transform(&encoded.view()).is_err());
+    }
+
+
+    #[test]
+    fn test_power_transformer() {
+
+        // log normal feature & skewed feature with negative values
+        let z = [-2.0, -1.5, -1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0];
+        let x = Array2::from_shape_fn((9, 2), |(i, j)| {
+            match j {
+                0 => f64::exp(z[i]),
+                _ => z[i] * z[i] * z[i] - 1.0
+            }
+        });
+
+        let positive = x.slice(ndarray::s![.., 0..1]);
+        let mut box_cox = PowerTransformer::new(PowerMethod::BoxCox, false);
+        box_cox.fit(&positive).unwrap();
+        assert!(box_cox.lambdas()[0].abs() < 1e-4);
+
+        let encoded = box_cox.transform(&positive).unwrap();
+        for (value, expected) in encoded.column(0).iter().zip(z.iter()) {
+            assert!((value - expected).abs() < 1e-3);
+        }
+
+        let decoded = box_cox.inverse_transform(&encoded.view()).unwrap();
+        assert!((decoded - positive).mapv(f64::abs).sum() < 1e-9);
+        assert!(box_cox.transform(&arr2(&[[0.0]]).view()).is_err());
+
+        let negative = arr2(&[[1.0], [-1.0]]);
+        assert!(PowerTransformer::new(PowerMethod::BoxCox, true).fit(&negative.view()).is_err());
+
+        let mut yeo_johnson = PowerTransformer::new(PowerMethod::YeoJohnson, true);
+        let encoded = yeo_johnson.fit_transform(&x.view()).unwrap();
+        for col in encoded.axis_iter(ndarray::Axis(1)) {
+            assert!(col.mean().unwrap().abs() < 1e-9);
+            assert!((col.var(0.0) - 1.0).abs() < 1e-9);
+        }
+
+        let decoded = yeo_johnson.inverse_transform(&encoded.view()).unwrap();
+        assert!((decoded - &x).mapv(f64::abs).sum() < 1e-6);
+
+        for lambda in [-1.0, 0.0, 0.5, 2.0, 3.0] {
+            for value in [-3.0, -0.5, 0.0, 0.5, 3.0] {
+                let y = PowerMethod::YeoJohnson.transform(value, lambda);
+                let x = PowerMethod::YeoJohnson.inverse(y, lambda);
+                assert!((x - value).abs() < 1e-9);
+            }
+        }
+
+        // constant features keep a linear transform instead of NaN
+        let constant = arr2(&[[3.0, 1.0], [3.0, 2.0], [3.0, 4.0]]);
+        let mut transformer = PowerTransformer::new(PowerMethod::YeoJohnson, false);
+        let encoded = transformer.fit_transform(&constant.view()).unwrap();
+        assert_eq!(transformer.lambdas()[0], 1.0);
+        assert!(encoded.iter().all(|x| x.is_finite()));
+        assert_eq!(encoded.column(0).to_vec(), vec![3.0, 3.0, 3.0]);
+
+        let decoded = transformer.inverse_transform(&encoded.view()).unwrap();
+        assert!((decoded - &constant).mapv(f64::abs).sum() < 1e-9);
+    }
+
+}
@@ -0,0 +1,351 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::prelude::SliceRandom;
+use ndarray::{Array2, Axis};
+use serde::Serialize;
+
+use crate::metrics::classification::class_rows;
+use crate::optimizer::model::*;
+use crate::optimizer::train::MetricFn;
+
+
+/// K-fold cross validation runner for scoring model configurations on held out folds
+#[derive(Debug, Clone)]
+pub struct CrossValidation {
+
+    /// Number of folds to split the dataset into
+    n_folds: usize,
+
+    /// Seed for shuffling rows before splitting (rows keep their order when `None`)
+    seed: Option<u64>,
+
+    /// Preserve class proportions of the labels in each fold
+    stratified: bool,
+
+    /// Named metrics computed on predictions of each test fold
+    metrics: Vec<(String, MetricFn)>
+}
+
+
+/// Row indices used for training and testing in a single fold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fold {
+
+    /// Rows used to train the model
+    pub train: Vec<usize>,
+
+    /// Rows held out to score the model
+    pub test: Vec<usize>
+}
+
+
+/// Scores of a model trained and evaluated on a single fold
+#[derive(Debug, Clone, Serialize)]
+pub struct FoldResult {
+
+    /// Index of the fold
+    pub fold: usize,
+
+    /// Loss of model on training rows of the fold
+    pub train_loss: f64,
+
+    /// Loss of model on test rows of the fold
+    pub test_loss: f64,
+
+    /// Named metrics computed on test rows of the fold
+    pub metrics: Vec<(String, f64)>
+}
+
+
+/// Summary statistics of a score across folds
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Aggregate {
+    pub mean: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64
+}
+
+
+/// Results of a cross validation run
+#[derive(Debug, Clone, Serialize)]
+pub struct CrossValidationResult {
+
+    /// Scores recorded for each fold
+    pub folds: Vec<FoldResult>,
+
+    /// Aggregate of test loss across folds
+    pub test_loss: Aggregate,
+
+    /// Aggregate of each named metric across folds
+    pub metrics: Vec<(String, Aggregate)>
+}
+
+
+impl Aggregate {
+
+    /// Compute summary statistics of scores (population standard deviation)
+    pub fn new(scores: &[f64]) -> Self {
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let var = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+
+        Aggregate {
+            mean,
+            std: var.sqrt(),
+            min: scores.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+        }
+    }
+}
+
+
+impl CrossValidationResult {
+
+    /// Aggregate of named metric across folds
+    pub fn metric(&self, name: &str) -> Option<Aggregate> {
+        self.metrics
+            .iter()
+            .find(|(metric, _)| metric == name)
+            .map(|(_, aggregate)| *aggregate)
+    }
+}
+
+
+impl CrossValidation {
+
+    /// Create cross validation runner.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_folds` - Number of folds, must be at least 2.
+    /// * `seed` - Seed for shuffling rows before splitting, rows are not shuffled when `None`.
+    /// * `stratified` - Preserve class proportions of the labels in each fold.
+    ///
+    pub fn new(
+        n_folds: usize,
+        seed: Option<u64>,
+        stratified: bool) -> Result<Self, String> {
+
+        if n_folds < 2 {
+            return Err(
+                "Cross validation requires at least 2 folds".to_string()
+            );
+        }
+
+        Ok(CrossValidation {
+            n_folds,
+            seed,
+            stratified,
+            metrics: vec![]
+        })
+    }
+
+    /// Add named metric computed on predictions of each test fold
+    pub fn add_metric(&mut self, name: &str, metric: MetricFn) {
+        self.metrics.push((name.to_string(), metric));
+    }
+
+    /// Number of folds the dataset is split into
+    pub fn n_folds(&self) -> usize {
+        self.n_folds
+    }
+
+    /// Shuffle indices in place when a seed is provided
+    fn shuffle(&self, indices: &mut [usize]) {
+        if let Some(seed) = self.seed {
+            indices.shuffle(&mut StdRng::seed_from_u64(seed));
+        }
+    }
+
+    /// Split rows of dataset into train and test indices for each fold.
+    ///
+    /// # Arguments
+    ///
+    /// * `y` - Target labels (non negative integers or one hot rows), used to stratify folds by class.
+    ///
+    pub fn folds(&self, y: &Array2<f64>) -> Result<Vec<Fold>, String> {
+
+        let rows = y.nrows();
+        if rows < self.n_folds {
+            return Err(format!(
+                "Number of folds {} exceeds number of rows {}",
+                self.n_folds, rows
+            ));
+        }
+
+        let mut assigned: Vec<Vec<usize>> = vec![vec![]; self.n_folds];
+
+        if self.stratified {
+
+            // deal each class across folds so class proportions are preserved
+            let mut classes = class_rows(y)?;
+
+            let mut position = 0;
+            for class in classes.iter_mut() {
+                self.shuffle(class);
+                for idx in class.iter() {
+                    assigned[position % self.n_folds].push(*idx);
+                    position += 1;
+                }
+            }
+
+        } else {
+
+            let mut indices: Vec<usize> = (0..rows).collect();
+            self.shuffle(&mut indices);
+
+            let (size, remainder) = (rows / self.n_folds, rows % self.n_folds);
+            let mut start = 0;
+            for (fold, test) in assigned.iter_mut().enumerate() {
+                let end = start + size + usize::from(fold < remainder);
+                test.extend_from_slice(&indices[start..end]);
+                start = end;
+            }
+        }
+
+        let folds = assigned
+            .iter()
+            .enumerate()
+            .map(|(fold, test)| {
+                let train = assigned
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != fold)
+                    .flat_map(|(_, idxs)| idxs.iter().cloned())
+                    .collect();
+                Fold { train, test: test.clone() }
+            })
+            .collect();
+
+        Ok(folds)
+    }
+
+    /// Train a new model on each fold and score it on the held out rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features of the full dataset.
+    /// * `y` - Target labels of the full dataset.
+    /// * `factory` - Creates an untrained model from the training rows of a fold.
+    /// * `fit` - Trains the model created for a fold.
+    ///
+    pub fn run<M, F, T>(
+        &self,
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        factory: F,
+        mut fit: T) -> Result<CrossValidationResult, String>
+    where
+        M: Model,
+        F: Fn(&Array2<f64>, &Array2<f64>) -> Result<M, String>,
+        T: FnMut(&mut M) {
+
+        if x.nrows() != y.nrows() {
+            return Err(
+                "Features and labels must have same number of rows".to_string()
+            );
+        }
+
+        let mut results = Vec::new();
+        for (idx, fold) in self.folds(y)?.iter().enumerate() {
+
+            let x_train = x.select(Axis(0), &fold.train);
+            let y_train = y.select(Axis(0), &fold.train);
+            let x_test = x.select(Axis(0), &fold.test);
+            let y_test = y.select(Axis(0), &fold.test);
+
+            let mut model = factory(&x_train, &y_train)?;
+            fit(&mut model);
+
+            model.set_input(&x_train);
+            model.set_output(&y_train);
+            model.forward();
+            let train_loss = model.loss();
+
+            model.set_input(&x_test);
+            model.set_output(&y_test);
+            model.forward();
+
+            let test_loss = model.loss();
+            let predicted = model.predicted();
+            let metrics = self.metrics
+                .iter()
+                .map(|(name, metric)| (name.clone(), metric(&y_test, &predicted)))
+                .collect();
+
+            results.push(FoldResult {
+                fold: idx,
+                train_loss,
+                test_loss,
+                metrics
+            });
+        }
+
+        let test_losses: Vec<f64> = results.iter().map(|r| r.test_loss).collect();
+        let metrics = self.metrics
+            .iter()
+            .enumerate()
+            .map(|(idx, (name, _))| {
+                let scores: Vec<f64> = results.iter().map(|r| r.metrics[idx].1).collect();
+                (name.clone(), Aggregate::new(&scores))
+            })
+            .collect();
+
+        Ok(CrossValidationResult {
+            folds: results,
+            test_loss: Aggregate::new(&test_losses),
+            metrics
+        })
+    }
+
+}
+
+
+#[cfg(test)]
+mod cross_validation_test {
+
+    use ndarray::arr2;
+    use crate::selection::cross_validation::*;
+
+    #[test]
+    fn test_folds() {
+
+        let y = arr2(&[[0.0], [0.0], [0.0], [0.0], [1.0], [1.0], [1.0], [1.0], [1.0]]);
+
+        let kfold = CrossValidation::new(3, None, false).unwrap();
+        let folds = kfold.folds(&y).unwrap();
+        assert_eq!(folds.len(), 3);
+        assert_eq!(folds[0].test, vec![0, 1, 2]);
+        assert_eq!(folds[0].train, vec![3, 4, 5, 6, 7, 8]);
+        assert_eq!(folds[2].test, vec![6, 7, 8]);
+
+        let shuffled = CrossValidation::new(2, Some(5), false).unwrap();
+        let folds = shuffled.folds(&y).unwrap();
+        assert_eq!(folds[0].test.len(), 5);
+        assert_eq!(folds[1].test.len(), 4);
+        assert_eq!(folds, shuffled.folds(&y).unwrap());
+
+        let mut all: Vec<usize> = folds.iter().flat_map(|f| f.test.clone()).collect();
+        all.sort();
+        assert_eq!(all, (0..9).collect::<Vec<usize>>());
+
+        let stratified = CrossValidation::new(2, Some(5), true).unwrap();
+        for fold in stratified.folds(&y).unwrap() {
+            let positives = fold.test.iter().filter(|idx| y[[**idx, 0]] == 1.0).count();
+            let negatives = fold.test.len() - positives;
+            assert_eq!(negatives, 2);
+            assert!(positives == 2 || positives == 3);
+        }
+
+        // labels are grouped by value instead of indexing a vector by label
+        assert_eq!(stratified.folds(&y.mapv(|v| v * 1e12)).unwrap().len(), 2);
+        assert!(stratified.folds(&y.mapv(|v| v - 1.0)).is_err());
+        assert!(stratified.folds(&y.mapv(|v| v + 0.5)).is_err());
+        assert!(stratified.folds(&y.mapv(|_| f64::NAN)).is_err());
+
+        assert!(CrossValidation::new(1, None, false).is_err());
+        assert!(CrossValidation::new(10, None, false).unwrap().folds(&y).is_err());
+    }
+
+}
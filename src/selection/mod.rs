@@ -0,0 +1,90 @@
+//! # Model selection
+//!
+//! This module provides utilities for scoring model configurations on held out data.
+//! Models are created from a factory for every split of the dataset,
+//! so hyperparameters such as the learning rate or `lambda` of `Ridge`, `Lasso` and `Elastic`
+//! can be compared on data the model was not trained on.
+//!
+//! ## Features
+//! - **Cross Validation**: K-fold and stratified k-fold splits with seeded shuffling, per fold scores and aggregates.
+//! - **Search**: Grid, random and successive halving search over learning rate, `lambda`, `alpha`, batch size and optimizer.
+//!
+//! ```rust
+//! use ndarray::arr2;
+//! use dendritic::optimizer::prelude::*;
+//! use dendritic::metrics::prelude::*;
+//! use dendritic::selection::prelude::*;
+//!
+//! let x = arr2(&[
+//!     [1.0, 2.0, 3.0],
+//!     [2.0, 3.0, 4.0],
+//!     [3.0, 4.0, 5.0],
+//!     [4.0, 5.0, 6.0],
+//!     [5.0, 6.0, 7.0],
+//!     [6.0, 7.0, 8.0]
+//! ]);
+//!
+//! let y = arr2(&[[10.0], [12.0], [14.0], [16.0], [18.0], [20.0]]);
+//!
+//! let mut cv = CrossValidation::new(3, Some(42), false).unwrap();
+//! cv.add_metric("mae", mean_absolute_error);
+//!
+//! let result = cv.run(
+//!     &x, &y,
+//!     |x, y| Ridge::new(x, y, 0.001, 0.01),
+//!     |model| model.train(500)
+//! ).unwrap();
+//!
+//! println!("Test loss: {:?}", result.test_loss.mean);
+//! println!("MAE: {:?}", result.metric("mae").unwrap().mean);
+//! ```
+//!
+//! # Example: Hyperparameter Search
+//!
+//! Candidates are ranked by a metric on the held out folds
+//! and the best configuration is refit on the full dataset.
+//!
+//! ```rust
+//! use ndarray::arr2;
+//! use dendritic::optimizer::prelude::*;
+//! use dendritic::metrics::prelude::*;
+//! use dendritic::selection::prelude::*;
+//!
+//! let x = arr2(&[
+//!     [1.0, 2.0, 3.0],
+//!     [2.0, 3.0, 4.0],
+//!     [3.0, 4.0, 5.0],
+//!     [4.0, 5.0, 6.0],
+//!     [5.0, 6.0, 7.0],
+//!     [6.0, 7.0, 8.0]
+//! ]);
+//!
+//! let y = arr2(&[[10.0], [12.0], [14.0], [16.0], [18.0], [20.0]]);
+//!
+//! let grid = ParamGrid {
+//!     learning_rate: vec![0.001, 0.0001],
+//!     lambda: ParamGrid::log_space(0.001, 1.0, 4).unwrap(),
+//!     alpha: vec![0.2, 0.8],
+//!     optimizer: vec![OptimizerKind::Default, OptimizerKind::Adam],
+//!     ..ParamGrid::default()
+//! };
+//!
+//! let cv = CrossValidation::new(3, Some(42), false).unwrap();
+//! let mut search = Search::new(cv, 200).unwrap();
+//! search.rank_by("r2", r2_score, Direction::Maximize);
+//!
+//! let result = search.successive_halving(
+//!     &x, &y,
+//!     &grid.sample(8, 7).unwrap(),
+//!     2,
+//!     |x, y, params| Elastic::new(x, y, params.learning_rate, params.lambda, params.alpha)
+//! ).unwrap();
+//!
+//! println!("Best: {:?}", result.ranked[0].params);
+//! let mut model = result.model;
+//! println!("Predictions: {:?}", model.predict(&x));
+//! ```
+
+pub mod cross_validation;
+pub mod search;
+pub mod prelude;
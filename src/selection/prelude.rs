@@ -0,0 +1,2 @@
+pub use super::cross_validation::*;
+pub use super::search::*;
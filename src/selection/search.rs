@@ -0,0 +1,506 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::prelude::SliceRandom;
+use ndarray::Array2;
+use serde::{Serialize, Deserialize};
+
+use crate::optimizer::model::*;
+use crate::optimizer::optimizers::*;
+use crate::optimizer::train::*;
+use crate::selection::cross_validation::*;
+
+
+/// Optimizer used to update parameters while training a candidate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OptimizerKind {
+
+    /// Update rule of the model itself
+    Default,
+    Nesterov,
+    Adagrad,
+    RMSProp,
+    Adadelta,
+    Adam
+}
+
+
+/// Whether lower or higher scores rank first
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Minimize,
+    Maximize
+}
+
+
+/// Single configuration of hyperparameters explored by a search
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hyperparameters {
+
+    /// Learning rate of model and optimizer
+    pub learning_rate: f64,
+
+    /// Regularization strength
+    pub lambda: f64,
+
+    /// Mixing parameter of L1 & L2 penalties for `Elastic`
+    pub alpha: f64,
+
+    /// Rows per batch, full dataset is trained each epoch when `None`
+    pub batch_size: Option<usize>,
+
+    /// Optimizer used to update parameters
+    pub optimizer: OptimizerKind
+}
+
+
+/// Values to explore for each hyperparameter
+#[derive(Debug, Clone)]
+pub struct ParamGrid {
+    pub learning_rate: Vec<f64>,
+    pub lambda: Vec<f64>,
+    pub alpha: Vec<f64>,
+    pub batch_size: Vec<Option<usize>>,
+    pub optimizer: Vec<OptimizerKind>
+}
+
+
+/// Cross validated scores of a single candidate configuration
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+
+    /// Hyperparameters the candidate was trained with
+    pub params: Hyperparameters,
+
+    /// Number of epochs the candidate was trained for
+    pub epochs: usize,
+
+    /// Mean of ranking metric across folds
+    pub score: f64,
+
+    /// Per fold scores and aggregates
+    pub result: CrossValidationResult
+}
+
+
+/// Ranked candidates of a search and best model refit on the full dataset
+pub struct SearchResult<M> {
+
+    /// Candidates ordered from best to worst
+    pub ranked: Vec<Candidate>,
+
+    /// Best model trained on all rows with best configuration
+    pub model: M
+}
+
+
+/// Hyperparameter search scoring candidates with cross validation
+pub struct Search {
+
+    /// Cross validation used to score each candidate
+    cv: CrossValidation,
+
+    /// Number of epochs (or batch iterations) each candidate is trained for
+    epochs: usize,
+
+    /// Metric candidates are ranked by, test loss when `None`
+    metric: Option<String>,
+
+    /// Whether lower or higher scores rank first
+    direction: Direction
+}
+
+
+impl Default for Hyperparameters {
+
+    fn default() -> Self {
+        Hyperparameters {
+            learning_rate: 0.01,
+            lambda: 0.0,
+            alpha: 0.5,
+            batch_size: None,
+            optimizer: OptimizerKind::Default
+        }
+    }
+}
+
+
+impl Default for ParamGrid {
+
+    fn default() -> Self {
+        let params = Hyperparameters::default();
+        ParamGrid {
+            learning_rate: vec![params.learning_rate],
+            lambda: vec![params.lambda],
+            alpha: vec![params.alpha],
+            batch_size: vec![params.batch_size],
+            optimizer: vec![params.optimizer]
+        }
+    }
+}
+
+
+impl ParamGrid {
+
+    /// Values spaced evenly on a log scale between low and high (inclusive).
+    ///
+    /// # Arguments
+    ///
+    /// * `low` - Smallest value, must be positive.
+    /// * `high` - Largest value, at least `low`.
+    /// * `n` - Number of values.
+    ///
+    pub fn log_space(low: f64, high: f64, n: usize) -> Result<Vec<f64>, String> {
+        if !(low > 0.0 && low <= high) {
+            return Err(format!(
+                "Log space requires 0 < low <= high, got low {} and high {}", low, high
+            ));
+        }
+        match n {
+            0 => Ok(Vec::new()),
+            1 => Ok(vec![low]),
+            _ => {
+                let (start, end) = (low.log10(), high.log10());
+                let step = (end - start) / (n - 1) as f64;
+                Ok((0..n).map(|i| 10f64.powf(start + step * i as f64)).collect())
+            }
+        }
+    }
+
+    /// Every combination of values in the grid
+    pub fn candidates(&self) -> Result<Vec<Hyperparameters>, String> {
+
+        if self.learning_rate.is_empty() || self.lambda.is_empty() || self.alpha.is_empty()
+            || self.batch_size.is_empty() || self.optimizer.is_empty() {
+            return Err(
+                "Every hyperparameter in grid requires at least one value".to_string()
+            );
+        }
+
+        let mut candidates = Vec::new();
+        for learning_rate in &self.learning_rate {
+            for lambda in &self.lambda {
+                for alpha in &self.alpha {
+                    for batch_size in &self.batch_size {
+                        for optimizer in &self.optimizer {
+                            candidates.push(Hyperparameters {
+                                learning_rate: *learning_rate,
+                                lambda: *lambda,
+                                alpha: *alpha,
+                                batch_size: *batch_size,
+                                optimizer: *optimizer
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Random combinations of values in the grid without repeats.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of combinations (all combinations when grid is smaller).
+    /// * `seed` - Seed for sampling combinations.
+    ///
+    pub fn sample(&self, n: usize, seed: u64) -> Result<Vec<Hyperparameters>, String> {
+        let mut candidates = self.candidates()?;
+        candidates.shuffle(&mut StdRng::seed_from_u64(seed));
+        candidates.truncate(n);
+        Ok(candidates)
+    }
+}
+
+
+/// Train model with optimizer and batch size from hyperparameters.
+///
+/// # Arguments
+///
+/// * `model` - Model to train.
+/// * `params` - Hyperparameters selecting optimizer and batch size.
+/// * `epochs` - Number of epochs, or iterations over all batches when batch size is set.
+///
+pub fn fit<M>(model: &mut M, params: &Hyperparameters, epochs: usize)
+where M: Model + Trainable + TrainOptimizer {
+
+    let lr = params.learning_rate;
+    match params.optimizer {
+        OptimizerKind::Default => {
+            match params.batch_size {
+                Some(batch_size) => model.train_batch(epochs, batch_size, 1, false, None),
+                None => model.train(epochs)
+            }
+        },
+        OptimizerKind::Nesterov => fit_optimizer(model, params, epochs, &mut Nesterov::new(model, lr)),
+        OptimizerKind::Adagrad => fit_optimizer(model, params, epochs, &mut Adagrad::new(model, lr)),
+        OptimizerKind::RMSProp => fit_optimizer(model, params, epochs, &mut RMSProp::new(model, lr)),
+        OptimizerKind::Adadelta => fit_optimizer(model, params, epochs, &mut Adadelta::new(model)),
+        OptimizerKind::Adam => fit_optimizer(model, params, epochs, &mut Adam::new(model, lr))
+    }
+}
+
+
+/// Train model with optimizer on full dataset or batches
+fn fit_optimizer<M: TrainOptimizer, O: Optimizer>(
+    model: &mut M,
+    params: &Hyperparameters,
+    epochs: usize,
+    optimizer: &mut O) {
+
+    match params.batch_size {
+        Some(batch_size) => model.train_batch_with_optimizer(epochs, batch_size, 1, false, None, optimizer),
+        None => model.train_with_optimizer(epochs, optimizer)
+    }
+}
+
+
+impl Search {
+
+    /// Create search ranking candidates by mean test loss across folds.
+    ///
+    /// # Arguments
+    ///
+    /// * `cv` - Cross validation used to score each candidate.
+    /// * `epochs` - Number of epochs each candidate is trained for
+    ///   (maximum epochs for successive halving).
+    ///
+    pub fn new(cv: CrossValidation, epochs: usize) -> Result<Self, String> {
+
+        if epochs == 0 {
+            return Err("Search requires at least 1 training epoch".to_string());
+        }
+
+        Ok(Search {
+            cv,
+            epochs,
+            metric: None,
+            direction: Direction::Minimize
+        })
+    }
+
+    /// Rank candidates by a metric computed on each test fold.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of metric.
+    /// * `metric` - Metric computed from true labels & predictions.
+    /// * `direction` - Whether lower or higher scores rank first.
+    ///
+    pub fn rank_by(&mut self, name: &str, metric: MetricFn, direction: Direction) {
+        self.cv.add_metric(name, metric);
+        self.metric = Some(name.to_string());
+        self.direction = direction;
+    }
+
+    /// Score every combination of values in the grid
+    pub fn grid<M, F>(
+        &self,
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        grid: &ParamGrid,
+        factory: F) -> Result<SearchResult<M>, String>
+    where
+        M: Model + Trainable + TrainOptimizer,
+        F: Fn(&Array2<f64>, &Array2<f64>, &Hyperparameters) -> Result<M, String> {
+
+        let ranked = self.evaluate(x, y, &grid.candidates()?, self.epochs, &factory)?;
+        self.refit(x, y, ranked, &factory)
+    }
+
+    /// Score a random sample of combinations in the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features.
+    /// * `y` - Target labels.
+    /// * `grid` - Values to explore for each hyperparameter.
+    /// * `n_iter` - Number of combinations to sample.
+    /// * `seed` - Seed for sampling combinations.
+    /// * `factory` - Creates an untrained model from data and hyperparameters.
+    ///
+    pub fn random<M, F>(
+        &self,
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        grid: &ParamGrid,
+        n_iter: usize,
+        seed: u64,
+        factory: F) -> Result<SearchResult<M>, String>
+    where
+        M: Model + Trainable + TrainOptimizer,
+        F: Fn(&Array2<f64>, &Array2<f64>, &Hyperparameters) -> Result<M, String> {
+
+        let ranked = self.evaluate(x, y, &grid.sample(n_iter, seed)?, self.epochs, &factory)?;
+        self.refit(x, y, ranked, &factory)
+    }
+
+    /// Successive halving, candidates are scored with a small epoch budget
+    /// and the best `1 / eta` of them are promoted to a budget `eta` times larger,
+    /// until a single candidate is trained with the full number of epochs.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input features.
+    /// * `y` - Target labels.
+    /// * `candidates` - Configurations to start with (from `ParamGrid::candidates` or `ParamGrid::sample`).
+    /// * `eta` - Reduction factor between rounds, must be at least 2.
+    /// * `factory` - Creates an untrained model from data and hyperparameters.
+    ///
+    pub fn successive_halving<M, F>(
+        &self,
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        candidates: &[Hyperparameters],
+        eta: usize,
+        factory: F) -> Result<SearchResult<M>, String>
+    where
+        M: Model + Trainable + TrainOptimizer,
+        F: Fn(&Array2<f64>, &Array2<f64>, &Hyperparameters) -> Result<M, String> {
+
+        if eta < 2 {
+            return Err("Successive halving requires eta of at least 2".to_string());
+        }
+
+        if candidates.is_empty() {
+            return Err("Successive halving requires at least one candidate".to_string());
+        }
+
+        let mut rounds = 0;
+        let mut remaining = candidates.len();
+        while remaining > 1 {
+            remaining = remaining.div_ceil(eta);
+            rounds += 1;
+        }
+
+        let mut survivors = candidates.to_vec();
+        let mut eliminated: Vec<Vec<Candidate>> = Vec::new();
+
+        for round in 0..=rounds {
+
+            let budget = (self.epochs / eta.pow((rounds - round) as u32)).max(1);
+            let mut ranked = self.evaluate(x, y, &survivors, budget, &factory)?;
+
+            if round == rounds {
+                ranked.extend(eliminated.into_iter().rev().flatten());
+                return self.refit(x, y, ranked, &factory);
+            }
+
+            let promoted = ranked.len().div_ceil(eta);
+            survivors = ranked[..promoted].iter().map(|c| c.params).collect();
+            eliminated.push(ranked.split_off(promoted));
+        }
+
+        unreachable!()
+    }
+
+    /// Cross validate candidates with epoch budget and rank by score
+    fn evaluate<M, F>(
+        &self,
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        candidates: &[Hyperparameters],
+        epochs: usize,
+        factory: &F) -> Result<Vec<Candidate>, String>
+    where
+        M: Model + Trainable + TrainOptimizer,
+        F: Fn(&Array2<f64>, &Array2<f64>, &Hyperparameters) -> Result<M, String> {
+
+        let mut scored = Vec::new();
+        for params in candidates {
+
+            let result = self.cv.run(
+                x, y,
+                |x, y| factory(x, y, params),
+                |model| fit(model, params, epochs)
+            )?;
+
+            let score = match &self.metric {
+                Some(name) => result.metric(name).unwrap().mean,
+                None => result.test_loss.mean
+            };
+
+            scored.push(Candidate {
+                params: *params,
+                epochs,
+                score,
+                result
+            });
+        }
+
+        // diverged candidates (NaN scores) rank last
+        let key = |candidate: &Candidate| {
+            let score = match self.direction {
+                Direction::Minimize => candidate.score,
+                Direction::Maximize => -candidate.score
+            };
+            if score.is_nan() { f64::INFINITY } else { score }
+        };
+
+        scored.sort_by(|a, b| key(a).total_cmp(&key(b)));
+        Ok(scored)
+    }
+
+    /// Train best candidate on all rows
+    fn refit<M, F>(
+        &self,
+        x: &Array2<f64>,
+        y: &Array2<f64>,
+        ranked: Vec<Candidate>,
+        factory: &F) -> Result<SearchResult<M>, String>
+    where
+        M: Model + Trainable + TrainOptimizer,
+        F: Fn(&Array2<f64>, &Array2<f64>, &Hyperparameters) -> Result<M, String> {
+
+        let best = ranked.first().ok_or("Search requires at least one candidate")?;
+        let mut model = factory(x, y, &best.params)?;
+        fit(&mut model, &best.params, best.epochs);
+
+        Ok(SearchResult { ranked, model })
+    }
+
+}
+
+
+#[cfg(test)]
+mod search_test {
+
+    use crate::selection::search::*;
+
+    #[test]
+    fn test_param_grid() {
+
+        let grid = ParamGrid {
+            learning_rate: vec![0.1, 0.01],
+            lambda: ParamGrid::log_space(0.001, 1.0, 4).unwrap(),
+            optimizer: vec![OptimizerKind::Default, OptimizerKind::Adam],
+            ..ParamGrid::default()
+        };
+
+        let candidates = grid.candidates().unwrap();
+        assert_eq!(candidates.len(), 16);
+        assert_eq!(candidates[0].learning_rate, 0.1);
+        assert_eq!(candidates[0].optimizer, OptimizerKind::Default);
+        assert_eq!(candidates[1].optimizer, OptimizerKind::Adam);
+        assert_eq!(candidates[0].alpha, 0.5);
+
+        let lambdas: Vec<f64> = grid.lambda.iter().map(|l| (l * 1000.0).round()).collect();
+        assert_eq!(lambdas, vec![1.0, 10.0, 100.0, 1000.0]);
+
+        let sample = grid.sample(5, 3).unwrap();
+        assert_eq!(sample.len(), 5);
+        assert_eq!(sample, grid.sample(5, 3).unwrap());
+        assert_eq!(grid.sample(100, 3).unwrap().len(), 16);
+
+        let empty = ParamGrid { alpha: vec![], ..ParamGrid::default() };
+        assert!(empty.candidates().is_err());
+
+        assert!(ParamGrid::log_space(0.001, 1.0, 0).unwrap().is_empty());
+        assert_eq!(ParamGrid::log_space(0.5, 1.0, 1).unwrap(), vec![0.5]);
+        assert!(ParamGrid::log_space(0.0, 1.0, 4).is_err());
+        assert!(ParamGrid::log_space(-1.0, 1.0, 4).is_err());
+        assert!(ParamGrid::log_space(1.0, 0.1, 4).is_err());
+        assert!(ParamGrid::log_space(f64::NAN, 1.0, 4).is_err());
+    }
+
+}
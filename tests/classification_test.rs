@@ -2,11 +2,17 @@
 mod classification_test {
 
     use std::fs;
-    use ndarray::{arr2, Array1};
+    use ndarray::{arr2, Array1, Array2};
 
+    use dendritic::autodiff::operations::loss::*;
     use dendritic::optimizer::model::*; 
     use dendritic::optimizer::train::*;
     use dendritic::optimizer::regression::logistic::*; 
+    use dendritic::optimizer::regression::svm::*;
+    use dendritic::optimizer::optimizers::*;
+    use dendritic::optimizer::regularizer::*;
+    use dendritic::preprocessing::processor::*;
+    use dendritic::metrics::classification::*;
  
     #[test]
     fn test_binary_classification() -> std::io::Result<()> {
@@ -116,6 +122,337 @@ mod classification_test {
 
         fs::remove_dir_all("data/multiclass_logistic")?; 
         Ok(())
-    }  
+    }
+
+
+    #[test]
+    fn test_regularized_logistic() -> std::io::Result<()> {
+
+        let x = arr2(&[
+            [1.0, 2.0],
+            [2.0, 1.0],
+            [1.5, 1.8],
+            [3.0, 3.2],
+            [2.8, 3.0],
+            [5.0, 5.5],
+            [6.0, 5.8],
+            [5.5, 6.0],
+            [6.2, 5.9],
+            [7.0, 6.5]
+        ]);
+
+        let y = arr2(&[[0.0], [0.0], [0.0], [0.0], [0.0], [1.0], [1.0], [1.0], [1.0], [1.0]]);
+
+        let mut model = Logistic::new(&x, &y, false, 0.01).unwrap();
+        let mut regularized = Logistic::new(&x, &y, false, 0.01).unwrap();
+        regularized.set_regularizer(Box::new(L2::new(0.5)));
+        assert!(model.regularizer().is_none());
+
+        model.train(2000);
+        regularized.train(2000);
+
+        // penalty shrinks weights & is included in loss
+        let weights = model.graph.node(1).output();
+        let shrunk = regularized.graph.node(1).output();
+        assert!(shrunk.mapv(|w| w * w).sum() < weights.mapv(|w| w * w).sum());
+        assert!(regularized.loss() > 0.0);
+
+        regularized.save("data/regularized_logistic")?;
+        let mut loaded = Logistic::load("data/regularized_logistic").unwrap();
+        assert_eq!(
+            loaded.regularizer().unwrap().penalty(&shrunk),
+            L2::new(0.5).penalty(&shrunk)
+        );
+        assert_eq!(loaded.predict(&x), regularized.predict(&x));
+
+        fs::remove_dir_all("data/regularized_logistic")?;
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_weighted_logistic() -> std::io::Result<()> {
+
+        // imbalanced classes with overlap
+        let x = arr2(&[
+            [1.0, 2.0],
+            [2.0, 1.0],
+            [1.5, 1.8],
+            [3.0, 3.2],
+            [2.8, 3.0],
+            [3.5, 3.6],
+            [4.0, 3.8],
+            [4.2, 4.4],
+            [3.8, 4.0],
+            [5.0, 5.2]
+        ]);
+
+        let y = arr2(&[[0.0], [0.0], [0.0], [0.0], [0.0], [0.0], [0.0], [0.0], [1.0], [1.0]]);
+
+        // balanced class weights n / (classes * count)
+        let weights = y.mapv(|label| if label == 1.0 { 2.5 } else { 0.625 });
+        let options = ModelOptions { sample_weight: Some(weights.clone()), ..ModelOptions::default() };
+
+        let mut model = Logistic::new(&x, &y, false, 0.01).unwrap();
+        let mut weighted = Logistic::with_options(&x, &y, false, 0.01, options).unwrap();
+        assert!(model.sample_weight().is_none());
+        assert_eq!(weighted.sample_weight().unwrap(), weights);
+
+        model.train(2000);
+        weighted.train(2000);
+
+        // minority class receives higher probabilities
+        let minority = |probs: Array2<f64>| probs[[8, 0]] + probs[[9, 0]];
+        assert!(minority(weighted.predicted()) > minority(model.predicted()));
+
+        weighted.save("data/weighted_logistic")?;
+        let mut loaded = Logistic::load("data/weighted_logistic").unwrap();
+        assert_eq!(loaded.sample_weight().unwrap(), weights);
+        assert_eq!(loaded.predict(&x), weighted.predict(&x));
+        fs::remove_dir_all("data/weighted_logistic")?;
+
+        // multi class without intercept
+        let one_hot = y.mapv(|label| 1.0 - label);
+        let y_multi = ndarray::concatenate![ndarray::Axis(1), one_hot, y];
+        let options = ModelOptions { fit_intercept: false, sample_weight: Some(weights) };
+        let mut multi = Logistic::with_options(&x, &y_multi, true, 0.01, options).unwrap();
+        multi.train(500);
+
+        assert_eq!(multi.graph.parameters(), vec![1]);
+        assert_eq!(multi.graph.node(3).output(), arr2(&[[0.0, 0.0]]));
+        assert!(multi.loss().is_finite());
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_weighted_and_focal_logistic() -> std::io::Result<()> {
+
+        // one positive for every nine negatives
+        let x = arr2(&[
+            [0.0], [0.4], [0.8], [1.2], [1.6], [2.0], [2.4], [2.8], [3.2],
+            [3.6], [0.2], [0.6], [1.0], [1.4], [1.8], [2.2], [2.6], [3.0],
+            [3.4], [3.8]
+        ]);
+
+        let mut y = Array2::zeros((20, 1));
+        y[[16, 0]] = 1.0;
+        y[[17, 0]] = 1.0;
+
+        let positives = |model: &Logistic| {
+            let probs = model.predicted();
+            [probs[[16, 0]], probs[[17, 0]]]
+        };
+
+        let mut model = Logistic::new(&x, &y, false, 0.1).unwrap();
+        model.train(1000);
+        assert!(positives(&model).iter().all(|p| *p < 0.5));
+
+        // balanced class weights recover the minority class
+        let mut weighted = Logistic::new(&x, &y, false, 0.1).unwrap();
+        weighted.set_loss(Box::new(ClassWeightedBinaryCrossEntropy::balanced(&y)));
+        weighted.train(1000);
+        assert!(positives(&weighted).iter().all(|p| *p > 0.5));
+
+        // focal loss raises probabilities of the minority class
+        let mut focal = Logistic::new(&x, &y, false, 0.1).unwrap();
+        focal.set_loss(Box::new(BinaryFocalLoss::new(2.0, 0.9)));
+        focal.train(1000);
+        assert!(positives(&focal)[1] > positives(&model)[1]);
+
+        // loss parameters are serialized with the graph
+        weighted.save("data/class_weighted_logistic")?;
+        let mut loaded = Logistic::load("data/class_weighted_logistic").unwrap();
+        assert_eq!(
+            loaded.graph.curr_node().operation().parameters(),
+            vec![20.0 / 36.0, 5.0]
+        );
+        let diff = loaded.predict(&x) - weighted.predict(&x);
+        assert!(diff.iter().all(|d| d.abs() < 1e-9));
+        fs::remove_dir_all("data/class_weighted_logistic")?;
+
+        focal.save("data/focal_logistic")?;
+        let loaded = Logistic::load("data/focal_logistic").unwrap();
+        assert_eq!(loaded.graph.curr_node().operation().parameters(), vec![2.0, 0.9]);
+        fs::remove_dir_all("data/focal_logistic")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_linear_svm() -> std::io::Result<()> {
+
+        let x = arr2(&[
+            [1.0, 2.0],
+            [2.0, 1.0],
+            [1.5, 1.8],
+            [3.0, 3.2],
+            [2.8, 3.0],
+            [5.0, 5.5],
+            [6.0, 5.8],
+            [5.5, 6.0],
+            [6.2, 5.9],
+            [7.0, 6.5]
+        ]);
+
+        let y = arr2(&[[0.0], [0.0], [0.0], [0.0], [0.0], [1.0], [1.0], [1.0], [1.0], [1.0]]);
+
+        let mut model = LinearSVM::new(&x, &y, 0.01, 0.001).unwrap();
+        assert!(!model.multi_class());
+        model.train(1000);
+        assert_eq!(model.predict(&x), y);
+
+        // margins of training points are respected
+        let scores = model.decision_function(&x);
+        assert!(scores.iter().zip(y.iter()).all(|(s, l)| (2.0 * l - 1.0) * s > 0.0));
+
+        model.save("data/linear_svm_test")?;
+        let mut loaded = LinearSVM::load("data/linear_svm_test").unwrap();
+        assert_eq!(format!("{:?}", loaded.graph().curr_node().operation()), "Hinge");
+        assert!(loaded.regularizer().is_some());
+        assert_eq!(loaded.lambda(), 0.001);
+        assert_eq!(loaded.predict(&x), y);
+        fs::remove_dir_all("data/linear_svm_test")?;
+
+        // lambda rebuilds the attached penalty and follows penalties attached later
+        model.set_lambda(0.5);
+        assert_eq!(model.lambda(), 0.5);
+        assert_eq!(model.regularizer().unwrap().penalty(&arr2(&[[2.0]])), 2.0);
+        model.set_regularizer(Box::new(L2::new(0.25)));
+        assert_eq!(model.lambda(), 0.25);
+
+        let mut squared = LinearSVM::squared_hinge(&x, &y, 0.01, 0.001).unwrap();
+        let mut optimizer = Adam::new(&squared, 0.01);
+        squared.train_with_optimizer(500, &mut optimizer);
+        assert_eq!(squared.predict(&x), y);
+
+        // one vs rest for multiple classes
+        let x_multi = arr2(&[
+            [0.0, 0.5],
+            [0.5, 0.0],
+            [0.4, 0.6],
+            [5.0, 0.2],
+            [5.5, 0.6],
+            [6.0, 0.1],
+            [0.3, 5.0],
+            [0.6, 5.5],
+            [0.1, 6.0]
+        ]);
+
+        let y_multi = arr2(&[
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0]
+        ]);
+
+        let mut multi = LinearSVM::new(&x_multi, &y_multi, 0.01, 0.0).unwrap();
+        assert!(multi.multi_class());
+        multi.train_batch(300, 3, 5, false, None);
+        assert_eq!(multi.graph().node(1).output().dim(), (2, 3));
+        assert_eq!(multi.predict(&x_multi), y_multi);
+
+        // NaN scores still pick one class instead of panicking
+        let missing = arr2(&[[f64::NAN, 0.5]]);
+        assert_eq!(multi.predict(&missing).sum(), 1.0);
+
+        assert!(LinearSVM::new(&x, &y.mapv(|l| 2.0 * l - 1.0), 0.01, 0.001).is_err());
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_predict_proba_and_class() -> std::io::Result<()> {
+
+        let x = arr2(&[
+            [1.0, 2.0],
+            [1.5, 1.8],
+            [2.0, 1.0],   // Class 0
+            [4.0, 4.5],
+            [4.5, 4.8],
+            [5.0, 5.2],   // Class 1
+            [7.0, 7.5],
+            [7.5, 8.0],
+            [8.0, 8.5],   // Class 2
+        ]);
+
+        let labels = arr2(&[
+            [1.0], [1.0], [1.0], [5.0], [5.0], [5.0], [9.0], [9.0], [9.0]
+        ]);
+
+        let mut encoder = OneHot::new();
+        let y = encoder.fit_transform(&labels.view()).unwrap();
+
+        let mut model = Logistic::new(&x, &y, true, 0.01).unwrap();
+        model.train(2000);
+        model.save("data/proba_logistic")?;
+
+        encoder.save("data/proba_logistic", "labels")?;
+
+        let mut loaded = Logistic::load("data/proba_logistic").unwrap();
+        let encoder = OneHot::load("data/proba_logistic", "labels").unwrap();
+        let proba = loaded.predict_proba(&x);
+        assert_eq!(proba.dim(), (9, 3));
+        for row in proba.rows() {
+            assert!((row.sum() - 1.0).abs() < 1e-9);
+        }
+
+        let classes = loaded.predict_class(&x, None).unwrap();
+        assert_eq!(classes, y);
+
+        let decoded = loaded.predict_labels(&x, None, &encoder).unwrap();
+        assert_eq!(decoded, labels);
+
+        // threshold no class can meet falls back to the most probable class
+        let strict = loaded.predict_class(&x, Some(&[1.0, 1.0, 1.0])).unwrap();
+        assert_eq!(strict, y);
+
+        // NaN probabilities still fall back to one class instead of panicking
+        let missing = arr2(&[[f64::NAN, 2.0]]);
+        let fallback = loaded.predict_class(&missing, Some(&[1.0, 1.0, 1.0])).unwrap();
+        assert_eq!(fallback.sum(), 1.0);
+
+        // a low threshold on class 2 takes over rows where it is the runner up
+        let favored = loaded.predict_class(&x, Some(&[1.0, 1.0, 0.0])).unwrap();
+        assert_eq!(favored.column(2).sum(), 9.0);
+
+        assert!(loaded.predict_class(&x, Some(&[0.5])).is_err());
+        assert!(loaded.predict_class(&x, Some(&[0.5, 1.5, 0.5])).is_err());
+        fs::remove_dir_all("data/proba_logistic")?;
+
+        // binary classification
+        let x_bin = x.slice(ndarray::s![0..6, ..]).to_owned();
+        let y_bin = arr2(&[[0.0], [0.0], [0.0], [1.0], [1.0], [1.0]]);
+        let mut binary = Logistic::new(&x_bin, &y_bin, false, 0.01).unwrap();
+        binary.train(2000);
+
+        let proba = binary.predict_proba(&x_bin);
+        assert_eq!(proba.dim(), (6, 2));
+        for row in proba.rows() {
+            assert!((row.sum() - 1.0).abs() < 1e-9);
+        }
+
+        assert_eq!(binary.predict_class(&x_bin, None).unwrap(), y_bin);
+        assert_eq!(
+            binary.predict_class(&x_bin, Some(&[0.0])).unwrap(),
+            Array2::<f64>::ones((6, 1))
+        );
+        assert!(binary.predict_class(&x_bin, Some(&[0.5, 0.5])).is_err());
+
+        // probabilities of both classes and 2 class one hot rows are scored by argmax
+        assert_eq!(accuracy(&y_bin, &binary.predict_proba(&x_bin)), 1.0);
+
+        let y_two = arr2(&[[1.0, 0.0], [1.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 1.0], [0.0, 1.0]]);
+        let mut two_class = Logistic::new(&x_bin, &y_two, true, 0.01).unwrap();
+        two_class.train(2000);
+        let classes = two_class.predict_class(&x_bin, None).unwrap();
+        assert_eq!(classes, y_two);
+        assert_eq!(accuracy(&y_two, &classes), 1.0);
+        assert_eq!(accuracy(&y_bin, &classes), 1.0);
+        Ok(())
+    }
 
 }
@@ -3,6 +3,7 @@ use ndarray::{arr2, Array2};
 use dendritic::optimizer::model::*; 
 use dendritic::optimizer::optimizers::*;
 use dendritic::optimizer::optimizers::Optimizer;
+use dendritic::optimizer::regularizer::*;
 use dendritic::optimizer::regression::sgd::*;
 
 fn load_sample_data() -> (Array2<f64>, Array2<f64>) {
@@ -203,3 +204,34 @@ fn test_adam() -> std::io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_adam_proximal() -> std::io::Result<()> {
+
+    let (x, y) = load_sample_data();
+    let lambda = 25.0;
+    let mut model = SGD::new(&x, &y, 0.0001).unwrap();
+    model.set_regularizer(Box::new(L1::new(lambda)));
+    let mut optimizer = Adam::new(&model, 0.1);
+
+    model.forward();
+    model.backward();
+    let w = model.graph.node(1).output();
+    let grad = model.graph.node(1).grad();
+    optimizer.step(&mut model);
+
+    // first adam step moves each weight by alpha * g / (|g| + epsilon),
+    // the soft threshold uses the same step instead of the learning rate of the model
+    let step = grad.mapv(|g| 0.1 / (g.abs() + 1e-6));
+    let moved = &w - &(&step * &grad);
+    let mut expected = moved.clone();
+    for ((idx, val), s) in moved.indexed_iter().zip(step.iter()) {
+        expected[idx] = val.signum() * (val.abs() - s * lambda).max(0.0);
+    }
+
+    let updated = model.graph.node(1).output();
+    assert!((&updated - &expected).mapv(f64::abs).sum() < 1e-9);
+    assert!((&updated - &soft_threshold(&moved, 0.0001 * lambda)).mapv(f64::abs).sum() > 1e-3);
+
+    Ok(())
+}
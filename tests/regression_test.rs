@@ -1,5 +1,5 @@
 use std::fs;
-use ndarray::arr2;
+use ndarray::{arr2, concatenate, s, Array2, Axis};
 
 use dendritic::optimizer::model::*; 
 use dendritic::optimizer::train::*; 
@@ -7,6 +7,18 @@ use dendritic::optimizer::regression::sgd::*;
 use dendritic::optimizer::regression::elastic::*;
 use dendritic::optimizer::regression::lasso::*;
 use dendritic::optimizer::regression::ridge::*;
+use dendritic::optimizer::regression::logistic::*;
+use dendritic::optimizer::regression::sparse::*;
+use dendritic::optimizer::regression::closed_form::*;
+use dendritic::optimizer::regression::glm::*;
+use dendritic::optimizer::optimizers::*;
+use dendritic::optimizer::regularizer::*;
+use dendritic::metrics::regression::*;
+use dendritic::selection::cross_validation::*;
+use dendritic::selection::search::*;
+use dendritic::preprocessing::prelude::*;
+use dendritic::data::prelude::*;
+
 
 #[test]
 fn test_linear() -> std::io::Result<()> {
@@ -60,7 +72,7 @@ fn test_ridge() -> std::io::Result<()> {
     assert_eq!(model.sgd.weight_dim, (3, 1));
     assert_eq!(model.sgd.bias_dim, (1, 1));
     assert_eq!(model.sgd.learning_rate, 0.001);
-    assert_eq!(model.lambda, 0.001);
+    assert_eq!(model.lambda(), 0.001);
     assert_eq!(model.sgd.input(), x); 
     assert_eq!(model.sgd.output(), y); 
 
@@ -95,7 +107,7 @@ fn test_lasso() -> std::io::Result<()> {
     assert_eq!(model.sgd.weight_dim, (3, 1));
     assert_eq!(model.sgd.bias_dim, (1, 1));
     assert_eq!(model.sgd.learning_rate, 0.001);
-    assert_eq!(model.lambda, 0.001);
+    assert_eq!(model.lambda(), 0.001);
     assert_eq!(model.sgd.input(), x); 
     assert_eq!(model.sgd.output(), y); 
 
@@ -129,8 +141,8 @@ fn test_elastic() -> std::io::Result<()> {
     assert_eq!(model.sgd.weight_dim, (3, 1));
     assert_eq!(model.sgd.bias_dim, (1, 1));
     assert_eq!(model.sgd.learning_rate, 0.001);
-    assert_eq!(model.lambda, 0.001);
-    assert_eq!(model.alpha, 0.5);
+    assert_eq!(model.lambda(), 0.001);
+    assert_eq!(model.alpha(), 0.5);
     assert_eq!(model.sgd.input(), x); 
     assert_eq!(model.sgd.output(), y); 
 
@@ -145,3 +157,808 @@ fn test_elastic() -> std::io::Result<()> {
     fs::remove_dir_all("data/elastic")?; 
     Ok(())
 }
+
+
+/// Rows of consecutive features, the target grows by 2 with every row
+fn linear_data(rows: usize) -> (Array2<f64>, Array2<f64>) {
+    let x = Array2::from_shape_fn((rows, 3), |(i, j)| (i + j + 1) as f64);
+    let y = Array2::from_shape_fn((rows, 1), |(i, _)| 10.0 + 2.0 * i as f64);
+    (x, y)
+}
+
+
+/// Features with two uninformative columns (second & fourth) for sparse and direct solvers
+fn sparse_features() -> Array2<f64> {
+    arr2(&[
+        [1.0, 0.5, 2.0, 1.0],
+        [2.0, -1.0, 0.0, 3.0],
+        [3.0, 0.0, 1.0, -2.0],
+        [4.0, 1.5, -1.0, 0.0],
+        [5.0, -0.5, 3.0, 1.0],
+        [6.0, 1.0, 2.0, -1.0]
+    ])
+}
+
+
+#[test]
+fn test_linear_batch() {
+
+    let (x, y) = linear_data(5);
+
+    let mut model = SGD::new(&x, &y, 0.001).unwrap();
+    model.forward();
+    let initial_loss = model.loss();
+
+    // batch size of 2 leaves a partial batch of 1 row
+    model.train_batch(5, 2, 100, false, None);
+
+    assert_eq!(model.input(), x);
+    assert_eq!(model.output(), y);
+    assert_eq!(model.predicted().nrows(), x.nrows());
+    assert!(model.loss() < initial_loss);
+
+    // only one full batch of 4 rows when dropping the remainder
+    let mut dropped = SGD::new(&x, &y, 0.001).unwrap();
+    dropped.train_batch(1, 4, 10, true, None);
+
+    assert_eq!(dropped.input(), x);
+    assert_eq!(dropped.output(), y);
+    assert!(dropped.loss() < initial_loss);
+
+    // batch larger than dataset with drop_last never updates parameters
+    let mut skipped = SGD::new(&x, &y, 0.001).unwrap();
+    skipped.train_batch(1, 10, 10, true, None);
+    assert_eq!(skipped.loss(), initial_loss);
+
+    // seeded batches train the same parameters on every run
+    let mut first = SGD::new(&x, &y, 0.001).unwrap();
+    let mut second = SGD::new(&x, &y, 0.001).unwrap();
+    first.train_batch(2, 2, 5, false, Some(9));
+    second.train_batch(2, 2, 5, false, Some(9));
+    assert_eq!(first.graph().node(1).output(), second.graph().node(1).output());
+}
+
+
+#[test]
+fn test_linear_validation() {
+
+    let (x, y) = linear_data(5);
+
+    let x_val = arr2(&[[6.0, 7.0, 8.0], [7.0, 8.0, 9.0]]);
+    let y_val = arr2(&[[20.0], [22.0]]);
+
+    let mut validation = Validation::new(&x_val, &y_val).unwrap();
+    validation.add_metric("max_error", |y_true, y_pred| {
+        (y_true - y_pred).mapv(f64::abs).fold(0.0, |a, &b| a.max(b))
+    });
+
+    let mut model = SGD::new(&x, &y, 0.001).unwrap();
+    let mut baseline = SGD::new(&x, &y, 0.001).unwrap();
+    model.train_validate(100, &mut validation).unwrap();
+    baseline.train(100);
+
+    // evaluating validation data leaves training data and parameters untouched
+    assert_eq!(model.input(), x);
+    assert_eq!(model.output(), y);
+    assert_eq!(model.graph.node(3).output(), baseline.graph.node(3).output());
+    assert_eq!(model.graph.node(1).output(), baseline.graph.node(1).output());
+
+    let history = validation.history();
+    assert_eq!(history.len(), 100);
+    assert_eq!(history[99].epoch, 100);
+    assert_eq!(history[99].metrics[0].0, "max_error");
+    assert!(history[99].validation_loss < history[0].validation_loss);
+    assert!(history[99].loss < history[0].loss);
+
+    // holding out a fraction trains on remaining rows, the full data is restored afterwards
+    let mut split = Validation::split(0.4, 7).unwrap();
+    let mut held_out = SGD::new(&x, &y, 0.001).unwrap();
+    held_out.train_batch_validate(2, 2, 5, false, Some(3), &mut split).unwrap();
+
+    assert_eq!(held_out.input(), x);
+    assert_eq!(held_out.output(), y);
+    assert_eq!(split.history().len(), 10);
+
+    // repeated runs split the same rows instead of shrinking the training set
+    held_out.train_validate(5, &mut split).unwrap();
+    assert_eq!(held_out.input(), x);
+    assert_eq!(split.history().len(), 5);
+
+    let mut single = SGD::new(&x.slice(s![0..1, ..]).to_owned(), &y.slice(s![0..1, ..]).to_owned(), 0.001).unwrap();
+    assert!(single.train_validate(5, &mut split).is_err());
+
+    assert!(Validation::split(1.0, 7).is_err());
+    assert!(Validation::new(&x_val, &y).is_err());
+}
+
+
+#[test]
+fn test_ridge_cross_validation() {
+
+    let (x, y) = linear_data(6);
+
+    let mut cv = CrossValidation::new(3, Some(42), false).unwrap();
+    cv.add_metric("mae", mean_absolute_error);
+
+    let score = |lambda: f64| {
+        cv.run(
+            &x, &y,
+            |x, y| Ridge::new(x, y, 0.001, lambda),
+            |model| model.train(500)
+        ).unwrap()
+    };
+
+    let result = score(0.001);
+    assert_eq!(result.folds.len(), 3);
+    assert_eq!(result.folds[0].metrics[0].0, "mae");
+    assert!(result.metric("mae").is_some());
+    assert!(result.metric("r2").is_none());
+    assert!(result.test_loss.min <= result.test_loss.mean);
+    assert!(result.test_loss.mean <= result.test_loss.max);
+
+    // heavy penalty shrinks weights and scores worse on held out folds
+    let penalized = score(10.0);
+    assert!(result.metric("mae").unwrap().mean < penalized.metric("mae").unwrap().mean);
+}
+
+
+#[test]
+fn test_hyperparameter_search() {
+
+    let (x, y) = linear_data(6);
+
+    let grid = ParamGrid {
+        learning_rate: vec![0.001],
+        lambda: vec![10.0, 0.001],
+        batch_size: vec![None, Some(2)],
+        optimizer: vec![OptimizerKind::Default, OptimizerKind::Adam],
+        ..ParamGrid::default()
+    };
+
+    let cv = CrossValidation::new(3, Some(42), false).unwrap();
+    let mut search = Search::new(cv, 100).unwrap();
+    search.rank_by("mae", mean_absolute_error, Direction::Minimize);
+
+    let factory = |x: &Array2<f64>, y: &Array2<f64>, params: &Hyperparameters| {
+        Ridge::new(x, y, params.learning_rate, params.lambda)
+    };
+
+    let result = search.grid(&x, &y, &grid, factory).unwrap();
+    assert_eq!(result.ranked.len(), 8);
+    assert_eq!(result.ranked[0].epochs, 100);
+    assert!(result.ranked[0].score <= result.ranked[7].score);
+    assert_eq!(result.ranked[0].score, result.ranked[0].result.metric("mae").unwrap().mean);
+
+    // best configuration is refit on every row
+    assert_eq!(result.model.input(), x);
+
+    let random = search.random(&x, &y, &grid, 3, 11, factory).unwrap();
+    assert_eq!(random.ranked.len(), 3);
+
+    // 8 candidates halved each round: 8 x 12 epochs, 4 x 25, 2 x 50, 1 x 100
+    let halving = search.successive_halving(
+        &x, &y, &grid.candidates().unwrap(), 2, factory
+    ).unwrap();
+
+    let epochs: Vec<usize> = halving.ranked.iter().map(|c| c.epochs).collect();
+    assert_eq!(epochs, vec![100, 50, 25, 25, 12, 12, 12, 12]);
+    assert!(search.successive_halving(&x, &y, &[], 2, factory).is_err());
+    assert!(Search::new(CrossValidation::new(2, None, false).unwrap(), 0).is_err());
+}
+
+
+#[test]
+fn test_sparse_regression() {
+
+    // target depends on first & third feature only
+    let x = sparse_features();
+
+    let y = arr2(&[[0.0], [7.0], [8.0], [15.0], [10.0], [15.0]]);
+
+    // penalty is applied when optimizers update parameters
+    let mut model = Lasso::new(&x, &y, 0.005, 2.0).unwrap();
+    let mut optimizer = DefaultOptimizer { alpha: 0.005 };
+    model.train_with_optimizer(5000, &mut optimizer);
+
+    let weights = model.graph().node(1).output();
+    assert_eq!(weights[[1, 0]], 0.0);
+    assert_eq!(weights[[3, 0]], 0.0);
+
+    let mut lasso = Lasso::new(&x, &y, 0.001, 2.0).unwrap();
+    let iterations = lasso.fit_sparse(SparseSolver::CoordinateDescent, 1000, 1e-8).unwrap();
+    assert!(iterations < 1000);
+
+    let weights = lasso.graph().node(1).output();
+    assert_eq!(weights[[1, 0]], 0.0);
+    assert_eq!(weights[[3, 0]], 0.0);
+    assert!((lasso.predicted() - &y).mapv(f64::abs).sum() < 5.0);
+
+    // default updates take proximal steps with the learning rate
+    let mut proximal = Lasso::new(&x, &y, 0.005, 2.0).unwrap();
+    proximal.train(5000);
+    assert_eq!(proximal.graph().node(1).output(), model.graph().node(1).output());
+
+    // features enter the model as lambda decreases
+    let mut elastic = Elastic::new(&x, &y, 0.001, 1.0, 0.9).unwrap();
+    let lambdas = lambda_path(&x, &y, 0.9, 6, 0.001);
+    let path = elastic.path(&lambdas, SparseSolver::CoordinateDescent, 10000, 1e-8).unwrap();
+
+    assert_eq!(path.len(), 6);
+    assert_eq!(path[0].nonzero, 0);
+    assert!(path[5].nonzero >= 2);
+    assert!(path.windows(2).all(|pair| pair[0].nonzero <= pair[1].nonzero));
+    assert_eq!(elastic.lambda(), lambdas[5]);
+
+    // lambda & alpha follow the attached penalty, which the solvers minimize
+    let mut attached = Lasso::new(&x, &y, 0.001, 2.0).unwrap();
+    attached.set_regularizer(Box::new(ElasticNet::new(1.0, 0.5)));
+    assert_eq!(attached.lambda(), 1.0);
+    attached.fit_sparse(SparseSolver::CoordinateDescent, 10000, 1e-10).unwrap();
+
+    let mut elastic = Elastic::new(&x, &y, 0.001, 1.0, 0.5).unwrap();
+    elastic.fit_sparse(SparseSolver::CoordinateDescent, 10000, 1e-10).unwrap();
+    let diff = attached.graph().node(1).output() - elastic.graph().node(1).output();
+    assert!(diff.mapv(f64::abs).sum() < 1e-9);
+
+    // setters rebuild the attached penalty
+    elastic.set_alpha(1.0);
+    assert_eq!(elastic.regularizer().unwrap().penalty(&arr2(&[[2.0]])), 2.0);
+
+    elastic.set_regularizer(Box::new(L1::new(3.0)));
+    assert_eq!((elastic.lambda(), elastic.alpha()), (3.0, 1.0));
+    elastic.set_regularizer(Box::new(MaxNorm::new(1.0)));
+    assert!(elastic.fit_sparse(SparseSolver::Fista, 100, 1e-8).is_err());
+}
+
+
+#[test]
+fn test_regularizer_with_optimizer() {
+
+    let (x, y) = linear_data(5);
+
+    // penalty of ridge is applied by optimizer steps
+    let mut model = SGD::new(&x, &y, 0.01).unwrap();
+    let mut ridge = Ridge::new(&x, &y, 0.01, 5.0).unwrap();
+    let mut optimizer = Adam::new(&model, 0.01);
+    let mut ridge_optimizer = Adam::new(&ridge, 0.01);
+    model.train_with_optimizer(500, &mut optimizer);
+    ridge.train_with_optimizer(500, &mut ridge_optimizer);
+
+    let weights = model.graph().node(1).output();
+    let shrunk = ridge.graph().node(1).output();
+    assert!(shrunk.mapv(|w| w * w).sum() < weights.mapv(|w| w * w).sum());
+
+    // constraints are projected after every update
+    let mut constrained = SGD::new(&x, &y, 0.001).unwrap();
+    constrained.set_regularizer(Box::new(MaxNorm::new(1.0)));
+    constrained.train(200);
+    let norm = constrained.graph().node(1).output().mapv(|w| w * w).sum().sqrt();
+    assert!(norm <= 1.0 + 1e-12);
+
+    // groups of weights are removed together
+    let mut grouped = SGD::new(&x, &y, 0.001).unwrap();
+    grouped.set_regularizer(Box::new(GroupLasso::new(100.0, vec![vec![0, 1], vec![2]])));
+    grouped.train(200);
+    let weights = grouped.graph().node(1).output();
+    assert_eq!(weights[[0, 0]], 0.0);
+    assert_eq!(weights[[1, 0]], 0.0);
+}
+
+
+#[test]
+fn test_direct_solvers() {
+
+    let x = sparse_features().slice(s![.., ..3]).to_owned();
+
+    let y = arr2(&[[0.5], [7.0], [8.0], [15.5], [10.0], [15.0]]);
+
+    // exact solution matches a long run of gradient descent
+    let mut iterative = SGD::new(&x, &y, 0.01).unwrap();
+    iterative.train(20000);
+
+    let mut exact = SGD::new(&x, &y, 0.01).unwrap();
+    exact.fit_direct(DirectSolver::Qr).unwrap();
+
+    let diff = exact.graph().node(1).output() - iterative.graph().node(1).output();
+    assert!(diff.mapv(f64::abs).sum() < 1e-3);
+    iterative.forward();
+    assert!(exact.loss() <= iterative.loss() + 1e-12);
+    assert_eq!(exact.predicted(), exact.predict(&x));
+
+    // attached L2 penalties are solved as a ridge term, other penalties can't be solved directly
+    let mut penalized = SGD::new(&x, &y, 0.01).unwrap();
+    penalized.set_regularizer(Box::new(L1::new(100.0)));
+    assert!(penalized.fit_direct(DirectSolver::Qr).is_err());
+    penalized.set_regularizer(Box::new(L2::new(0.5)));
+    penalized.fit_direct(DirectSolver::Cholesky).unwrap();
+
+    // ridge solution is a fixed point of iterative training (warm start)
+    let mut ridge = Ridge::new(&x, &y, 0.01, 0.5).unwrap();
+    ridge.fit_direct(DirectSolver::Cholesky).unwrap();
+    let weights = ridge.graph().node(1).output();
+    let bias = ridge.graph().node(3).output();
+    assert_eq!(penalized.graph().node(1).output(), weights);
+
+    ridge.train(100);
+    let change = &ridge.graph().node(1).output() - &weights;
+    assert!(change.mapv(f64::abs).sum() < 1e-9);
+    assert!((&ridge.graph().node(3).output() - &bias).sum().abs() < 1e-9);
+
+    // ridge term shrinks weights towards zero
+    let l2 = |w: Array2<f64>| w.mapv(|v| v * v).sum();
+    assert!(l2(weights.clone()) < l2(exact.graph().node(1).output()));
+
+    // changing lambda rebuilds the penalty used by training
+    ridge.set_lambda(2.0);
+    ridge.fit_direct(DirectSolver::Cholesky).unwrap();
+    let stronger = ridge.graph().node(1).output();
+    ridge.train(100);
+    assert!((&ridge.graph().node(1).output() - &stronger).mapv(f64::abs).sum() < 1e-9);
+    assert!(l2(stronger.clone()) < l2(weights));
+
+    // collinear features need a ridge term
+    let collinear = arr2(&[[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]]);
+    let target = arr2(&[[1.0], [2.0], [3.0]]);
+    let mut linear = SGD::new(&collinear, &target, 0.01).unwrap();
+    assert!(linear.fit_direct(DirectSolver::NormalEquation).is_err());
+    let mut ridge = Ridge::new(&collinear, &target, 0.01, 0.1).unwrap();
+    assert!(ridge.fit_direct(DirectSolver::NormalEquation).is_ok());
+
+    // ridge term is read from the attached penalty
+    let mut attached = Ridge::new(&x, &y, 0.01, 0.5).unwrap();
+    attached.set_regularizer(Box::new(L2::new(2.0)));
+    assert_eq!(attached.lambda(), 2.0);
+    attached.fit_direct(DirectSolver::Cholesky).unwrap();
+    assert_eq!(attached.graph().node(1).output(), stronger);
+
+    attached.set_regularizer(Box::new(L1::new(2.0)));
+    assert_eq!(attached.lambda(), 0.0);
+    assert!(attached.fit_direct(DirectSolver::Cholesky).is_err());
+}
+
+
+#[test]
+fn test_multi_output_regression() -> std::io::Result<()> {
+
+    let x = sparse_features().slice(s![.., ..3]).to_owned();
+
+    // first target depends on first & third feature, second target on second feature
+    let y = arr2(&[
+        [0.0, -3.75],
+        [7.0, -4.5],
+        [8.0, -4.0],
+        [15.0, -3.25],
+        [10.0, -4.25],
+        [15.0, -3.5]
+    ]);
+
+    let mut model = SGD::new(&x, &y, 0.01).unwrap();
+    assert_eq!(model.weight_dim, (3, 2));
+    assert_eq!(model.bias_dim, (1, 2));
+    assert_eq!(model.n_targets(), 2);
+
+    model.train(5000);
+    model.forward();
+
+    let target_loss = model.target_loss();
+    assert_eq!(target_loss.len(), 2);
+    assert!((target_loss.mean().unwrap() - model.loss()).abs() < 1e-12);
+    assert!(target_loss.iter().all(|loss| *loss < 0.01));
+
+    model.save("data/multi_output")?;
+    let mut loaded = SGD::load("data/multi_output").unwrap();
+    let output = loaded.predict(&x);
+    assert_eq!(output.dim(), (6, 2));
+    assert!((output - &y).mapv(f64::abs).sum() < 0.5);
+    fs::remove_dir_all("data/multi_output")?;
+
+    // wrapped models share multi target support
+    let mut ridge = Ridge::new(&x, &y, 0.01, 0.01).unwrap();
+    let mut optimizer = Adam::new(&ridge, 0.05);
+    ridge.train_with_optimizer(2000, &mut optimizer);
+    ridge.forward();
+    assert_eq!(ridge.target_loss().len(), 2);
+    assert_eq!(ridge.predict(&x).dim(), (6, 2));
+
+    let mut lasso = Lasso::new(&x, &y, 0.001, 1.0).unwrap();
+    lasso.fit_sparse(SparseSolver::CoordinateDescent, 1000, 1e-8).unwrap();
+    let weights = lasso.graph().node(1).output();
+    assert_eq!(weights[[1, 0]], 0.0);
+    assert_eq!(weights[[0, 1]], 0.0);
+    assert_eq!(weights[[2, 1]], 0.0);
+
+    let mut elastic = Elastic::new(&x, &y, 0.001, 0.1, 0.5).unwrap();
+    elastic.train(100);
+    assert_eq!(elastic.target_loss().len(), 2);
+
+    // direct solution recovers each target exactly
+    let mut exact = SGD::new(&x, &y, 0.01).unwrap();
+    exact.fit_direct(DirectSolver::Qr).unwrap();
+    assert!(exact.target_loss().iter().all(|loss| *loss < 1e-20));
+
+    assert!(SGD::new(&x, &arr2(&[[1.0, 2.0]]), 0.01).is_err());
+    Ok(())
+}
+
+
+#[test]
+fn test_intercept_and_sample_weights() -> std::io::Result<()> {
+
+    let x = arr2(&[
+        [1.0, 0.5],
+        [2.0, -1.0],
+        [3.0, 0.0],
+        [4.0, 1.5],
+        [5.0, -0.5],
+        [6.0, 1.0]
+    ]);
+
+    let y = arr2(&[[3.5], [3.0], [6.0], [9.5], [9.0], [13.0]]);
+
+    // bias stays at zero and is not a parameter without an intercept
+    let options = ModelOptions { fit_intercept: false, ..ModelOptions::default() };
+    let mut model = SGD::with_options(&x, &y, 0.005, options).unwrap();
+    assert_eq!(model.graph().parameters(), vec![1]);
+
+    let mut optimizer = Adam::new(&model, 0.05);
+    model.train_with_optimizer(500, &mut optimizer);
+    assert_eq!(model.graph().node(3).output(), arr2(&[[0.0]]));
+
+    model.save("data/no_intercept")?;
+    let loaded = SGD::load("data/no_intercept").unwrap();
+    assert!(!loaded.fit_intercept);
+    assert_eq!(loaded.graph().parameters(), vec![1]);
+    fs::remove_dir_all("data/no_intercept")?;
+
+    let mut direct = SGD::with_options(&x, &y, 0.005, ModelOptions {
+        fit_intercept: false,
+        sample_weight: None
+    }).unwrap();
+    direct.fit_direct(DirectSolver::Cholesky).unwrap();
+    assert_eq!(direct.graph().node(3).output(), arr2(&[[0.0]]));
+    let diff = direct.graph().node(1).output() - model.graph().node(1).output();
+    assert!(diff.mapv(f64::abs).sum() < 0.05);
+
+    // weight of 2 matches a duplicated row, weight of 0 matches a removed row
+    let weights = arr2(&[[2.0], [1.0], [1.0], [1.0], [1.0], [0.0]]);
+    let options = ModelOptions { sample_weight: Some(weights.clone()), ..ModelOptions::default() };
+    let mut weighted = SGD::with_options(&x, &y, 0.01, options.clone()).unwrap();
+    weighted.fit_direct(DirectSolver::Qr).unwrap();
+
+    let rows = [0, 0, 1, 2, 3, 4];
+    let x_dup = x.select(ndarray::Axis(0), &rows);
+    let y_dup = y.select(ndarray::Axis(0), &rows);
+    let mut duplicated = SGD::new(&x_dup, &y_dup, 0.01).unwrap();
+    duplicated.fit_direct(DirectSolver::Qr).unwrap();
+
+    let diff = weighted.graph().node(1).output() - duplicated.graph().node(1).output();
+    assert!(diff.mapv(f64::abs).sum() < 1e-9);
+    let diff = weighted.graph().node(3).output() - duplicated.graph().node(3).output();
+    assert!(diff.mapv(f64::abs).sum() < 1e-9);
+
+    // gradient descent with weighted loss reaches the weighted solution
+    let mut iterative = SGD::with_options(&x, &y, 0.01, options.clone()).unwrap();
+    iterative.train(20000);
+    let diff = iterative.graph().node(1).output() - weighted.graph().node(1).output();
+    assert!(diff.mapv(f64::abs).sum() < 1e-3);
+    assert_eq!(iterative.sample_weight().unwrap(), weights);
+
+    // batches carry their sample weights, full weights are restored after training
+    let mut batched = Ridge::with_options(&x, &y, 0.01, 0.0, options.clone()).unwrap();
+    batched.train_batch(20, 2, 50, false, None);
+    assert_eq!(batched.sample_weight().unwrap(), weights);
+
+    // sparse solvers agree on the weighted objective
+    let mut lasso = Lasso::with_options(&x, &y, 0.01, 0.5, options.clone()).unwrap();
+    lasso.fit_sparse(SparseSolver::CoordinateDescent, 10000, 1e-12).unwrap();
+    let mut fista = Lasso::with_options(&x, &y, 0.01, 0.5, options).unwrap();
+    fista.fit_sparse(SparseSolver::Fista, 100000, 1e-12).unwrap();
+    let diff = lasso.graph().node(1).output() - fista.graph().node(1).output();
+    assert!(diff.mapv(f64::abs).sum() < 1e-6);
+
+    // invalid sample weights
+    let short = ModelOptions { sample_weight: Some(arr2(&[[1.0], [1.0]])), ..ModelOptions::default() };
+    assert!(SGD::with_options(&x, &y, 0.01, short).is_err());
+    let negative = ModelOptions { sample_weight: Some(-weights), ..ModelOptions::default() };
+    assert!(Elastic::with_options(&x, &y, 0.01, 0.1, 0.5, negative).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_robust_losses() -> std::io::Result<()> {
+
+    // y = 2x + 1 with a single large outlier
+    let x = arr2(&[
+        [0.0], [0.5], [1.0], [1.5], [2.0], [2.5], [3.0], [3.5], [4.0], [4.5]
+    ]);
+    let mut y = x.mapv(|v| 2.0 * v + 1.0);
+    y[[9, 0]] = 60.0;
+
+    let mut mse = SGD::new(&x, &y, 0.01).unwrap();
+    let mut huber = SGD::huber(&x, &y, 0.01, 1.0, ModelOptions::default()).unwrap();
+    let mut log_cosh = SGD::log_cosh(&x, &y, 0.01, ModelOptions::default()).unwrap();
+    mse.train(5000);
+    huber.train(5000);
+    log_cosh.train(5000);
+
+    // robust losses are pulled less by the outlier
+    let slope = |model: &SGD| model.graph.node(1).output()[[0, 0]];
+    assert!((slope(&huber) - 2.0).abs() < (slope(&mse) - 2.0).abs());
+    assert!((slope(&log_cosh) - 2.0).abs() < (slope(&mse) - 2.0).abs());
+    assert!(huber.fit_direct(DirectSolver::Qr).is_err());
+
+    // robust losses use sample weights & intercept options
+    let mut weights = Array2::ones((10, 1));
+    weights[[9, 0]] = 0.0;
+    let weighted = ModelOptions { sample_weight: Some(weights), ..ModelOptions::default() };
+    let mut ignored = SGD::huber(&x, &y, 0.01, 1.0, weighted).unwrap();
+    ignored.train(5000);
+    assert!((slope(&ignored) - 2.0).abs() < (slope(&huber) - 2.0).abs());
+
+    let no_intercept = ModelOptions { fit_intercept: false, ..ModelOptions::default() };
+    let mut origin = SGD::log_cosh(&x, &y, 0.01, no_intercept).unwrap();
+    origin.train(100);
+    assert_eq!(origin.graph.node(3).output(), Array2::<f64>::zeros((1, 1)));
+
+    // quantiles bound the targets as a prediction interval
+    let noisy = arr2(&[
+        [1.2], [1.7], [3.4], [3.6], [5.3], [5.8], [7.1], [7.9], [8.6], [10.4]
+    ]);
+    let mut lower = SGD::quantile(&x, &noisy, 0.05, 0.1, ModelOptions::default()).unwrap();
+    let mut upper = SGD::quantile(&x, &noisy, 0.05, 0.9, ModelOptions::default()).unwrap();
+    lower.train(5000);
+    upper.train(5000);
+
+    let below = (lower.predicted() - &noisy).iter().filter(|r| **r <= 1e-6).count();
+    let above = (upper.predicted() - &noisy).iter().filter(|r| **r >= -1e-6).count();
+    assert!(below >= 8);
+    assert!(above >= 8);
+    assert!((upper.predicted() - lower.predicted()).iter().all(|w| *w > 0.0));
+
+    assert!(SGD::quantile(&x, &noisy, 0.05, 1.0, ModelOptions::default()).is_err());
+    assert!(SGD::huber(&x, &noisy, 0.05, 0.0, ModelOptions::default()).is_err());
+
+    // loss parameters are kept when saved
+    upper.save("data/quantile_regression")?;
+    let mut loaded = SGD::load("data/quantile_regression").unwrap();
+    assert_eq!(format!("{:?}", loaded.graph.curr_node().operation()), "Quantile { tau: 0.9 }");
+    assert_eq!(loaded.predict(&x), upper.predict(&x));
+    fs::remove_dir_all("data/quantile_regression")?;
+    Ok(())
+}
+
+#[test]
+fn test_generalized_linear_models() -> std::io::Result<()> {
+
+    let x = arr2(&[
+        [0.0], [0.2], [0.4], [0.6], [0.8], [1.0], [1.2], [1.4], [1.6], [1.8]
+    ]);
+
+    // poisson means with log link recover coefficients
+    let counts = x.mapv(|v: f64| (0.5 + 0.8 * v).exp());
+    let mut poisson = GLM::poisson(&x, &counts, 0.01).unwrap();
+    poisson.forward();
+    let initial = poisson.loss();
+    poisson.train(3000);
+
+    assert!(poisson.loss() < initial);
+    assert!((poisson.graph.node(1).output()[[0, 0]] - 0.8).abs() < 0.05);
+    assert!((poisson.graph.node(3).output()[[0, 0]] - 0.5).abs() < 0.05);
+    assert!(poisson.predicted().iter().all(|mu| *mu > 0.0));
+
+    // gamma with identity & inverse links
+    let linear = x.mapv(|v| 2.0 + 3.0 * v);
+    let mut identity = GLM::new(&x, &linear, Family::Gamma, Link::Identity, 0.1).unwrap();
+    identity.train(3000);
+    assert!((identity.graph.node(1).output()[[0, 0]] - 3.0).abs() < 0.1);
+
+    let inverse_targets = x.mapv(|v| 1.0 / (0.5 + 0.25 * v));
+    let mut inverse = GLM::new(&x, &inverse_targets, Family::Gamma, Link::Inverse, 0.02).unwrap();
+    inverse.train(5000);
+    assert!((inverse.linear_predictor() - x.mapv(|v| 0.5 + 0.25 * v)).iter().all(|d| d.abs() < 0.05));
+
+    // tweedie for claims with exact zeros
+    let claims = arr2(&[
+        [0.0], [0.0], [1.2], [0.0], [2.5], [0.0], [3.1], [4.0], [0.0], [6.3]
+    ]);
+    let mut tweedie = GLM::tweedie(&x, &claims, 1.5, 0.01).unwrap();
+    tweedie.forward();
+    let initial = tweedie.loss();
+    tweedie.train(2000);
+    assert!(tweedie.loss() < initial);
+    assert!(tweedie.graph.node(1).output()[[0, 0]] > 0.0);
+
+    tweedie.save("data/tweedie_glm")?;
+    let mut loaded = GLM::load("data/tweedie_glm").unwrap();
+    assert_eq!(loaded.family, Family::Tweedie(1.5));
+    assert_eq!(loaded.link, Link::Log);
+    assert_eq!(format!("{:?}", loaded.graph.curr_node().operation()), "TweedieDeviance { power: 1.5 }");
+    assert_eq!(loaded.predict(&x), tweedie.predict(&x));
+    fs::remove_dir_all("data/tweedie_glm")?;
+
+    // targets outside of the support of the family
+    assert!(GLM::poisson(&x, &(&counts - 5.0), 0.01).is_err());
+    assert!(GLM::gamma(&x, &claims, 0.01).is_err());
+    assert!(GLM::tweedie(&x, &claims, 0.5, 0.01).is_err());
+
+    let options = ModelOptions { fit_intercept: false, ..ModelOptions::default() };
+    assert!(GLM::with_options(&x, &linear, Family::Gamma, Link::Inverse, 0.1, options).is_err());
+    Ok(())
+}
+
+
+#[test]
+fn test_missing_values() -> std::io::Result<()> {
+
+    let nan = f64::NAN;
+    let x = arr2(&[
+        [1.0, 2.0],
+        [2.0, nan],
+        [3.0, 4.0],
+        [nan, 5.0],
+        [5.0, 6.0]
+    ]);
+
+    let y = arr2(&[[5.0], [7.0], [11.0], [13.0], [17.0]]);
+
+    // missing values are reported instead of turning the loss into NaN
+    assert!(SGD::new(&x, &y, 0.01).is_err());
+    assert!(Logistic::new(&x, &y.mapv(|v| (v > 10.0) as u8 as f64), false, 0.01).is_err());
+    assert!(GLM::poisson(&x, &y, 0.01).is_err());
+
+    let mut imputer = SimpleImputer::new(ImputeStrategy::Mean, true);
+    let imputed = imputer.fit_transform(&x.view()).unwrap();
+    assert_eq!(imputed.dim(), (5, 4));
+
+    let mut model = SGD::new(&imputed, &y, 0.01).unwrap();
+    model.train(500);
+    assert!(model.loss().is_finite());
+
+    model.save("data/imputed_linear")?;
+    imputer.save("data/imputed_linear", "imputer")?;
+
+    let mut loaded = SGD::load("data/imputed_linear").unwrap();
+    let loaded_imputer = SimpleImputer::load("data/imputed_linear", "imputer").unwrap();
+
+    let test = arr2(&[[nan, 3.0], [4.0, nan]]);
+    let expected = model.predict(&imputer.transform(&test.view()).unwrap());
+    let output = loaded.predict(&loaded_imputer.transform(&test.view()).unwrap());
+    assert!(output.iter().all(|v| v.is_finite()));
+    assert!((output - expected).mapv(f64::abs).sum() < 1e-9);
+
+    fs::remove_dir_all("data/imputed_linear")?;
+    Ok(())
+}
+
+
+#[test]
+fn test_nonlinear_features() {
+
+    let x = Array2::from_shape_fn((21, 1), |(i, _)| -1.0 + i as f64 / 10.0);
+    let y = x.mapv(|v| 2.0 * v * v - v + 0.5);
+
+    let mut linear = SGD::new(&x, &y, 0.01).unwrap();
+    linear.train(3000);
+
+    // the bias node of the model already covers the constant term
+    let mut poly = PolynomialFeatures::new(2, false, false).unwrap();
+    let x_poly = poly.fit_transform(&x.view()).unwrap();
+    let mut quadratic = SGD::new(&x_poly, &y, 0.01).unwrap();
+    quadratic.train(3000);
+    assert!(quadratic.loss() < 1e-4);
+    assert!(quadratic.loss() < linear.loss());
+
+    let test = poly.transform(&arr2(&[[0.25], [-0.75]]).view()).unwrap();
+    let output = quadratic.predict(&test);
+    let expected = arr2(&[[0.375], [2.375]]);
+    assert!((output - expected).mapv(f64::abs).sum() < 0.05);
+
+    let mut splines = SplineTransformer::new(5, 3).unwrap();
+    let x_splines = splines.fit_transform(&x.view()).unwrap();
+    let mut spline_model = SGD::new(&x_splines, &y, 0.01).unwrap();
+    spline_model.train(3000);
+    assert!(spline_model.loss() < linear.loss());
+
+    let mut bins = KBinsDiscretizer::new(5, BinStrategy::Quantile, BinEncoding::OneHot).unwrap();
+    let x_bins = bins.fit_transform(&x.view()).unwrap();
+    let mut binned = SGD::new(&x_bins, &y, 0.01).unwrap();
+    binned.train(3000);
+    assert!(binned.loss() < linear.loss());
+}
+
+
+#[test]
+fn test_csv_dataset() -> std::io::Result<()> {
+
+    fs::create_dir_all("data/csv_housing")?;
+    fs::write(
+        "data/csv_housing/housing.csv",
+        "rooms,age,city,price\n\
+         1,10,austin,5\n\
+         2,NA,denver,9\n\
+         3,30,austin,11\n\
+         4,40,\"denver\",15\n\
+         5,,austin,17\n\
+         6,60,denver,21\n"
+    )?;
+
+    let table = Table::read_csv("data/csv_housing/housing.csv").unwrap();
+    assert_eq!(table.numeric_columns(), vec!["rooms", "age", "price"]);
+
+    let (x, y) = table.features_targets(&["rooms", "age"], &["price"]).unwrap();
+    assert!(x[[1, 1]].is_nan() && x[[4, 1]].is_nan());
+
+    let mut imputer = SimpleImputer::new(ImputeStrategy::Median, false);
+    let numeric = imputer.fit_transform(&x.view()).unwrap();
+
+    let mut encoder = OrdinalEncoder::new(None);
+    let city = table.encode(&["city"], &mut encoder).unwrap();
+    assert_eq!(city.column(0).to_vec(), vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+
+    let features = concatenate(Axis(1), &[numeric.view(), city.view()]).unwrap();
+    let mut model = SGD::new(&features, &y, 0.0001).unwrap();
+    model.train(1000);
+    assert!(model.loss().is_finite());
+
+    fs::remove_dir_all("data/csv_housing")?;
+    Ok(())
+}
+
+
+#[test]
+fn test_dataset_loader() -> std::io::Result<()> {
+
+    fs::create_dir_all("data/csv_loader")?;
+    let mut text = "a,b,target\n".to_string();
+    for i in 0..40 {
+        let (a, b) = ((i % 8) as f64 / 4.0, (i % 5) as f64 / 2.0);
+        text.push_str(&format!("{},{},{}\n", a, b, 3.0 * a - 2.0 * b + 1.0));
+    }
+    fs::write("data/csv_loader/rows.csv", text)?;
+
+    let dataset = CsvDataset::new(
+        "data/csv_loader/rows.csv",
+        &["a", "b"],
+        &["target"],
+        CsvOptions::default()
+    ).unwrap();
+
+    let options = SplitOptions { test: 0.2, validation: 0.1, seed: Some(42), stratified: false };
+    let splits = split(&dataset, options).unwrap();
+    assert_eq!((splits.train.len(), splits.test.len(), splits.validation.len()), (28, 8, 4));
+
+    let all_val: Vec<usize> = (0..splits.validation.len()).collect();
+    let val = splits.validation.rows(&all_val).unwrap();
+    let mut validation = Validation::new(&val.x, &val.y).unwrap();
+    validation.add_metric("mae", mean_absolute_error);
+
+    // the model is created from one batch, rows are read from disk while training
+    let init = splits.train.rows(&[0]).unwrap();
+    let mut model = SGD::new(&init.x, &init.y, 0.01).unwrap();
+
+    let loader_options = LoaderOptions { batch_size: 4, seed: Some(7), ..LoaderOptions::default() };
+    let mut loader = DataLoader::new(&splits.train, loader_options).unwrap();
+    assert_eq!(loader.n_batches(), 7);
+    model.train_loader_validate(&mut loader, 300, &mut validation).unwrap();
+
+    let history = validation.history();
+    assert_eq!(history.len(), 300);
+    assert!(history[299].validation_loss < history[0].validation_loss);
+
+    let all_test: Vec<usize> = (0..splits.test.len()).collect();
+    let test = splits.test.rows(&all_test).unwrap();
+    let predicted = model.predict(&test.x);
+    assert!(mean_absolute_error(&test.y, &predicted) < 0.1);
+
+    let mut optimized = SGD::new(&init.x, &init.y, 0.01).unwrap();
+    let mut adam = Adam::default(&optimized);
+    optimized.train_loader_with_optimizer(&mut loader, 50, &mut adam).unwrap();
+    assert!(optimized.loss().is_finite());
+
+    // fractions of the model data can't be held out when training from a loader
+    let mut fraction = Validation::split(0.2, 1).unwrap();
+    assert!(optimized.train_loader_validate(&mut loader, 1, &mut fraction).is_err());
+
+    fs::remove_dir_all("data/csv_loader")?;
+    Ok(())
+}